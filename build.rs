@@ -0,0 +1,12 @@
+use std::process::Command;
+
+fn main() {
+    // the version of the compiler, for logging::log_startup_info
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc).arg("--version").output().ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=LOGGING_RUSTC_VERSION={}", version);
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
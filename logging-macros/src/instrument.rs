@@ -0,0 +1,136 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{parse::{Parse, Parser}, Expr, FnArg, GenericArgument, Ident, ItemFn, Pat, PathArguments, ReturnType, Token, Type};
+
+#[derive(Default)]
+struct Args {
+    level: Option<Expr>,
+    logger: Option<Expr>,
+    skip: Vec<Ident>,
+    skip_ret: bool,
+}
+impl Args {
+    fn parse(tokens: TokenStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("level") {
+                args.level = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("logger") {
+                args.logger = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("skip") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                args.skip.extend(content.parse_terminated(Ident::parse, Token![,])?);
+            } else if meta.path.is_ident("skip_ret") {
+                args.skip_ret = true;
+            } else {
+                return Err(meta.error("expected `level`, `logger`, `skip` or `skip_ret`"));
+            }
+            Ok(())
+        });
+        parser.parse2(tokens)?;
+        Ok(args)
+    }
+}
+
+pub(crate) fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let args = Args::parse(args)?;
+    let ItemFn { attrs, vis, sig, block } = syn::parse2(item)?;
+    let name = sig.ident.to_string();
+
+    let level = args.level.map(ToTokens::into_token_stream)
+        .unwrap_or_else(|| quote!(::logging::Level::DEBUG));
+    let logger = args.logger.map(|logger| quote!(::logging::Logger::new(#logger)))
+        .unwrap_or_else(|| quote!(::logging::__macro_logger(module_path!())));
+
+    let logged_args: Vec<&Ident> = sig.inputs.iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(arg) => match arg.pat.as_ref() {
+                Pat::Ident(pat) => Some(&pat.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .filter(|ident| !args.skip.contains(ident))
+        .collect();
+    let entry_format = format!(
+        "-> {}({})",
+        name,
+        logged_args.iter().map(|ident| format!("{} = {{:?}}", ident)).collect::<Vec<String>>().join(", "),
+    );
+
+    // `impl Trait` can't be the type of a variable, so the type is left to inference then
+    let result_type = match &sig.output {
+        ReturnType::Type(_, ty) if !contains_impl_trait(ty) => quote!(: #ty),
+        _ => quote!(),
+    };
+    let call = if sig.asyncness.is_some() {
+        quote!(async move #block.await)
+    } else {
+        quote!((|| #block)())
+    };
+    let exit_message = if args.skip_ret {
+        let exit_format = format!("<- {} ({{:?}})", name);
+        quote!(format!(#exit_format, __elapsed))
+    } else {
+        let exit_format = format!("<- {} = {{:?}} ({{:?}})", name);
+        quote!(format!(#exit_format, __result, __elapsed))
+    };
+    let exit = quote! {
+        if __enabled {
+            let __elapsed = __start.elapsed();
+            __logger.log_fields(#exit_message, __level, ::std::vec![
+                (::std::string::String::from("span"), ::logging::FieldValue::from(#name)),
+                (::std::string::String::from("duration_us"), ::logging::FieldValue::from(__elapsed.as_micros())),
+            ]);
+        }
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            let __logger = #logger;
+            let __level = #level;
+            // the arguments and the result are only formatted if the records are logged
+            let __enabled = __level >= __logger.level();
+            if __enabled {
+                __logger.log(format!(#entry_format, #(#logged_args),*), __level);
+            }
+            let __start = ::std::time::Instant::now();
+            #[allow(clippy::redundant_closure_call)]
+            let __result #result_type = #call;
+            #exit
+            __result
+        }
+    })
+}
+
+/// Whether a type mentions `impl Trait` anywhere, e.g. `Option<impl Iterator<Item = u8>>`.
+fn contains_impl_trait(ty: &Type) -> bool {
+    match ty {
+        Type::ImplTrait(_) => true,
+        Type::Array(array) => contains_impl_trait(&array.elem),
+        Type::Group(group) => contains_impl_trait(&group.elem),
+        Type::Paren(paren) => contains_impl_trait(&paren.elem),
+        Type::Ptr(ptr) => contains_impl_trait(&ptr.elem),
+        Type::Reference(reference) => contains_impl_trait(&reference.elem),
+        Type::Slice(slice) => contains_impl_trait(&slice.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().any(contains_impl_trait),
+        Type::Path(path) => {
+            path.qself.as_ref().is_some_and(|qself| contains_impl_trait(&qself.ty))
+                || path.path.segments.iter().any(|segment| match &segment.arguments {
+                    PathArguments::AngleBracketed(arguments) => arguments.args.iter().any(|argument| match argument {
+                        GenericArgument::Type(ty) => contains_impl_trait(ty),
+                        GenericArgument::AssocType(assoc) => contains_impl_trait(&assoc.ty),
+                        _ => false,
+                    }),
+                    PathArguments::Parenthesized(arguments) => {
+                        arguments.inputs.iter().any(contains_impl_trait)
+                            || matches!(&arguments.output, ReturnType::Type(_, ty) if contains_impl_trait(ty))
+                    }
+                    PathArguments::None => false,
+                })
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,90 @@
+//! Attribute macros for the `logging` crate. Use them through `logging` with the `macros` feature enabled.
+
+mod instrument;
+mod test;
+
+use proc_macro::TokenStream;
+
+/// Log entry to and exit from a function.
+///
+/// On entry, the function name and its arguments (formatted with [Debug](std::fmt::Debug)) are logged.
+/// On exit, the duration of the call and the return value (also formatted with [Debug](std::fmt::Debug)) are logged.
+/// The exit record has the function name as `span` field and the duration in microseconds as `duration_us` field,
+/// so it can be written as a span by the `ChromeTraceHandler`.
+/// Works for `async fn`s as well, in which case the duration includes the time spent waiting.
+/// Nothing is formatted if the logger doesn't log at the level.
+///
+/// # Arguments
+///
+/// * `level`: The level to log at. Defaults to [Level::DEBUG](../logging/Level/constant.DEBUG.html).
+/// * `logger`: The name of the logger to log with. Defaults to the module path, like the logging macros.
+/// * `skip(..)`: Arguments that should not be logged, e.g. because they don't implement [Debug](std::fmt::Debug). `self` is never logged.
+/// * `skip_ret`: Don't log the return value.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, instrument};
+///
+/// #[instrument(level = Level::INFO, logger = "app::math", skip(verbose))]
+/// fn add(a: i32, b: i32, verbose: bool) -> i32 {
+///     a + b
+/// }
+///
+/// logging::set_level(Level::ALL);
+/// // logs "-> add(a = 1, b = 2)" and "<- add = 3 (...)"
+/// assert_eq!(add(1, 2, true), 3);
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Simple(u32);
+///
+/// // `?` converts errors to the declared return type, and `impl Trait` in it works as well
+/// #[instrument]
+/// fn parse(text: &str) -> Result<Simple, String> {
+///     Ok(Simple(text.parse().map_err(|_| format!("{} is not a number", text))?))
+/// }
+/// #[instrument]
+/// fn evens(to: u32) -> Option<impl Iterator<Item = u32> + std::fmt::Debug> {
+///     Some((0..to).step_by(2))
+/// }
+///
+/// assert_eq!(parse("7"), Ok(Simple(7)));
+/// assert_eq!(evens(5).unwrap().count(), 3);
+/// ```
+#[proc_macro_attribute]
+pub fn instrument(args: TokenStream, item: TokenStream) -> TokenStream {
+    instrument::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Run a test with isolated logging: records logged on the test's thread are captured for this test only and printed if the test fails,
+/// and all loggers use the given level on that thread, without affecting tests running in parallel (see `logging::testing`).
+/// The previous state is restored when the test ends.
+///
+/// On an `async fn`, the attribute of the runtime (e.g. `#[tokio::test]`) has to follow it and turns the function into a test.
+///
+/// # Arguments
+///
+/// * `level`: The level all loggers use during the test. Defaults to [Level::ALL](../logging/Level/constant.ALL.html).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{testing, Level, Logger};
+///
+/// #[logging::test(level = Level::INFO)]
+/// fn logs_the_connection() {
+///     Logger::new("db").debug("resolving host");
+///     Logger::new("db").info("connected");
+///     let records = testing::captured();
+///     assert_eq!(records.len(), 1);
+///     assert_eq!(records[0].message, "connected");
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn test(args: TokenStream, item: TokenStream) -> TokenStream {
+    test::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
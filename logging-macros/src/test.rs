@@ -0,0 +1,30 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{parse::Parser, Expr, ItemFn};
+
+pub(crate) fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut level = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("level") {
+            level = Some(meta.value()?.parse::<Expr>()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `level`"))
+        }
+    });
+    parser.parse2(args)?;
+    let level = level.map(ToTokens::into_token_stream)
+        .unwrap_or_else(|| quote!(::logging::Level::ALL));
+
+    let ItemFn { attrs, vis, sig, block } = syn::parse2(item)?;
+    // async tests are turned into tests by the attribute of their runtime, e.g. #[tokio::test]
+    let test = if sig.asyncness.is_some() { quote!() } else { quote!(#[test]) };
+    Ok(quote! {
+        #test
+        #(#attrs)*
+        #vis #sig {
+            let __logging_capture = ::logging::testing::capture(#level);
+            #block
+        }
+    })
+}
@@ -1,6 +1,7 @@
 //! All the different logging levels.
 
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::sync::{OnceLock, RwLock};
 use crate::LogLevel;
 /// The minimum log level
@@ -50,4 +51,76 @@ pub fn add_level(level: LogLevel, name: String) {
 pub fn get_level(level: LogLevel) -> Option<String> {
     let lock = _get_log_levels().read().expect("Log levels are poisoned");
     lock.get(&level).map(|name| name.to_string())
-}
\ No newline at end of file
+}
+/// All registered levels with their names, sorted from lowest to highest.
+pub fn get_levels() -> Vec<(LogLevel, String)> {
+    let lock = _get_log_levels().read().expect("Log levels are poisoned");
+    let mut levels: Vec<(LogLevel, String)> = lock.iter()
+        .map(|(level, name)| (*level, name.to_string()))
+        .collect();
+    levels.sort();
+    levels
+}
+/// The error for a level name that isn't registered, e.g. from [Logger::log_named](crate::Logger::log_named).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownLevel(pub String);
+impl Display for UnknownLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown level '{}'", self.0)
+    }
+}
+impl std::error::Error for UnknownLevel {}
+/// Look up a registered level by its name. Names are compared case-insensitively.
+///
+/// # Arguments
+///
+/// * `name`: The name of the level, e.g. `warn`.
+///
+/// returns: Option<LogLevel>
+///
+/// # Examples
+///
+/// ```
+/// use logging::Level;
+/// assert_eq!(Level::by_name("warn"), Some(Level::WARN));
+/// Level::add_level(35, "NOTICE".to_string());
+/// assert_eq!(Level::by_name("Notice"), Some(35));
+/// assert_eq!(Level::by_name("verbose"), None);
+/// ```
+pub fn by_name(name: &str) -> Option<LogLevel> {
+    let lock = _get_log_levels().read().expect("Log levels are poisoned");
+    lock.iter()
+        .filter(|(_, level_name)| level_name.eq_ignore_ascii_case(name))
+        .map(|(level, _)| *level)
+        .min()
+}
+/// The name of a level for display. Levels without a registered name are shown relative to the nearest registered level,
+/// e.g. `ERROR+5` for 45. If two levels are equally near, the lower one is used.
+///
+/// # Arguments
+///
+/// * `level`: The level to name.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// use logging::Level;
+/// assert_eq!(Level::name(Level::WARN), "WARN");
+/// assert_eq!(Level::name(45), "ERROR+5");
+/// assert_eq!(Level::name(28), "WARN-2");
+/// ```
+pub fn name(level: LogLevel) -> String {
+    let lock = _get_log_levels().read().expect("Log levels are poisoned");
+    if let Some(name) = lock.get(&level) {
+        return name.to_string();
+    }
+    let nearest = lock.iter()
+        .min_by_key(|(known, _)| ((level as i64 - **known as i64).abs(), **known));
+    match nearest {
+        Some((known, name)) if level > *known => format!("{}+{}", name, level as i64 - *known as i64),
+        Some((known, name)) => format!("{}-{}", name, *known as i64 - level as i64),
+        None => level.to_string(),
+    }
+}
@@ -0,0 +1,208 @@
+//! A small admin interface to inspect and change logger levels of a running process.
+//!
+//! Commands are plain text, one per line:
+//! * `list`: all loggers with their current level.
+//! * `levels`: all registered levels.
+//! * `get <logger>`: the level of a single logger.
+//! * `set <logger> <level>`: set the level of a logger and all of its children.
+//!
+//! The root logger is addressed as `root`. Levels can be given by name (case-insensitive) or number.
+//! Every response ends with a line that is either `ok` or `error: <reason>`.
+//!
+//! On Unix, [AdminServer](AdminServer) serves these commands over a Unix domain socket,
+//! which the `logctl` binary (or anything like `socat`) can talk to.
+
+use crate::{Level, LogLevel, Logger};
+
+/// The name the root logger is addressed by.
+pub const ROOT_NAME: &str = "root";
+
+/// Execute a single admin command.
+/// Can be used to hook the admin commands into an existing interface (e.g. an HTTP server).
+///
+/// # Arguments
+///
+/// * `command`: The command line, e.g. `set foo::db debug`.
+///
+/// returns: Result<String, String> The output of the command or the reason it failed.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// let logger = Logger::new("foo::db");
+/// logging::admin::execute("set foo::db debug").unwrap();
+/// assert_eq!(logger.level(), Level::DEBUG);
+/// assert_eq!(logging::admin::execute("get foo::db").unwrap(), "DEBUG");
+/// assert!(logging::admin::execute("set foo::db loud").is_err());
+/// ```
+pub fn execute(command: &str) -> Result<String, String> {
+    let args: Vec<&str> = command.split_whitespace().collect();
+    match args.as_slice() {
+        ["list"] => Ok(crate::loggers().iter()
+            .map(|logger| format!("{} {}", display_name(&logger.name()), level_name(logger.level())))
+            .collect::<Vec<String>>()
+            .join("\n")),
+        ["levels"] => Ok(Level::get_levels().iter()
+            .map(|(level, name)| format!("{name} {level}"))
+            .collect::<Vec<String>>()
+            .join("\n")),
+        ["get", name] => {
+            let logger = crate::find_logger(logger_name(name))
+                .ok_or_else(|| format!("unknown logger '{name}'"))?;
+            Ok(level_name(logger.level()))
+        }
+        ["set", name, level] => {
+            let level = parse_level(level).ok_or_else(|| format!("unknown level '{level}'"))?;
+            Logger::new(logger_name(name)).set_level(level);
+            Ok(String::new())
+        }
+        [] => Err("empty command".to_string()),
+        [command, ..] => Err(format!("invalid command '{command}'")),
+    }
+}
+fn logger_name(name: &str) -> &str {
+    if name == ROOT_NAME { "" } else { name }
+}
+fn display_name(name: &str) -> &str {
+    if name.is_empty() { ROOT_NAME } else { name }
+}
+fn level_name(level: LogLevel) -> String {
+    Level::get_level(level).unwrap_or(level.to_string())
+}
+fn parse_level(level: &str) -> Option<LogLevel> {
+    if let Ok(level) = level.parse() {
+        return Some(level);
+    }
+    Level::by_name(level)
+}
+
+#[cfg(unix)]
+pub use unix::{request, AdminServer};
+
+#[cfg(unix)]
+mod unix {
+    use std::io::{self, BufRead, BufReader, Read, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+
+    /// Serves the [admin commands](super) on a Unix domain socket.
+    /// The server stops and removes the socket file when dropped.
+    pub struct AdminServer {
+        path: PathBuf,
+        stop: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    }
+    impl AdminServer {
+        /// Start serving on a socket at the given path.
+        /// A stale socket file left behind by a previous process is replaced.
+        ///
+        /// # Arguments
+        ///
+        /// * `path`: Where to create the socket.
+        ///
+        /// returns: Result<AdminServer, Error>
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use logging::{Level, Logger};
+        /// let path = std::env::temp_dir().join(format!("logging-admin-{}.sock", std::process::id()));
+        /// let server = logging::admin::AdminServer::bind(&path).unwrap();
+        /// Logger::new("foo::db");
+        ///
+        /// logging::admin::request(&path, "set foo::db debug").unwrap();
+        /// assert_eq!(Logger::new("foo::db").level(), Level::DEBUG);
+        /// assert!(logging::admin::request(&path, "list").unwrap().contains("foo::db DEBUG"));
+        /// drop(server);
+        /// assert!(!path.exists());
+        /// ```
+        pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+            let path = path.as_ref().to_path_buf();
+            let listener = match UnixListener::bind(&path) {
+                Err(err) if err.kind() == io::ErrorKind::AddrInUse && UnixStream::connect(&path).is_err() => {
+                    std::fs::remove_file(&path)?;
+                    UnixListener::bind(&path)?
+                }
+                result => result?,
+            };
+            let stop = Arc::new(AtomicBool::new(false));
+            let thread = {
+                let stop = Arc::clone(&stop);
+                thread::Builder::new()
+                    .name("logging-admin".to_string())
+                    .spawn(move || {
+                        for stream in listener.incoming() {
+                            if stop.load(Ordering::SeqCst) {
+                                break;
+                            }
+                            if let Ok(stream) = stream {
+                                thread::spawn(move || serve(stream));
+                            }
+                        }
+                    })?
+            };
+            Ok(Self { path, stop, thread: Some(thread) })
+        }
+        /// The path of the socket.
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+    impl Drop for AdminServer {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::SeqCst);
+            // wake up the listener so it notices it has been stopped
+            let _ = UnixStream::connect(&self.path);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+    fn serve(stream: UnixStream) {
+        let Ok(mut writer) = stream.try_clone() else { return };
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { return };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match super::execute(&line) {
+                Ok(output) if output.is_empty() => "ok\n".to_string(),
+                Ok(output) => format!("{output}\nok\n"),
+                Err(err) => format!("error: {err}\n"),
+            };
+            if writer.write_all(response.as_bytes()).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Send a single command to an [AdminServer](AdminServer) and wait for the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path of the server's socket.
+    /// * `command`: The command to execute.
+    ///
+    /// returns: Result<String, Error> The output of the command. Errors reported by the server are returned as [io::ErrorKind::Other].
+    pub fn request(path: impl AsRef<Path>, command: &str) -> io::Result<String> {
+        let mut stream = UnixStream::connect(path)?;
+        stream.write_all(format!("{}\n", command.trim()).as_bytes())?;
+        stream.shutdown(std::net::Shutdown::Write)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        let mut lines: Vec<&str> = response.lines().collect();
+        match lines.pop() {
+            Some("ok") => Ok(lines.join("\n")),
+            Some(status) => match status.strip_prefix("error: ") {
+                Some(err) => Err(io::Error::other(err.to_string())),
+                None => Err(io::Error::new(io::ErrorKind::InvalidData, "malformed response")),
+            },
+            None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "no response")),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::formatter::format_time;
+use crate::{Handler, HandlerConfig, HandlerHealth, LogLevel, Record};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The records of one (logger, level, template) seen in the current window.
+struct Group {
+    count: u64,
+    first: SystemTime,
+    last: SystemTime,
+}
+
+struct Window {
+    end: Instant,
+    length: Duration,
+    groups: HashMap<(String, LogLevel, String), Group>,
+    /// Whether the thread ending the windows has been started.
+    timer: bool,
+}
+
+/// Instead of passing on every record, counts them and passes on one summary per logger, level and message template
+/// at the end of every window, for noisy repetitive events like retries or dropped packets.
+///
+/// The template of a message is its [template](Record::template) if it was logged with one, otherwise the message with every number replaced by `{}`,
+/// so `retry 3 of 5` and `retry 4 of 5` are counted together.
+/// A summary is a record of the same logger and level with the template as message and the fields `count`, `first` and `last`
+/// (the times of the first and last record, in RFC 3339 format).
+///
+/// A background thread, started with the first record, ends every window on time, so a burst followed by silence is summarized as well.
+/// Windows also end when the summaries are [flushed](AggregatingHandler::flush) (which [close](Handler::close) does as well).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{AggregatingHandler, FieldValue, Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let aggregating = AggregatingHandler::new(Arc::clone(&memory)).with_window(Duration::from_secs(60));
+/// let logger = Logger::new("net");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(aggregating.clone());
+/// for port in 8000..8100 {
+///     logger.warn(format!("connection to port {} refused", port));
+/// }
+/// assert!(memory.records().is_empty());
+///
+/// aggregating.flush();
+/// let records = memory.records();
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0].message, "connection to port {} refused");
+/// assert_eq!(records[0].field("count"), Some(&FieldValue::Int(100)));
+///
+/// // summaries are passed on at the end of every window, even without further records
+/// logger.add_handler(AggregatingHandler::new(Arc::clone(&memory)).with_window(Duration::from_millis(50)));
+/// logger.warn("connection to port 9000 refused");
+/// std::thread::sleep(Duration::from_millis(500));
+/// assert_eq!(memory.records().len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct AggregatingHandler {
+    inner: Arc<dyn Handler>,
+    current: Arc<Mutex<Window>>,
+}
+impl AggregatingHandler {
+    /// Wrap a handler, summarizing records every minute.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: The handler receiving the summaries.
+    ///
+    /// returns: AggregatingHandler
+    pub fn new<T: Handler + 'static>(inner: T) -> Self {
+        let window = Duration::from_secs(60);
+        Self {
+            inner: Arc::new(inner),
+            current: Arc::new(Mutex::new(Window { end: Instant::now() + window, length: window, groups: HashMap::new(), timer: false })),
+        }
+    }
+    /// Set how long records are counted before their summaries are passed on.
+    pub fn with_window(self, window: Duration) -> Self {
+        let mut current = self.current.lock().expect("Aggregation window is poisoned");
+        current.length = window;
+        current.end = Instant::now() + window;
+        drop(current);
+        self
+    }
+    /// Pass on the summaries of the current window right away and start a new window.
+    ///
+    /// returns: ()
+    pub fn flush(&self) {
+        flush(self.inner.as_ref(), &self.current)
+    }
+    /// Start the thread ending the windows. It stops once the handler is gone.
+    fn start_timer(&self) {
+        let current = Arc::downgrade(&self.current);
+        let inner = Arc::clone(&self.inner);
+        std::thread::Builder::new()
+            .name("logging-aggregate".to_string())
+            .spawn(move || {
+                while let Some(current) = current.upgrade() {
+                    let wait = current.lock().expect("Aggregation window is poisoned").end.saturating_duration_since(Instant::now());
+                    if wait.is_zero() {
+                        flush(inner.as_ref(), &current);
+                        continue;
+                    }
+                    drop(current);
+                    std::thread::sleep(wait);
+                }
+            })
+            .expect("failed to spawn the aggregation thread");
+    }
+}
+/// Pass on the summaries of the current window and start a new window.
+fn flush(inner: &dyn Handler, current: &Mutex<Window>) {
+    let groups = {
+        let mut current = current.lock().expect("Aggregation window is poisoned");
+        current.end = Instant::now() + current.length;
+        std::mem::take(&mut current.groups)
+    };
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, group)| group.first);
+    for ((logger, level, template), group) in groups {
+        let mut summary = Record::new(level, template, logger);
+        summary.time = group.last;
+        summary.fields = vec![
+            ("count".to_string(), group.count.into()),
+            ("first".to_string(), format_time(group.first).into()),
+            ("last".to_string(), format_time(group.last).into()),
+        ];
+        inner.handle(&summary);
+    }
+}
+impl Handler for AggregatingHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let (ended, start_timer) = {
+            let mut current = self.current.lock().expect("Aggregation window is poisoned");
+            let ended = Instant::now() >= current.end;
+            let start_timer = !std::mem::replace(&mut current.timer, true);
+            let group = current.groups.entry((record.logger.clone(), record.level, record.template.clone().unwrap_or_else(|| template(&record.message))))
+                .or_insert(Group { count: 0, first: record.time, last: record.time });
+            group.count += 1;
+            group.last = group.last.max(record.time);
+            (ended, start_timer)
+        };
+        if start_timer {
+            self.start_timer();
+        }
+        if ended {
+            self.flush();
+        }
+    }
+    fn reopen(&self) {
+        self.inner.reopen()
+    }
+    fn close(&self) {
+        self.flush();
+        self.inner.close()
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        self.inner.accepts(record)
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("AggregatingHandler")
+            .with_setting("window", format!("{:?}", self.current.lock().expect("Aggregation window is poisoned").length))
+            .with_inner(self.inner.describe())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        self.inner.health()
+    }
+}
+
+/// The message with every number replaced by `{}`. Digits that are part of a word, like in `utf8`, are kept.
+fn template(message: &str) -> String {
+    let mut template = String::with_capacity(message.len());
+    let mut previous = ' ';
+    let mut in_number = false;
+    for character in message.chars() {
+        if character.is_ascii_digit() && (in_number || !(previous.is_alphanumeric() || previous == '_')) {
+            if !in_number {
+                template.push_str("{}");
+            }
+            in_number = true;
+        } else {
+            template.push(character);
+            in_number = false;
+        }
+        previous = character;
+    }
+    template
+}
@@ -0,0 +1,80 @@
+//! Logging of [anyhow](https://docs.rs/anyhow) and [eyre](https://docs.rs/eyre) errors.
+
+use crate::{format_error_chain, Logger};
+#[cfg(feature = "anyhow")]
+use std::backtrace::BacktraceStatus;
+
+/// Format an [anyhow::Error](anyhow::Error) with its context chain (see [format_error_chain](format_error_chain)) and, if one was captured, its backtrace.
+///
+/// # Arguments
+///
+/// * `err`: The error to format.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Context;
+/// let err = std::fs::read_to_string("/does/not/exist")
+///     .context("loading config")
+///     .unwrap_err();
+/// assert!(logging::format_anyhow(&err).starts_with("loading config\n  caused by: "));
+/// ```
+#[cfg(feature = "anyhow")]
+pub fn format_anyhow(err: &anyhow::Error) -> String {
+    let chain = format_error_chain(err.as_ref());
+    match err.backtrace().status() {
+        BacktraceStatus::Captured => format!("{}\n  backtrace:\n{}", chain, err.backtrace()),
+        _ => chain,
+    }
+}
+/// Format an [eyre::Report](eyre::Report) with its context chain (see [format_error_chain](format_error_chain)).
+///
+/// # Arguments
+///
+/// * `err`: The report to format.
+///
+/// returns: String
+#[cfg(feature = "eyre")]
+pub fn format_eyre(err: &eyre::Report) -> String {
+    format_error_chain(err.as_ref())
+}
+
+impl Logger {
+    /// Log an [anyhow::Error](anyhow::Error) with its context chain and backtrace at [Level::ERROR](crate::Level::ERROR) (see [format_anyhow](format_anyhow)).
+    ///
+    /// # Arguments
+    ///
+    /// * `err`: The error to be logged.
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use anyhow::Context;
+    /// use logging::Level;
+    /// let logger = logging::Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// let result = std::fs::read_to_string("/does/not/exist").context("loading config");
+    /// if let Err(err) = result {
+    ///     logger.error_anyhow(&err);
+    /// }
+    /// ```
+    #[cfg(feature = "anyhow")]
+    pub fn error_anyhow(&self, err: &anyhow::Error) {
+        self.error(format_anyhow(err))
+    }
+    /// Log an [eyre::Report](eyre::Report) with its context chain at [Level::ERROR](crate::Level::ERROR) (see [format_eyre](format_eyre)).
+    ///
+    /// # Arguments
+    ///
+    /// * `err`: The report to be logged.
+    ///
+    /// returns: ()
+    #[cfg(feature = "eyre")]
+    pub fn error_eyre(&self, err: &eyre::Report) {
+        self.error(format_eyre(err))
+    }
+}
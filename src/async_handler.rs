@@ -0,0 +1,365 @@
+use crate::stats::HandlerId;
+use crate::{Handler, HandlerConfig, HandlerHealth, LogLevel, Record};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{JoinHandle, Thread};
+
+/// A handler sending records somewhere asynchronously, e.g. an exporter posting them to a collector over HTTP or gRPC.
+/// Wrap it in an [AsyncAdapter](AsyncAdapter) to add it to a logger, which handles the records on a background worker,
+/// so neither the logging thread nor a thread per request is blocked while a record is sent.
+///
+/// The futures are run on the worker thread only, so they don't need to be `Send`.
+#[allow(async_fn_in_trait)]
+pub trait AsyncHandler: Send + Sync + 'static {
+    /// Handle a record. Up to the [concurrency](AsyncAdapter::with_concurrency) of the adapter, records are handled at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: The record to log.
+    ///
+    /// returns: ()
+    async fn log(&self, record: Record);
+    /// Finish up, e.g. send records still buffered. Called when the adapter is closed, after all queued records have been handled.
+    ///
+    /// returns: ()
+    async fn close(&self) {}
+    /// Describe the handler for [export_config](crate::export_config). By default, only the name of its type is given.
+    ///
+    /// returns: HandlerConfig
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new(crate::config::short_type_name::<Self>())
+    }
+}
+
+/// The async runtime the worker of an [AsyncAdapter](AsyncAdapter) runs the [AsyncHandler](AsyncHandler) on.
+/// Handlers using the I/O or timers of a runtime (e.g. an HTTP client built for tokio) need to run on that runtime.
+///
+/// Every worker runs its own single-threaded instance of the runtime on its own thread, so the host application may use any runtime (or none).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsyncRuntime {
+    /// A minimal executor bundled with this crate, for handlers that don't depend on a runtime.
+    Thread,
+    /// A current-thread tokio runtime with all drivers enabled (with the `tokio` feature).
+    #[cfg(feature = "tokio")]
+    Tokio,
+    /// The async-std runtime (with the `async-std` feature).
+    #[cfg(feature = "async-std")]
+    AsyncStd,
+    /// The smol runtime (with the `smol` feature).
+    #[cfg(feature = "smol")]
+    Smol,
+}
+impl Default for AsyncRuntime {
+    /// [Tokio](AsyncRuntime::Tokio) with the `tokio` feature, otherwise [Thread](AsyncRuntime::Thread).
+    fn default() -> Self {
+        #[cfg(feature = "tokio")]
+        return Self::Tokio;
+        #[cfg(not(feature = "tokio"))]
+        return Self::Thread;
+    }
+}
+impl AsyncRuntime {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Thread => "thread",
+            #[cfg(feature = "tokio")]
+            Self::Tokio => "tokio",
+            #[cfg(feature = "async-std")]
+            Self::AsyncStd => "async-std",
+            #[cfg(feature = "smol")]
+            Self::Smol => "smol",
+        }
+    }
+    /// Run a future to completion on the current thread, counting a runtime that fails to start as a failure of the handler.
+    #[cfg_attr(not(feature = "tokio"), allow(unused_variables))]
+    fn block_on(self, future: impl Future<Output = ()>, id: HandlerId, handler: &HandlerConfig) {
+        match self {
+            Self::Thread => block_on(future),
+            #[cfg(feature = "tokio")]
+            Self::Tokio => match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(future),
+                Err(error) => id.record_failure(handler, error),
+            },
+            #[cfg(feature = "async-std")]
+            Self::AsyncStd => async_std::task::block_on(future),
+            #[cfg(feature = "smol")]
+            Self::Smol => smol::block_on(future),
+        }
+    }
+}
+
+/// Wakes the thread blocked in [block_on](block_on).
+struct Unpark(Thread);
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+/// The bundled executor: poll the future whenever it is woken, parking the thread in between.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
+
+enum Message {
+    Record(Box<Record>),
+    /// Acknowledged once all records sent before have been handled.
+    Flush(std_mpsc::Sender<()>),
+}
+
+/// The messages for a worker, shared between the adapter and the worker.
+#[derive(Default)]
+struct Queue {
+    messages: VecDeque<Message>,
+    /// Set when the worker should stop once all messages are handled.
+    closed: bool,
+    waker: Option<Waker>,
+}
+impl Queue {
+    fn push(&mut self, message: Message) {
+        self.messages.push_back(message);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+    fn close(&mut self) {
+        self.closed = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct Worker {
+    queue: Arc<Mutex<Queue>>,
+    thread: Option<JoinHandle<()>>,
+}
+impl Worker {
+    /// Stop the worker once all queued records are handled and wait for it.
+    fn stop(mut self) {
+        self.queue.lock().expect("Async adapter queue is poisoned").close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // the last adapter is gone, so nothing can be logged to the worker anymore
+        self.queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).close();
+    }
+}
+
+/// Runs an [AsyncHandler](AsyncHandler) as a [Handler](Handler): records are queued and handled by a background thread
+/// running an [async runtime](AsyncRuntime), several at a time.
+///
+/// The worker is started with the first record. When the queue is full, records are dropped (and counted in [stats](crate::stats)).
+/// [close](Handler::close) (e.g. by [shutdown](crate::shutdown)) waits until all queued records are handled and stops the worker;
+/// records logged afterward start a new one.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{AsyncAdapter, AsyncHandler, AsyncRuntime, Handler, Level, Logger, Record};
+/// use std::sync::Mutex;
+///
+/// #[derive(Default)]
+/// struct Collector(Mutex<Vec<String>>);
+/// impl AsyncHandler for Collector {
+///     async fn log(&self, record: Record) {
+///         // e.g. an HTTP request
+///         self.0.lock().unwrap().push(record.message);
+///     }
+/// }
+///
+/// let adapter = AsyncAdapter::new(Collector::default())
+///     .with_runtime(AsyncRuntime::Thread)
+///     .with_concurrency(4);
+/// let logger = Logger::new("exporter");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(adapter.clone());
+/// logger.info("first");
+/// logger.info("second");
+///
+/// adapter.flush();
+/// let mut sent = adapter.handler().0.lock().unwrap().clone();
+/// sent.sort();
+/// assert_eq!(sent, ["first", "second"]);
+/// ```
+pub struct AsyncAdapter<H: AsyncHandler> {
+    handler: Arc<H>,
+    capacity: usize,
+    concurrency: usize,
+    runtime: AsyncRuntime,
+    worker: Arc<Mutex<Option<Worker>>>,
+    id: HandlerId,
+}
+impl<H: AsyncHandler> Clone for AsyncAdapter<H> {
+    fn clone(&self) -> Self {
+        Self {
+            handler: Arc::clone(&self.handler),
+            capacity: self.capacity,
+            concurrency: self.concurrency,
+            runtime: self.runtime,
+            worker: Arc::clone(&self.worker),
+            id: self.id,
+        }
+    }
+}
+impl<H: AsyncHandler> AsyncAdapter<H> {
+    /// Wrap an async handler. By default, up to 10000 records are queued, up to 16 are handled at the same time,
+    /// and the [default runtime](AsyncRuntime::default) is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler`: The handler to run.
+    ///
+    /// returns: AsyncAdapter<H>
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            capacity: 10_000,
+            concurrency: 16,
+            runtime: AsyncRuntime::default(),
+            worker: Default::default(),
+            id: HandlerId::new(),
+        }
+    }
+    /// Set how many records may wait to be handled before further records are dropped.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+    /// Set how many records are handled at the same time.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+    /// Set the runtime the handler runs on.
+    pub fn with_runtime(mut self, runtime: AsyncRuntime) -> Self {
+        self.runtime = runtime;
+        self
+    }
+    /// The wrapped handler.
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+    /// Block until all records logged so far have been handled.
+    ///
+    /// returns: ()
+    pub fn flush(&self) {
+        let (ack, acked) = std_mpsc::channel();
+        match &*self.worker.lock().expect("Async adapter is poisoned") {
+            Some(worker) => worker.queue.lock().expect("Async adapter queue is poisoned").push(Message::Flush(ack)),
+            None => return,
+        }
+        let _ = acked.recv();
+    }
+    fn start(&self) -> Worker {
+        let queue: Arc<Mutex<Queue>> = Default::default();
+        let handler = Arc::clone(&self.handler);
+        let concurrency = self.concurrency;
+        let runtime = self.runtime;
+        let (id, description) = (self.id, self.describe());
+        let thread = std::thread::Builder::new()
+            .name("logging-async".to_string())
+            .spawn({
+                let queue = Arc::clone(&queue);
+                move || {
+                    runtime.block_on(
+                        async {
+                            run(&*handler, &queue, concurrency, id, &description).await;
+                            handler.close().await;
+                        },
+                        id,
+                        &description,
+                    );
+                    // only left over if the runtime failed to start, dropping them releases waiting flushes
+                    queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).messages.clear();
+                }
+            })
+            .expect("failed to spawn the async logging thread");
+        Worker { queue, thread: Some(thread) }
+    }
+}
+/// Handle the queued records until the queue is closed and empty, polling up to `concurrency` of them at the same time.
+async fn run<H: AsyncHandler>(handler: &H, queue: &Mutex<Queue>, concurrency: usize, id: HandlerId, description: &HandlerConfig) {
+    let mut running: Vec<Pin<Box<dyn Future<Output = ()> + '_>>> = Vec::new();
+    let mut flushing: Option<std_mpsc::Sender<()>> = None;
+    std::future::poll_fn(|context| loop {
+        running.retain_mut(|future| match std::panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(context))) {
+            Ok(poll) => poll.is_pending(),
+            Err(_) => {
+                id.record_failure(description, "the handler panicked");
+                false
+            }
+        });
+        if let Some(ack) = &flushing {
+            if !running.is_empty() {
+                return Poll::Pending;
+            }
+            let _ = ack.send(());
+            flushing = None;
+        }
+        let mut started = false;
+        let mut queue = queue.lock().expect("Async adapter queue is poisoned");
+        while running.len() < concurrency && flushing.is_none() {
+            match queue.messages.pop_front() {
+                Some(Message::Record(record)) => running.push(Box::pin(handler.log(*record))),
+                Some(Message::Flush(ack)) => flushing = Some(ack),
+                None if queue.closed && running.is_empty() => return Poll::Ready(()),
+                None => {
+                    queue.waker = Some(context.waker().clone());
+                    break;
+                }
+            }
+            started = true;
+        }
+        if !started {
+            return Poll::Pending;
+        }
+    }).await
+}
+impl<H: AsyncHandler> Handler for AsyncAdapter<H> {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut worker = self.worker.lock().expect("Async adapter is poisoned");
+        let worker = worker.get_or_insert_with(|| self.start());
+        let mut queue = worker.queue.lock().expect("Async adapter queue is poisoned");
+        if queue.messages.len() >= self.capacity {
+            drop(queue);
+            self.id.record_dropped(&self.describe(), record);
+            return;
+        }
+        queue.push(Message::Record(Box::new(record.clone())));
+    }
+    fn close(&self) {
+        let worker = self.worker.lock().expect("Async adapter is poisoned").take();
+        if let Some(worker) = worker {
+            worker.stop();
+        }
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("AsyncAdapter")
+            .with_setting("capacity", self.capacity)
+            .with_setting("concurrency", self.concurrency)
+            .with_setting("runtime", self.runtime.name())
+            .with_inner(self.handler.describe())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
@@ -0,0 +1,172 @@
+use crate::formatter::format_time;
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerHealth, LogLevel, Record};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The hash the chain of every file starts with.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// The result of [AuditHandler::verify](AuditHandler::verify).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuditStatus {
+    /// Every line matches the hash chain.
+    Intact {
+        /// The number of lines verified.
+        lines: usize,
+        /// The hash of the last line. Compare it with [AuditHandler::head](AuditHandler::head) (or a copy kept elsewhere) to detect removed lines at the end of the file.
+        head: String,
+    },
+    /// A line was changed, inserted, removed or is malformed.
+    Broken {
+        /// The first line (starting at 1) that doesn't match the hash chain.
+        line: usize,
+    },
+}
+
+struct Chain {
+    file: File,
+    head: String,
+}
+
+/// A tamper-evident [Handler](Handler) that appends records to a file as a hash chain.
+///
+/// Every line starts with the SHA-256 hash of the previous line's hash and its own content,
+/// so changing, inserting or removing a line breaks the chain from that line on.
+/// Use [verify](AuditHandler::verify) to check a file.
+/// Newlines in messages are escaped, so every record is exactly one line.
+///
+/// Like the [FileHandler](crate::FileHandler), the file can be reopened for log rotation.
+/// The chain continues from the last line of an existing file and starts fresh in a new one,
+/// so every file can be verified on its own.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{AuditHandler, AuditStatus, Level, Logger};
+/// let path = std::env::temp_dir().join(format!("logging-audit-{}.log", std::process::id()));
+/// let handler = std::sync::Arc::new(AuditHandler::new(&path).unwrap());
+/// let logger = Logger::new("audit");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(std::sync::Arc::clone(&handler));
+/// logger.info("user jane granted admin rights");
+/// logger.info("user john deleted");
+///
+/// let status = AuditHandler::verify(&path).unwrap();
+/// assert_eq!(status, AuditStatus::Intact { lines: 2, head: handler.head() });
+///
+/// let tampered = std::fs::read_to_string(&path).unwrap().replace("jane", "joan");
+/// std::fs::write(&path, tampered).unwrap();
+/// assert_eq!(AuditHandler::verify(&path).unwrap(), AuditStatus::Broken { line: 1 });
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct AuditHandler {
+    path: PathBuf,
+    chain: Mutex<Chain>,
+    id: HandlerId,
+}
+impl AuditHandler {
+    /// Create a handler appending to the file at the given path.
+    /// The file is created if it doesn't exist yet, otherwise the chain continues from its last line.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to log to.
+    ///
+    /// returns: Result<AuditHandler, Error>
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let chain = Self::open(&path)?;
+        Ok(Self { path, chain: Mutex::new(chain), id: HandlerId::new() })
+    }
+    /// The path of the file this handler logs to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// The hash of the last line written, in hex.
+    /// Keeping a copy of it outside the file allows detecting lines removed from the end.
+    pub fn head(&self) -> String {
+        self.chain.lock().expect("Audit handler is poisoned").head.clone()
+    }
+    /// Check that a file written by an [AuditHandler](AuditHandler) hasn't been tampered with.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to verify.
+    ///
+    /// returns: Result<AuditStatus, Error> An error if the file can't be read.
+    pub fn verify(path: impl AsRef<Path>) -> io::Result<AuditStatus> {
+        let mut head = GENESIS.to_string();
+        let mut lines = 0;
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            lines += 1;
+            match line.split_once(' ') {
+                Some((hash, content)) if hash == chain_hash(&head, content) => head = hash.to_string(),
+                _ => return Ok(AuditStatus::Broken { line: lines }),
+            }
+        }
+        Ok(AuditStatus::Intact { lines, head })
+    }
+    fn open(path: &Path) -> io::Result<Chain> {
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(path)?;
+        let head = last_line(&mut file)?
+            .and_then(|line| line.split_once(' ').map(|(hash, _)| hash.to_string()))
+            .unwrap_or_else(|| GENESIS.to_string());
+        Ok(Chain { file, head })
+    }
+}
+impl Handler for AuditHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let text = record.formatted.clone().unwrap_or_else(|| DefaultFormatter.format(record));
+        let content = format!("{} {}", format_time(record.time), text.replace('\r', "\\r").replace('\n', "\\n"));
+        let mut chain = self.chain.lock().expect("Audit handler is poisoned");
+        let hash = chain_hash(&chain.head, &content);
+        if let Err(err) = writeln!(chain.file, "{} {}", hash, content) {
+            self.id.record_failure(&self.describe(), err);
+            return;
+        }
+        chain.head = hash;
+    }
+    fn reopen(&self) {
+        let mut chain = self.chain.lock().expect("Audit handler is poisoned");
+        match Self::open(&self.path) {
+            Ok(reopened) => *chain = reopened,
+            Err(err) => self.id.record_failure(&self.describe(), err),
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+
+fn chain_hash(previous: &str, content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(previous.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(content.as_bytes());
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+/// Read the last non-empty line of a file without reading all of it.
+fn last_line(file: &mut File) -> io::Result<Option<String>> {
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut chunk = 4096;
+    loop {
+        let start = len.saturating_sub(chunk);
+        file.seek(SeekFrom::Start(start))?;
+        let mut tail = Vec::new();
+        Read::by_ref(file).take(len - start).read_to_end(&mut tail)?;
+        let trimmed = tail.strip_suffix(b"\n").unwrap_or(&tail);
+        match trimmed.iter().rposition(|byte| *byte == b'\n') {
+            Some(newline) => return Ok(Some(String::from_utf8_lossy(&trimmed[newline + 1..]).into_owned())),
+            None if start == 0 => return Ok((!trimmed.is_empty()).then(|| String::from_utf8_lossy(trimmed).into_owned())),
+            None => chunk *= 2,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Control the loggers of a running process through its admin socket.
+//!
+//! Usage: `logctl [--socket <path>] <command...>`, e.g. `logctl set foo::db debug`.
+//! The socket can also be given through the `LOGCTL_SOCKET` environment variable.
+
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: logctl [--socket <path>] (list | levels | get <logger> | set <logger> <level>)";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let socket = match args.iter().position(|arg| arg == "--socket") {
+        Some(index) if index + 1 < args.len() => {
+            let socket = args.remove(index + 1);
+            args.remove(index);
+            socket
+        }
+        Some(_) => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+        None => match std::env::var("LOGCTL_SOCKET") {
+            Ok(socket) => socket,
+            Err(_) => {
+                eprintln!("no socket given. Use --socket or set LOGCTL_SOCKET.\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    if args.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+    match logging::admin::request(&socket, &args.join(" ")) {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{output}");
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("logctl: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
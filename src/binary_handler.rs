@@ -0,0 +1,411 @@
+use crate::stats::HandlerId;
+use crate::{FieldValue, Handler, HandlerHealth, LogLevel, Record, SCHEMA_VERSION};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// A [Handler](Handler) writing records in a compact binary format, for high volume logging where text or JSON is too expensive.
+///
+/// Every record is a MessagePack array of `[seconds, nanoseconds, level, logger, message, {fields}, correlation_id or nil, sequence, schema_version, span_id or nil, template or nil, [tags]]`,
+/// so the stream can also be read by any MessagePack library. Elements are only ever appended (see [SCHEMA_VERSION](crate::SCHEMA_VERSION)),
+/// so readers should ignore any elements after the ones they know. Field values are written as native MessagePack values of their type
+/// (integers, floats, booleans, strings, maps and arrays) since schema version 2, and as strings before.
+/// Use a [RecordReader](RecordReader) to decode the records again.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, BinaryHandler, FieldValue, Level, Logger, RecordReader};
+/// let path = std::env::temp_dir().join(format!("logging-binary-{}.bin", std::process::id()));
+/// let logger = Logger::new("sensor");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(BinaryHandler::file(&path).unwrap());
+/// logger.log_fields("reading", Level::DEBUG, fields! { celsius = 21.5 });
+///
+/// let records: Vec<_> = RecordReader::open(&path).unwrap().collect::<Result<_, _>>().unwrap();
+/// assert_eq!(records[0].message, "reading");
+/// assert_eq!(records[0].field("celsius"), Some(&FieldValue::Float(21.5)));
+/// assert!(records[0].sequence > 0);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct BinaryHandler {
+    path: Option<PathBuf>,
+    target: Mutex<Box<dyn Write + Send>>,
+    id: HandlerId,
+}
+impl BinaryHandler {
+    /// Create a handler writing to any target, e.g. a socket.
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: Where to write the records to.
+    ///
+    /// returns: BinaryHandler
+    pub fn new<W: Write + Send + 'static>(target: W) -> Self {
+        Self { path: None, target: Mutex::new(Box::new(target)), id: HandlerId::new() }
+    }
+    /// Create a handler appending to the file at the given path.
+    /// The file is created if it doesn't exist yet and can be reopened for log rotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to log to.
+    ///
+    /// returns: Result<BinaryHandler, Error>
+    pub fn file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open(&path)?;
+        Ok(Self { path: Some(path), target: Mutex::new(Box::new(file)), id: HandlerId::new() })
+    }
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+impl Handler for BinaryHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let bytes = encode(record);
+        let mut target = self.target.lock().expect("Binary handler is poisoned");
+        if let Err(err) = target.write_all(&bytes).and_then(|_| target.flush()) {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn reopen(&self) {
+        let Some(path) = &self.path else { return };
+        let mut target = self.target.lock().expect("Binary handler is poisoned");
+        match Self::open(path) {
+            Ok(reopened) => *target = Box::new(reopened),
+            Err(err) => self.id.record_failure(&self.describe(), err),
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+
+/// Reads records written by a [BinaryHandler](BinaryHandler).
+///
+/// Iterates over the records until the end of the input. A truncated or invalid record ends the iteration with an error.
+pub struct RecordReader<R> {
+    source: R,
+    failed: bool,
+}
+impl<R: Read> RecordReader<R> {
+    /// Read records from any source.
+    pub fn new(source: R) -> Self {
+        Self { source, failed: false }
+    }
+}
+impl RecordReader<BufReader<File>> {
+    /// Read the records of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file written by a [BinaryHandler](BinaryHandler).
+    ///
+    /// returns: Result<RecordReader<BufReader<File>>, Error>
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufReader::new(File::open(path)?)))
+    }
+}
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = io::Result<Record>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let mut marker = [0];
+        match self.source.read(&mut marker) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(err) => {
+                self.failed = true;
+                return Some(Err(err));
+            }
+        }
+        let record = decode(marker[0], &mut self.source);
+        self.failed = record.is_err();
+        Some(record)
+    }
+}
+
+/// The number of elements of a record written by this version.
+const ELEMENTS: usize = 12;
+
+fn encode(record: &Record) -> Vec<u8> {
+    let time = record.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let mut bytes = Vec::with_capacity(64 + record.message.len());
+    bytes.push(0x90 | ELEMENTS as u8);
+    write_int(&mut bytes, time.as_secs() as i64);
+    write_int(&mut bytes, time.subsec_nanos() as i64);
+    write_int(&mut bytes, record.level as i64);
+    write_str(&mut bytes, &record.logger);
+    write_str(&mut bytes, &record.message);
+    write_fields(&mut bytes, &record.fields);
+    match &record.correlation_id {
+        Some(id) => write_str(&mut bytes, id),
+        None => bytes.push(0xc0),
+    }
+    write_int(&mut bytes, record.sequence as i64);
+    write_int(&mut bytes, SCHEMA_VERSION as i64);
+    match &record.span_id {
+        Some(id) => write_str(&mut bytes, id),
+        None => bytes.push(0xc0),
+    }
+    match &record.template {
+        Some(template) => write_str(&mut bytes, template),
+        None => bytes.push(0xc0),
+    }
+    write_value(&mut bytes, &FieldValue::List(record.tags.iter().map(|tag| FieldValue::Str(tag.clone())).collect()));
+    bytes
+}
+fn write_fields(bytes: &mut Vec<u8>, fields: &[(String, FieldValue)]) {
+    write_map_len(bytes, fields.len());
+    for (key, value) in fields {
+        write_str(bytes, key);
+        write_value(bytes, value);
+    }
+}
+fn write_value(bytes: &mut Vec<u8>, value: &FieldValue) {
+    match value {
+        FieldValue::Bool(value) => bytes.push(if *value { 0xc3 } else { 0xc2 }),
+        FieldValue::Int(number) => write_int(bytes, *number),
+        FieldValue::Float(number) => {
+            bytes.push(0xcb);
+            bytes.extend_from_slice(&number.to_be_bytes());
+        }
+        FieldValue::Str(text) => write_str(bytes, text),
+        FieldValue::Map(fields) => write_fields(bytes, fields),
+        FieldValue::List(values) => {
+            if values.len() < 16 {
+                bytes.push(0x90 | values.len() as u8);
+            } else {
+                bytes.push(0xdd);
+                bytes.extend_from_slice(&(values.len() as u32).to_be_bytes());
+            }
+            values.iter().for_each(|value| write_value(bytes, value));
+        }
+        FieldValue::Lazy(lazy) => write_value(bytes, &lazy.evaluate()),
+    }
+}
+fn write_int(bytes: &mut Vec<u8>, value: i64) {
+    match value {
+        0..=0x7f => bytes.push(value as u8),
+        -32..=-1 => bytes.push(value as i8 as u8),
+        _ => {
+            bytes.push(0xd3);
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+fn write_str(bytes: &mut Vec<u8>, text: &str) {
+    match text.len() {
+        length @ 0..=31 => bytes.push(0xa0 | length as u8),
+        length => {
+            bytes.push(0xdb);
+            bytes.extend_from_slice(&(length as u32).to_be_bytes());
+        }
+    }
+    bytes.extend_from_slice(text.as_bytes());
+}
+fn write_map_len(bytes: &mut Vec<u8>, length: usize) {
+    if length < 16 {
+        bytes.push(0x80 | length as u8);
+    } else {
+        bytes.push(0xdf);
+        bytes.extend_from_slice(&(length as u32).to_be_bytes());
+    }
+}
+
+/// How deeply field values may be nested, so a crafted record can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+fn invalid() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "invalid binary record")
+}
+fn read_bytes<const N: usize>(source: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    source.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+fn read_marker(source: &mut impl Read) -> io::Result<u8> {
+    Ok(read_bytes::<1>(source)?[0])
+}
+fn read_int(marker: u8, source: &mut impl Read) -> io::Result<i64> {
+    Ok(match marker {
+        0x00..=0x7f => marker as i64,
+        0xe0..=0xff => marker as i8 as i64,
+        0xcc => u8::from_be_bytes(read_bytes(source)?) as i64,
+        0xcd => u16::from_be_bytes(read_bytes(source)?) as i64,
+        0xce => u32::from_be_bytes(read_bytes(source)?) as i64,
+        0xcf => u64::from_be_bytes(read_bytes(source)?) as i64,
+        0xd0 => i8::from_be_bytes(read_bytes(source)?) as i64,
+        0xd1 => i16::from_be_bytes(read_bytes(source)?) as i64,
+        0xd2 => i32::from_be_bytes(read_bytes(source)?) as i64,
+        0xd3 => i64::from_be_bytes(read_bytes(source)?),
+        _ => return Err(invalid()),
+    })
+}
+fn read_str(marker: u8, source: &mut impl Read) -> io::Result<String> {
+    let length = match marker {
+        0xa0..=0xbf => (marker & 0x1f) as usize,
+        0xd9 => u8::from_be_bytes(read_bytes(source)?) as usize,
+        0xda => u16::from_be_bytes(read_bytes(source)?) as usize,
+        0xdb => u32::from_be_bytes(read_bytes(source)?) as usize,
+        _ => return Err(invalid()),
+    };
+    let mut bytes = Vec::new();
+    source.take(length as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < length {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+fn read_map_len(marker: u8, source: &mut impl Read) -> io::Result<usize> {
+    Ok(match marker {
+        0x80..=0x8f => (marker & 0x0f) as usize,
+        0xde => u16::from_be_bytes(read_bytes(source)?) as usize,
+        0xdf => u32::from_be_bytes(read_bytes(source)?) as usize,
+        _ => return Err(invalid()),
+    })
+}
+/// Read a map of fields. Values may be of any type written by [write_fields], older versions only wrote strings.
+fn read_fields(marker: u8, source: &mut impl Read, depth: usize) -> io::Result<Vec<(String, FieldValue)>> {
+    let length = read_map_len(marker, source)?;
+    let mut fields = Vec::with_capacity(length.min(64));
+    for _ in 0..length {
+        let key = read_str(read_marker(source)?, source)?;
+        let value = read_value(read_marker(source)?, source, depth)?;
+        fields.push((key, value));
+    }
+    Ok(fields)
+}
+/// Read a value nested in `depth` maps or arrays.
+fn read_value(marker: u8, source: &mut impl Read, depth: usize) -> io::Result<FieldValue> {
+    if depth > MAX_DEPTH {
+        return Err(invalid());
+    }
+    Ok(match marker {
+        0xc2 => FieldValue::Bool(false),
+        0xc3 => FieldValue::Bool(true),
+        0xca => FieldValue::Float(f32::from_be_bytes(read_bytes(source)?) as f64),
+        0xcb => FieldValue::Float(f64::from_be_bytes(read_bytes(source)?)),
+        0xa0..=0xbf | 0xd9..=0xdb => FieldValue::Str(read_str(marker, source)?),
+        0x80..=0x8f | 0xde | 0xdf => FieldValue::Map(read_fields(marker, source, depth + 1)?),
+        0x90..=0x9f | 0xdc | 0xdd => {
+            let length = read_array_len(marker, source)?;
+            let mut values = Vec::with_capacity(length.min(64));
+            for _ in 0..length {
+                values.push(read_value(read_marker(source)?, source, depth + 1)?);
+            }
+            FieldValue::List(values)
+        }
+        marker => FieldValue::Int(read_int(marker, source)?),
+    })
+}
+fn read_array_len(marker: u8, source: &mut impl Read) -> io::Result<usize> {
+    Ok(match marker {
+        0x90..=0x9f => (marker & 0x0f) as usize,
+        0xdc => u16::from_be_bytes(read_bytes(source)?) as usize,
+        0xdd => u32::from_be_bytes(read_bytes(source)?) as usize,
+        _ => return Err(invalid()),
+    })
+}
+/// Skip a MessagePack value of any type, nested in `depth` maps or arrays.
+fn skip_value(source: &mut impl Read, depth: usize) -> io::Result<()> {
+    if depth > MAX_DEPTH {
+        return Err(invalid());
+    }
+    let marker = read_marker(source)?;
+    let length = match marker {
+        0xc0 | 0xc2 | 0xc3 | 0x00..=0x7f | 0xe0..=0xff => 0,
+        0xcc | 0xd0 => 1,
+        0xcd | 0xd1 => 2,
+        0xca | 0xce | 0xd2 => 4,
+        0xcb | 0xcf | 0xd3 => 8,
+        0xd4..=0xd8 => 1 + (1 << (marker - 0xd4)),
+        0xa0..=0xbf | 0xd9..=0xdb => {
+            read_str(marker, source)?;
+            0
+        }
+        0xc4 => u8::from_be_bytes(read_bytes(source)?) as u64,
+        0xc5 => u16::from_be_bytes(read_bytes(source)?) as u64,
+        0xc6 => u32::from_be_bytes(read_bytes(source)?) as u64,
+        0xc7 => 1 + u8::from_be_bytes(read_bytes(source)?) as u64,
+        0xc8 => 1 + u16::from_be_bytes(read_bytes(source)?) as u64,
+        0xc9 => 1 + u32::from_be_bytes(read_bytes(source)?) as u64,
+        0x90..=0x9f | 0xdc | 0xdd => {
+            for _ in 0..read_array_len(marker, source)? {
+                skip_value(source, depth + 1)?;
+            }
+            0
+        }
+        0x80..=0x8f | 0xde | 0xdf => {
+            for _ in 0..read_map_len(marker, source)? * 2 {
+                skip_value(source, depth + 1)?;
+            }
+            0
+        }
+        _ => return Err(invalid()),
+    };
+    let skipped = io::copy(&mut source.take(length), &mut io::sink())?;
+    if skipped < length {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+fn decode(marker: u8, source: &mut impl Read) -> io::Result<Record> {
+    // records written before the sequence number was added have 7 elements, newer versions may have more
+    let elements = read_array_len(marker, source)?;
+    if elements < 7 {
+        return Err(invalid());
+    }
+    let seconds = read_int(read_marker(source)?, source)?;
+    let nanos = read_int(read_marker(source)?, source)?;
+    let level = read_int(read_marker(source)?, source)?;
+    let logger = read_str(read_marker(source)?, source)?;
+    let message = read_str(read_marker(source)?, source)?;
+    let mut record = Record::unsequenced(LogLevel::try_from(level).map_err(|_| invalid())?, message, logger);
+    record.time = UNIX_EPOCH.checked_add(Duration::new(seconds.max(0) as u64, nanos.clamp(0, 999_999_999) as u32)).ok_or_else(invalid)?;
+    record.fields = read_fields(read_marker(source)?, source, 0)?;
+    record.correlation_id = match read_marker(source)? {
+        0xc0 => None,
+        marker => Some(read_str(marker, source)?),
+    };
+    if elements > 7 {
+        record.sequence = read_int(read_marker(source)?, source)? as u64;
+    }
+    if elements > 8 {
+        // the schema version
+        skip_value(source, 0)?;
+    }
+    record.span_id = match elements {
+        ..=9 => None,
+        _ => match read_marker(source)? {
+            0xc0 => None,
+            marker => Some(read_str(marker, source)?),
+        },
+    };
+    record.template = match elements {
+        ..=10 => None,
+        _ => match read_marker(source)? {
+            0xc0 => None,
+            marker => Some(read_str(marker, source)?),
+        },
+    };
+    if elements > 11 {
+        match read_value(read_marker(source)?, source, 0)? {
+            FieldValue::List(tags) => record.tags = tags.iter().map(FieldValue::to_string).collect(),
+            _ => return Err(invalid()),
+        }
+    }
+    // whatever later versions add
+    for _ in 12..elements {
+        skip_value(source, 0)?;
+    }
+    Ok(record)
+}
@@ -0,0 +1,112 @@
+use crate::formatter::{json_fields, json_string};
+use crate::{FileHandler, Handler, HandlerConfig, LogLevel, Record};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(0);
+thread_local! {
+    /// A small number identifying the current thread in the trace.
+    static THREAD: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed) + 1;
+}
+
+/// A [Handler](Handler) writing spans in the [Chrome trace event format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+/// so performance investigations can look at them in `chrome://tracing`, [Perfetto](https://ui.perfetto.dev) or [speedscope](https://www.speedscope.app).
+///
+/// Spans are the records logged when a [Stopwatch](crate::Stopwatch) (and so [time!](crate::time!)) or an `#[instrument]`ed function finishes:
+/// records with a `span` and a `duration_us` field. They become complete events on the thread they were logged on.
+/// All other records become instant events, so log messages show up as markers between the spans.
+///
+/// The file is a JSON array that is never closed, which the viewers accept, so the trace can be loaded while the program is still running.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{ChromeTraceHandler, Level, Logger, Stopwatch};
+/// let path = std::env::temp_dir().join(format!("logging-trace-{}.json", std::process::id()));
+/// let logger = Logger::new("renderer");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(ChromeTraceHandler::new(&path).unwrap());
+/// {
+///     let _frame = Stopwatch::new(&logger, Level::DEBUG, "frame");
+///     logger.info("textures loaded");
+/// }
+///
+/// let trace = std::fs::read_to_string(&path).unwrap();
+/// let events: serde_json::Value = serde_json::from_str(&format!("{}{{}}]", trace)).unwrap();
+/// assert_eq!(events[0]["ph"], "i");
+/// assert_eq!(events[0]["name"], "textures loaded");
+/// assert_eq!(events[1]["ph"], "X");
+/// assert_eq!(events[1]["name"], "frame");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct ChromeTraceHandler {
+    file: FileHandler,
+}
+impl ChromeTraceHandler {
+    /// Create a handler appending to the trace file at the given path.
+    /// The file is created (and the JSON array started) if it doesn't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to write the trace to.
+    ///
+    /// returns: Result<ChromeTraceHandler, Error>
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        start(path.as_ref())?;
+        Ok(Self { file: FileHandler::new(path)? })
+    }
+    /// The path of the file this handler writes to.
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+    /// The trace event of a record.
+    fn event(record: &Record) -> String {
+        let time = record.time.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+        let thread = THREAD.with(|thread| *thread);
+        let span = record.field("span").zip(record.field("duration_us").and_then(|duration| duration.as_i64()));
+        let (name, phase, args) = match span {
+            Some((name, duration)) => (
+                name.to_string(),
+                format!("\"ph\":\"X\",\"ts\":{},\"dur\":{}", time.saturating_sub(duration as u64), duration),
+                record.fields.iter().filter(|(key, _)| key != "span" && key != "duration_us").cloned().collect(),
+            ),
+            None => (record.message.clone(), format!("\"ph\":\"i\",\"s\":\"t\",\"ts\":{}", time), record.fields.clone()),
+        };
+        format!(
+            "{{\"name\":{},\"cat\":{},{},\"pid\":{},\"tid\":{},\"args\":{}}},",
+            json_string(&name), json_string(&record.logger), phase, std::process::id(), thread, json_fields(&args),
+        )
+    }
+}
+impl Handler for ChromeTraceHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut record = record.clone();
+        record.formatted = Some(Self::event(&record));
+        self.file.handle(&record)
+    }
+    fn reopen(&self) {
+        // a rotated trace starts a new array, errors are reported by the file handler
+        let _ = start(self.path());
+        self.file.reopen()
+    }
+    fn close(&self) {
+        self.file.close()
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("ChromeTraceHandler").with_setting("path", self.path().display())
+    }
+}
+
+/// Start the JSON array of a trace file, if the file is new.
+fn start(path: &Path) -> io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        file.write_all(b"[\n")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use crate::stats::HandlerId;
+use crate::{Handler, HandlerConfig, HandlerHealth, LogLevel, Record};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+struct Circuit {
+    failures: u32,
+    open_until: Option<Instant>,
+    skipped: u64,
+    /// The errors the [health](Handler::health) of the handler reported when it was last checked.
+    seen_errors: u64,
+}
+
+/// Stops passing records on to a handler that keeps failing, e.g. a file on a full disk or an unreachable collector,
+/// so a dead sink doesn't slow down every log call.
+///
+/// A handler counts as failing when its [health](Handler::health) reports new errors, as the built-in handlers do,
+/// which also catches errors on their background threads (e.g. a batch that failed to send).
+/// Handlers without a health report count as failing when they report an error with [record_handler_error](crate::record_handler_error) while handling a record.
+/// After a number of failures in a row, the circuit opens: for the cool-down period, records are skipped instead of being passed on.
+/// Skipped records are counted in [stats](crate::stats) as dropped, and the breaker and the handler are reported as disabled by [handler_health](crate::handler_health). After the cool-down, the next record is passed on as a probe.
+/// If it succeeds, the circuit closes again, otherwise another cool-down starts.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{record_handler_error, CircuitBreakerHandler, Handler, Level, Logger, LogLevel};
+/// use std::time::Duration;
+///
+/// struct Unreachable;
+/// impl Handler for Unreachable {
+///     fn log(&self, _level: LogLevel, _message: String, _logger: String) {
+///         record_handler_error("Unreachable");
+///     }
+/// }
+///
+/// let breaker = CircuitBreakerHandler::new(Unreachable)
+///     .with_threshold(3)
+///     .with_cooldown(Duration::from_secs(60));
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(breaker.clone());
+/// for _ in 0..5 {
+///     logger.info("Hello World");
+/// }
+/// assert!(breaker.is_open());
+/// assert_eq!(breaker.skipped(), 2);
+///
+/// // built-in handlers are watched through their health, and reported as disabled while the circuit is open
+/// # #[cfg(target_os = "linux")] {
+/// use logging::{handler_health, FileHandler, HealthState};
+/// let full = CircuitBreakerHandler::new(FileHandler::new("/dev/full").unwrap()).with_threshold(1);
+/// full.log(Level::INFO, "Hello World".to_string(), "foo".to_string());
+/// assert!(full.is_open());
+/// let health = handler_health();
+/// let file = health.iter().find(|health| health.settings.get("path").is_some_and(|path| path == "/dev/full")).unwrap();
+/// assert_eq!(file.state, HealthState::Disabled);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CircuitBreakerHandler {
+    inner: Arc<dyn Handler>,
+    threshold: u32,
+    cooldown: Duration,
+    circuit: Arc<Mutex<Circuit>>,
+    id: HandlerId,
+}
+impl CircuitBreakerHandler {
+    /// Wrap a handler. By default, the circuit opens after 5 failures in a row for 30 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: The handler to protect.
+    ///
+    /// returns: CircuitBreakerHandler
+    pub fn new<T: Handler + 'static>(inner: T) -> Self {
+        // errors from before the handler was wrapped don't count
+        let seen_errors = inner.health().map_or(0, |health| health.errors);
+        Self {
+            inner: Arc::new(inner),
+            threshold: 5,
+            cooldown: Duration::from_secs(30),
+            circuit: Arc::new(Mutex::new(Circuit { seen_errors, ..Default::default() })),
+            id: HandlerId::new(),
+        }
+    }
+    /// Set after how many failures in a row the circuit opens.
+    pub fn with_threshold(mut self, failures: u32) -> Self {
+        self.threshold = failures.max(1);
+        self
+    }
+    /// Set for how long records are skipped once the circuit is open.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+    /// Whether records are currently being skipped.
+    pub fn is_open(&self) -> bool {
+        let circuit = self.circuit.lock().expect("Circuit breaker is poisoned");
+        circuit.open_until.is_some_and(|open_until| Instant::now() < open_until)
+    }
+    /// How many records have been skipped in total because the circuit was open.
+    pub fn skipped(&self) -> u64 {
+        self.circuit.lock().expect("Circuit breaker is poisoned").skipped
+    }
+    fn close_circuit(&self, circuit: &mut Circuit) {
+        circuit.failures = 0;
+        if circuit.open_until.take().is_some() {
+            self.set_disabled(false);
+        }
+    }
+    /// Report this breaker and the handler behind it as disabled (or enabled again) in [handler_health](crate::handler_health).
+    fn set_disabled(&self, disabled: bool) {
+        self.id.set_disabled(&self.describe(), disabled);
+        if let Some(health) = self.inner.health() {
+            crate::stats::set_handler_disabled(&health, disabled);
+        }
+    }
+}
+impl Handler for CircuitBreakerHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        {
+            let mut circuit = self.circuit.lock().expect("Circuit breaker is poisoned");
+            if circuit.open_until.is_some_and(|open_until| Instant::now() < open_until) {
+                circuit.skipped += 1;
+                drop(circuit);
+                self.id.record_dropped(&self.describe(), record);
+                return;
+            }
+        }
+        let failed = crate::stats::handler_failed(|| self.inner.handle(record));
+        let errors = self.inner.health().map(|health| health.errors);
+        let mut circuit = self.circuit.lock().expect("Circuit breaker is poisoned");
+        // new errors in the health of the handler include those on its background threads, e.g. a batch failing to send
+        let failed = failed || errors.is_some_and(|errors| errors > circuit.seen_errors);
+        if let Some(errors) = errors {
+            circuit.seen_errors = circuit.seen_errors.max(errors);
+        }
+        if !failed {
+            self.close_circuit(&mut circuit);
+            return;
+        }
+        circuit.failures += 1;
+        // a failed probe after a cool-down opens the circuit again right away
+        if circuit.failures >= self.threshold || circuit.open_until.is_some() {
+            circuit.open_until = Some(Instant::now() + self.cooldown);
+            self.set_disabled(true);
+        }
+    }
+    fn reopen(&self) {
+        self.inner.reopen();
+        let mut circuit = self.circuit.lock().expect("Circuit breaker is poisoned");
+        self.close_circuit(&mut circuit);
+    }
+    fn close(&self) {
+        self.inner.close()
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        !self.is_open() && self.inner.accepts(record)
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("CircuitBreakerHandler")
+            .with_setting("threshold", self.threshold)
+            .with_setting("cooldown", format!("{:?}", self.cooldown))
+            .with_inner(self.inner.describe())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::Level;
+use std::collections::BTreeMap;
+
+/// The effective logging setup of the program, as returned by [export_config](export_config).
+///
+/// With the `serde` feature, it can be serialized, e.g. to JSON or TOML, to be attached to a support ticket or shown by a diagnostics endpoint.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// logging::Logger::new("app");
+/// let json = serde_json::to_string_pretty(&logging::export_config()).unwrap();
+/// assert!(json.contains("\"name\": \"app\""));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigDocument {
+    /// Every logger created so far, the root logger first.
+    pub loggers: Vec<LoggerConfig>,
+}
+impl ConfigDocument {
+    /// The configuration of a logger, if it has been created.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The full name of the logger. The empty name is the root logger.
+    ///
+    /// returns: Option<&LoggerConfig>
+    pub fn logger(&self, name: &str) -> Option<&LoggerConfig> {
+        self.loggers.iter().find(|logger| logger.name == name)
+    }
+}
+
+/// The configuration of a single logger in a [ConfigDocument](ConfigDocument).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoggerConfig {
+    /// The full name of the logger. The root logger has an empty name.
+    pub name: String,
+    /// The name of the minimum level of the logger (see [Level::name](Level::name)).
+    pub level: String,
+    /// The handlers attached to the logger, including the ones inherited from its parents.
+    pub handlers: Vec<HandlerConfig>,
+}
+
+/// The description of a handler in a [ConfigDocument](ConfigDocument), as returned by [Handler::describe](crate::Handler::describe).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Handler, HandlerConfig, LogLevel};
+///
+/// struct WebhookHandler {
+///     url: String,
+/// }
+/// impl Handler for WebhookHandler {
+///     fn log(&self, level: LogLevel, message: String, logger: String) {
+///         // send the message to self.url
+///     }
+///     fn describe(&self) -> HandlerConfig {
+///         HandlerConfig::new("WebhookHandler").with_setting("url", &self.url)
+///     }
+/// }
+/// let handler = WebhookHandler { url: "https://example.com/hook".to_string() };
+/// assert_eq!(handler.describe().settings["url"], "https://example.com/hook");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandlerConfig {
+    /// The kind of handler, usually the name of its type, e.g. `FileHandler`.
+    pub kind: String,
+    /// The settings of the handler worth knowing about, e.g. the path of a file.
+    pub settings: BTreeMap<String, String>,
+    /// The handlers wrapped by this one, e.g. the inner handler of a [ConfiguredHandler](crate::ConfiguredHandler).
+    pub inner: Vec<HandlerConfig>,
+}
+impl HandlerConfig {
+    /// Describe a handler without settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind`: The kind of handler, usually the name of its type.
+    ///
+    /// returns: HandlerConfig
+    pub fn new(kind: impl ToString) -> Self {
+        Self { kind: kind.to_string(), settings: BTreeMap::new(), inner: Vec::new() }
+    }
+    /// Add a setting.
+    pub fn with_setting(mut self, key: impl ToString, value: impl ToString) -> Self {
+        self.settings.insert(key.to_string(), value.to_string());
+        self
+    }
+    /// Add a wrapped handler.
+    pub fn with_inner(mut self, inner: HandlerConfig) -> Self {
+        self.inner.push(inner);
+        self
+    }
+}
+
+/// The name of a type without its module path and generic parameters, e.g. `FileHandler`.
+pub(crate) fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Export the current configuration of all loggers: their levels and their handlers with the handlers' filters and settings,
+/// e.g. so a running service can report its effective logging setup.
+///
+/// returns: ConfigDocument
+///
+/// # Examples
+///
+/// ```
+/// use logging::{ConfiguredHandler, FileHandler, Level, Logger, ModuleFilter};
+/// let path = std::env::temp_dir().join(format!("logging-export-{}.log", std::process::id()));
+/// let logger = Logger::new("app");
+/// logger.set_level(Level::INFO);
+/// logger.add_handler(ConfiguredHandler::new(FileHandler::new(&path).unwrap()).with_level(Level::WARN).with_filter(ModuleFilter::new()));
+///
+/// let config = logging::export_config();
+/// let app = config.logger("app").unwrap();
+/// assert_eq!(app.level, "INFO");
+/// let handler = app.handlers.last().unwrap();
+/// assert_eq!(handler.kind, "ConfiguredHandler");
+/// assert_eq!(handler.settings["level"], "WARN");
+/// assert_eq!(handler.settings["filters"], "ModuleFilter");
+/// assert_eq!(handler.inner[0].kind, "FileHandler");
+/// assert_eq!(handler.inner[0].settings["path"], path.display().to_string());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn export_config() -> ConfigDocument {
+    let loggers = crate::logger::all_loggers().into_iter()
+        .map(|logger| {
+            let lock = logger.read().expect("Logger is poisoned");
+            LoggerConfig {
+                name: lock.name().to_string(),
+                level: Level::name(lock.level()),
+                handlers: lock.handlers().iter().map(|handler| handler.describe()).collect(),
+            }
+        })
+        .collect();
+    ConfigDocument { loggers }
+}
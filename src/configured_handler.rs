@@ -0,0 +1,220 @@
+use crate::config::short_type_name;
+use crate::{Filter, Formatter, Handler, HandlerConfig, HandlerHealth, Level, LogLevel, Record};
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+/// Wraps a handler with its own level range, filters and formatter.
+///
+/// The level and filters are checked before the record is passed on to the inner handler.
+/// If a formatter is set, it renders the record into [Record::formatted](Record::formatted), which the built-in handlers write instead of their own format.
+/// Configured handlers are cheap to clone, so the same configuration can be attached to several loggers.
+#[derive(Clone)]
+pub struct ConfiguredHandler {
+    inner: Arc<dyn Handler>,
+    level: LogLevel,
+    max_level: LogLevel,
+    filters: Vec<(&'static str, Arc<dyn Filter>)>,
+    formatter: Option<(&'static str, Arc<dyn Formatter>)>,
+    backtrace_level: Option<LogLevel>,
+}
+impl ConfiguredHandler {
+    /// Wrap a handler. Without further configuration, every record is passed on unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: The handler that actually logs the records.
+    ///
+    /// returns: ConfiguredHandler
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{ConfiguredHandler, ConsoleHandler, Level, Logger, Record};
+    ///
+    /// let handler = ConfiguredHandler::new(ConsoleHandler::new())
+    ///     .with_level(Level::WARN)
+    ///     .with_filter(|record: &Record| !record.message.contains("password"))
+    ///     .with_formatter(|record: &Record| format!("[{}] {}", record.logger, record.message));
+    ///
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(handler.clone());
+    /// Logger::new("bar").add_handler(handler);
+    ///
+    /// // will not be logged by the configured handler
+    /// logger.info("Hello World");
+    /// // prints "[foo] Something is off"
+    /// logger.warn("Something is off");
+    /// ```
+    pub fn new<T: Handler + 'static>(inner: T) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            level: Level::MIN,
+            max_level: Level::MAX,
+            filters: Vec::new(),
+            formatter: None,
+            backtrace_level: None,
+        }
+    }
+    /// Only pass on records at or above this level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The minimum level.
+    ///
+    /// returns: ConfiguredHandler
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+    /// Only pass on records at or below this level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The maximum level.
+    ///
+    /// returns: ConfiguredHandler
+    pub fn with_max_level(mut self, level: LogLevel) -> Self {
+        self.max_level = level;
+        self
+    }
+    /// Only pass on records with a level in the range, setting both the minimum and maximum level.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels`: The range of levels, e.g. `Level::DEBUG..=Level::INFO` or `Level::WARN..`.
+    ///
+    /// returns: ConfiguredHandler
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{ConfiguredHandler, Level, Logger, MemoryHandler};
+    /// use std::sync::Arc;
+    /// let (stdout, stderr) = (Arc::new(MemoryHandler::new(10)), Arc::new(MemoryHandler::new(10)));
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(ConfiguredHandler::new(Arc::clone(&stdout)).with_levels(Level::DEBUG..=Level::SUCCESS));
+    /// logger.add_handler(ConfiguredHandler::new(Arc::clone(&stderr)).with_levels(Level::WARN..));
+    ///
+    /// logger.info("Hello World");
+    /// logger.error("Something went wrong");
+    /// assert_eq!(stdout.records()[0].message, "Hello World");
+    /// assert_eq!(stderr.records()[0].message, "Something went wrong");
+    /// assert_eq!((stdout.records().len(), stderr.records().len()), (1, 1));
+    /// ```
+    pub fn with_levels(mut self, levels: impl RangeBounds<LogLevel>) -> Self {
+        self.level = match levels.start_bound() {
+            Bound::Included(level) => *level,
+            Bound::Excluded(level) => level.saturating_add(1),
+            Bound::Unbounded => Level::MIN,
+        };
+        self.max_level = match levels.end_bound() {
+            Bound::Included(level) => *level,
+            Bound::Excluded(level) => level.saturating_sub(1),
+            Bound::Unbounded => Level::MAX,
+        };
+        self
+    }
+    /// Add a filter. Records are only passed on if all filters accept them.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter`: The filter to add.
+    ///
+    /// returns: ConfiguredHandler
+    pub fn with_filter<F: Filter + 'static>(mut self, filter: F) -> Self {
+        self.filters.push((short_type_name::<F>(), Arc::new(filter)));
+        self
+    }
+    /// Set the formatter used to render the records for the inner handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `formatter`: The formatter to use.
+    ///
+    /// returns: ConfiguredHandler
+    pub fn with_formatter<F: Formatter + 'static>(mut self, formatter: F) -> Self {
+        self.formatter = Some((short_type_name::<F>(), Arc::new(formatter)));
+        self
+    }
+    /// Capture a backtrace for records at or above this level, if the logger hasn't already captured one
+    /// (see [set_backtrace_level](crate::set_backtrace_level)).
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The minimum level to capture backtraces for.
+    ///
+    /// returns: ConfiguredHandler
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{ConfiguredHandler, ConsoleHandler, Level, Logger};
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(ConfiguredHandler::new(ConsoleHandler::new()).with_backtrace(Level::CRITICAL));
+    /// // prints the message followed by a backtrace
+    /// logger.critical("Something went very wrong");
+    /// ```
+    pub fn with_backtrace(mut self, level: LogLevel) -> Self {
+        self.backtrace_level = Some(level);
+        self
+    }
+}
+impl Handler for ConfiguredHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        if record.level < self.level || record.level > self.max_level || !self.filters.iter().all(|(_, filter)| filter.filter(record)) {
+            return;
+        }
+        let capture = record.backtrace.is_none() && self.backtrace_level.is_some_and(|level| record.level >= level);
+        if !capture && self.formatter.is_none() {
+            return self.inner.handle(record);
+        }
+        let mut record = record.clone();
+        if capture {
+            record.capture_backtrace();
+        }
+        if let Some((_, formatter)) = &self.formatter {
+            record.formatted = Some(formatter.format(&record));
+        }
+        self.inner.handle(&record)
+    }
+    fn reopen(&self) {
+        self.inner.reopen()
+    }
+    fn close(&self) {
+        self.inner.close()
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        record.level >= self.level && record.level <= self.max_level
+            && self.filters.iter().all(|(_, filter)| filter.filter(record))
+            && self.inner.accepts(record)
+    }
+    fn describe(&self) -> HandlerConfig {
+        let mut config = HandlerConfig::new("ConfiguredHandler");
+        if self.level != Level::MIN {
+            config = config.with_setting("level", Level::name(self.level));
+        }
+        if self.max_level != Level::MAX {
+            config = config.with_setting("max_level", Level::name(self.max_level));
+        }
+        if !self.filters.is_empty() {
+            let names: Vec<&str> = self.filters.iter().map(|(name, _)| *name).collect();
+            config = config.with_setting("filters", names.join(", "));
+        }
+        if let Some((name, _)) = &self.formatter {
+            config = config.with_setting("formatter", name);
+        }
+        if let Some(level) = self.backtrace_level {
+            config = config.with_setting("backtrace_level", Level::name(level));
+        }
+        config.with_inner(self.inner.describe())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        self.inner.health()
+    }
+}
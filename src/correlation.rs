@@ -0,0 +1,272 @@
+//! Correlation IDs to connect all records belonging to one request or task.
+//!
+//! The current correlation ID is stored per thread and attached to every record logged on that thread
+//! (see [Record::correlation_id](crate::Record::correlation_id)).
+//! For async code, [scope](scope) keeps the ID attached to a future, no matter which thread polls it.
+//!
+//! IDs from [new_id](new_id) are compatible with the trace IDs of [W3C Trace Context](https://www.w3.org/TR/trace-context/),
+//! so the correlation ID doubles as the trace ID. Together with the [span ID](set_span), it can be received from upstream services
+//! with [set_traceparent](set_traceparent) and passed on to downstream services with [traceparent](traceparent).
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+    static SPAN: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generate a new, random looking correlation ID of 32 hex digits.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// let id = logging::correlation::new_id();
+/// assert_eq!(id.len(), 32);
+/// assert_ne!(id, logging::correlation::new_id());
+/// ```
+pub fn new_id() -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+    let seed = nanos ^ (std::process::id() as u64).rotate_left(32) ^ COUNTER.fetch_add(1, Ordering::Relaxed).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    format!("{:016x}{:016x}", splitmix64(seed), splitmix64(seed ^ 0xd1b5_4a32_d192_ed03))
+}
+/// Generate a new, random looking span ID of 16 hex digits, as used by W3C Trace Context.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// let id = logging::correlation::new_span_id();
+/// assert_eq!(id.len(), 16);
+/// assert_ne!(id, logging::correlation::new_span_id());
+/// ```
+pub fn new_span_id() -> String {
+    new_id()[..16].to_string()
+}
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The correlation ID of the current thread, if one is set.
+///
+/// returns: Option<String>
+pub fn current() -> Option<String> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// The span ID of the current thread, if one is set.
+///
+/// returns: Option<String>
+pub fn current_span() -> Option<String> {
+    SPAN.with(|span| span.borrow().clone())
+}
+
+/// Restores the previous correlation and span ID when dropped. Returned by [set](set), [set_span](set_span) and [set_traceparent](set_traceparent).
+pub struct CorrelationGuard {
+    previous: Option<String>,
+    previous_span: Option<String>,
+}
+impl CorrelationGuard {
+    fn replace(id: Option<String>, span: Option<String>) -> Self {
+        let previous = CURRENT.with(|current| current.replace(id));
+        let previous_span = SPAN.with(|current| current.replace(span));
+        Self { previous, previous_span }
+    }
+}
+impl Drop for CorrelationGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        let previous_span = self.previous_span.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+        SPAN.with(|span| *span.borrow_mut() = previous_span);
+    }
+}
+
+/// Set the correlation ID of the current thread until the returned guard is dropped.
+///
+/// # Arguments
+///
+/// * `id`: The correlation ID, e.g. from [new_id](new_id) or a request header.
+///
+/// returns: CorrelationGuard
+///
+/// # Examples
+///
+/// ```
+/// use logging::correlation;
+/// {
+///     let _guard = correlation::set("request-42");
+///     assert_eq!(correlation::current().as_deref(), Some("request-42"));
+/// }
+/// assert_eq!(correlation::current(), None);
+/// ```
+pub fn set(id: impl ToString) -> CorrelationGuard {
+    CorrelationGuard::replace(Some(id.to_string()), current_span())
+}
+
+/// Set the span ID of the current thread until the returned guard is dropped. It is attached to every record like the correlation ID
+/// (see [Record::span_id](crate::Record::span_id)).
+///
+/// # Arguments
+///
+/// * `id`: The span ID, e.g. from [new_span_id](new_span_id).
+///
+/// returns: CorrelationGuard
+pub fn set_span(id: impl ToString) -> CorrelationGuard {
+    CorrelationGuard::replace(current(), Some(id.to_string()))
+}
+
+/// Parse a W3C `traceparent` header like `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01` into its trace ID and parent span ID.
+///
+/// # Arguments
+///
+/// * `header`: The value of the header.
+///
+/// returns: Option<(String, String)> The trace ID and the span ID of the caller, or `None` if the header is invalid.
+///
+/// # Examples
+///
+/// ```
+/// use logging::correlation;
+/// let (trace, parent) = correlation::parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+/// assert_eq!(trace, "4bf92f3577b34da6a3ce929d0e0e4736");
+/// assert_eq!(parent, "00f067aa0ba902b7");
+/// assert_eq!(correlation::parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01"), None);
+/// ```
+pub fn parse_traceparent(header: &str) -> Option<(String, String)> {
+    let mut parts = header.trim().split('-');
+    let (version, trace, parent, flags) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    // future versions may append more parts, version 00 must not
+    if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.next().is_some()) || !is_hex(flags, 2) {
+        return None;
+    }
+    if !is_trace_id(trace) || !is_hex(parent, 16) || parent.bytes().all(|digit| digit == b'0') {
+        return None;
+    }
+    Some((trace.to_string(), parent.to_string()))
+}
+fn is_hex(text: &str, length: usize) -> bool {
+    text.len() == length && text.bytes().all(|digit| matches!(digit, b'0'..=b'9' | b'a'..=b'f'))
+}
+fn is_trace_id(id: &str) -> bool {
+    is_hex(id, 32) && id.bytes().any(|digit| digit != b'0')
+}
+
+/// Continue a trace received from an upstream service: the trace ID of a W3C `traceparent` header becomes the correlation ID
+/// and a new span ID is set for the work of this service, until the returned guard is dropped.
+///
+/// # Arguments
+///
+/// * `header`: The value of the `traceparent` header.
+///
+/// returns: Option<CorrelationGuard> `None` (without changing anything) if the header is invalid.
+///
+/// # Examples
+///
+/// ```
+/// use logging::correlation;
+/// {
+///     let _guard = correlation::set_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+///     assert_eq!(correlation::current().as_deref(), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+///     assert_ne!(correlation::current_span().as_deref(), Some("00f067aa0ba902b7"));
+///     // passed on to the next service
+///     let outgoing = correlation::traceparent().unwrap();
+///     assert!(outgoing.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+/// }
+/// assert_eq!(correlation::current(), None);
+/// ```
+pub fn set_traceparent(header: &str) -> Option<CorrelationGuard> {
+    let (trace, _) = parse_traceparent(header)?;
+    Some(CorrelationGuard::replace(Some(trace), Some(new_span_id())))
+}
+
+/// The W3C `traceparent` header to send to downstream services, so their records share the trace ID of this thread.
+/// If no span ID is set, a new one is used.
+///
+/// returns: Option<String> `None` if no correlation ID is set or it isn't a valid trace ID (32 hex digits, as generated by [new_id](new_id)).
+pub fn traceparent() -> Option<String> {
+    let trace = current().filter(|id| is_trace_id(id))?;
+    let span = current_span().filter(|id| is_hex(id, 16)).unwrap_or_else(new_span_id);
+    Some(format!("00-{}-{}-01", trace, span))
+}
+
+/// Run a closure with the given correlation ID.
+///
+/// # Arguments
+///
+/// * `id`: The correlation ID.
+/// * `f`: The closure to run.
+///
+/// returns: R The result of the closure.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{correlation, Level, Logger};
+/// logging::set_level(Level::ALL);
+/// correlation::with_id(correlation::new_id(), || {
+///     // logged with the correlation ID attached
+///     Logger::new("foo").info("handling request");
+/// });
+/// ```
+pub fn with_id<R>(id: impl ToString, f: impl FnOnce() -> R) -> R {
+    let _guard = set(id);
+    f()
+}
+
+/// A future that has a correlation ID set whenever it is polled. Created by [scope](scope).
+pub struct Scoped<F> {
+    id: String,
+    span: Option<String>,
+    future: F,
+}
+impl<F> Scoped<F> {
+    /// Also set a span ID while the future runs.
+    pub fn with_span(mut self, span: impl ToString) -> Self {
+        self.span = Some(span.to_string());
+        self
+    }
+}
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: the future is never moved out of the pinned struct.
+        let this = unsafe { self.get_unchecked_mut() };
+        let _guard = CorrelationGuard::replace(Some(this.id.clone()), this.span.clone());
+        unsafe { Pin::new_unchecked(&mut this.future) }.poll(cx)
+    }
+}
+
+/// Attach a correlation ID to a future, so it is set while the future runs, regardless of the thread polling it.
+/// The span ID of the current thread is kept for the future, unless another one is set with [with_span](Scoped::with_span).
+///
+/// # Arguments
+///
+/// * `id`: The correlation ID.
+/// * `future`: The future to run with the ID.
+///
+/// returns: Scoped<F>
+///
+/// # Examples
+///
+/// ```
+/// use logging::correlation;
+/// async fn handle_request() {
+///     assert!(correlation::current().is_some());
+/// }
+/// let future = correlation::scope(correlation::new_id(), handle_request());
+/// ```
+pub fn scope<F: Future>(id: impl ToString, future: F) -> Scoped<F> {
+    Scoped { id: id.to_string(), span: current_span(), future }
+}
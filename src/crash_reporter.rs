@@ -0,0 +1,162 @@
+use crate::formatter::format_time;
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerConfig, HandlerHealth, Level, LogLevel, Record};
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// A [Handler](Handler) keeping the most recent records and writing them to a crash file when the program crashes:
+/// when a [FATAL](Level::FATAL) record is logged, or a panic occurs once the [panic hook](CrashReporter::install_panic_hook) is installed.
+///
+/// Every crash file is named after the time of the crash and the process ID, e.g. `crash-2024-05-01T12-30-00.000Z-4242.txt`,
+/// and contains the reason, the backtrace, process metadata (arguments, working directory, host and versions) and the history of records, oldest first.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{CrashReporter, Level, Logger};
+/// let directory = std::env::temp_dir().join(format!("logging-crashes-{}", std::process::id()));
+/// let reporter = CrashReporter::new(&directory).with_history(100);
+/// reporter.install_panic_hook();
+/// let logger = Logger::new("app");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(reporter.clone());
+///
+/// logger.info("loading the world");
+/// logger.fatal("out of memory");
+///
+/// let report = std::fs::read_to_string(reporter.last_report().unwrap()).unwrap();
+/// assert!(report.contains("reason: FATAL (app): out of memory"));
+/// assert!(report.contains("INFO (app): loading the world"));
+/// # std::fs::remove_dir_all(&directory).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct CrashReporter {
+    directory: PathBuf,
+    capacity: usize,
+    level: LogLevel,
+    history: Arc<Mutex<VecDeque<Record>>>,
+    last_report: Arc<Mutex<Option<PathBuf>>>,
+    id: HandlerId,
+}
+impl CrashReporter {
+    /// Create a reporter writing crash files to the given directory, keeping the last 1000 records.
+    /// The directory is created when the first crash file is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory`: Where to write the crash files.
+    ///
+    /// returns: CrashReporter
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            capacity: 1000,
+            level: Level::FATAL,
+            history: Default::default(),
+            last_report: Default::default(),
+            id: HandlerId::new(),
+        }
+    }
+    /// Set how many of the most recent records are kept for the crash file.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+    /// Set the level at which records trigger a crash file. Defaults to [FATAL](Level::FATAL).
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+    /// Write a crash file when the program panics, before the previous panic hook (usually printing the panic) runs.
+    ///
+    /// returns: ()
+    pub fn install_panic_hook(&self) {
+        let reporter = self.clone();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = reporter.report(&format!("panic: {}", info), &Backtrace::force_capture());
+            previous(info)
+        }));
+    }
+    /// The path of the last crash file written by this reporter, if any.
+    pub fn last_report(&self) -> Option<PathBuf> {
+        self.last_report.lock().expect("Crash reporter is poisoned").clone()
+    }
+    /// Write a crash file right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason`: Why the program crashed.
+    /// * `backtrace`: Where the program crashed.
+    ///
+    /// returns: Result<PathBuf, Error> The path of the crash file.
+    pub fn report(&self, reason: &str, backtrace: &Backtrace) -> io::Result<PathBuf> {
+        let time = SystemTime::now();
+        let mut report = String::new();
+        let _ = writeln!(report, "crash report");
+        let _ = writeln!(report, "reason: {}", reason);
+        let _ = writeln!(report, "time: {}", format_time(time));
+        let _ = writeln!(report, "pid: {}", std::process::id());
+        let _ = writeln!(report, "host: {}", crate::system::hostname());
+        let args: Vec<String> = std::env::args_os().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        let _ = writeln!(report, "args: {:?}", args);
+        if let Ok(directory) = std::env::current_dir() {
+            let _ = writeln!(report, "cwd: {}", directory.display());
+        }
+        let _ = writeln!(report, "logging: {}", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(report, "rustc: {}", env!("LOGGING_RUSTC_VERSION"));
+        let _ = writeln!(report, "\nbacktrace:\n{}", backtrace);
+        // a poisoned history is still worth writing after a panic
+        let history = self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _ = writeln!(report, "\nhistory ({} records):", history.len());
+        for record in history.iter() {
+            let _ = writeln!(report, "{} {}", format_time(record.time), DefaultFormatter.format(record));
+        }
+        drop(history);
+
+        std::fs::create_dir_all(&self.directory)?;
+        let name = format!("crash-{}-{}.txt", format_time(time).replace(':', "-"), std::process::id());
+        let path = self.directory.join(name);
+        std::fs::write(&path, report)?;
+        *self.last_report.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(path.clone());
+        Ok(path)
+    }
+}
+impl Handler for CrashReporter {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        if self.capacity > 0 {
+            let mut history = self.history.lock().expect("Crash reporter is poisoned");
+            if history.len() >= self.capacity {
+                history.pop_front();
+            }
+            history.push_back(record.clone());
+        }
+        if record.level >= self.level {
+            let reason = DefaultFormatter.format(&Record { backtrace: None, ..record.clone() });
+            let result = match &record.backtrace {
+                Some(backtrace) => self.report(&reason, backtrace),
+                None => self.report(&reason, &Backtrace::force_capture()),
+            };
+            if let Err(err) = result {
+                self.id.record_failure(&self.describe(), err);
+            }
+        }
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("CrashReporter")
+            .with_setting("directory", self.directory.display())
+            .with_setting("history", self.capacity)
+            .with_setting("level", Level::name(self.level))
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
@@ -0,0 +1,124 @@
+use crate::formatter::format_time;
+use crate::stats::HandlerId;
+use crate::{Handler, HandlerHealth, Level, LogLevel, Record};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A [Handler](Handler) appending records to a CSV file, e.g. for analysis in a spreadsheet or with pandas.
+///
+/// The columns are `time`, `level`, `logger` and `message`, followed by one column per field given to
+/// [with_field_column](CsvHandler::with_field_column) and a final `fields` column with all other fields as `key=value` pairs.
+/// A header row is written when the file is empty. Values are quoted where necessary, so messages may contain commas, quotes and newlines.
+/// Like the [FileHandler](crate::FileHandler), the file can be reopened for log rotation.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, CsvHandler, Level, Logger};
+/// let path = std::env::temp_dir().join(format!("logging-csv-{}.csv", std::process::id()));
+/// let logger = Logger::new("shop");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(CsvHandler::new(&path).unwrap().with_field_column("user"));
+/// logger.log_fields("bought \"milk\", eggs", Level::INFO, fields! { user = "jane", items = 2 });
+///
+/// let content = std::fs::read_to_string(&path).unwrap();
+/// let mut lines = content.lines();
+/// assert_eq!(lines.next(), Some("time,level,logger,message,user,fields"));
+/// assert!(lines.next().unwrap().ends_with(",INFO,shop,\"bought \"\"milk\"\", eggs\",jane,items=2"));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct CsvHandler {
+    path: PathBuf,
+    columns: Vec<String>,
+    file: Mutex<File>,
+    id: HandlerId,
+}
+impl CsvHandler {
+    /// Create a handler appending to the file at the given path.
+    /// The file is created if it doesn't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to log to.
+    ///
+    /// returns: Result<CsvHandler, Error>
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open(&path)?;
+        Ok(Self { path, columns: Vec::new(), file: Mutex::new(file), id: HandlerId::new() })
+    }
+    /// Give a field its own column instead of listing it in the `fields` column.
+    /// The column stays empty for records without the field.
+    pub fn with_field_column(mut self, key: impl ToString) -> Self {
+        self.columns.push(key.to_string());
+        self
+    }
+    /// The path of the file this handler logs to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+    fn header(&self) -> String {
+        let mut header = vec!["time", "level", "logger", "message"];
+        header.extend(self.columns.iter().map(String::as_str));
+        header.push("fields");
+        csv_row(&header)
+    }
+}
+impl Handler for CsvHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let time = format_time(record.time);
+        let level = Level::name(record.level);
+        let columns: Vec<String> = self.columns.iter()
+            .map(|key| record.field(key).map(ToString::to_string).unwrap_or_default())
+            .collect();
+        let mut row = vec![time.as_str(), level.as_str(), record.logger.as_str(), record.message.as_str()];
+        row.extend(columns.iter().map(String::as_str));
+        let rest = record.fields.iter()
+            .filter(|(key, _)| !self.columns.contains(key))
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<String>>()
+            .join(" ");
+        row.push(&rest);
+        let mut file = self.file.lock().expect("CSV handler is poisoned");
+        let mut text = String::new();
+        if file.metadata().is_ok_and(|metadata| metadata.len() == 0) {
+            text.push_str(&self.header());
+        }
+        text.push_str(&csv_row(&row));
+        if let Err(err) = file.write_all(text.as_bytes()) {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn reopen(&self) {
+        let mut file = self.file.lock().expect("CSV handler is poisoned");
+        match Self::open(&self.path) {
+            Ok(reopened) => *file = reopened,
+            Err(err) => self.id.record_failure(&self.describe(), err),
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+
+/// Join values into a CSV row (RFC 4180), quoting values containing separators, quotes or line breaks.
+fn csv_row(values: &[&str]) -> String {
+    let mut row = values.iter()
+        .map(|value| if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
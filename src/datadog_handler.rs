@@ -0,0 +1,281 @@
+use crate::formatter::{format_time, json_fields, json_string};
+use crate::stats::HandlerId;
+use crate::{Handler, HandlerConfig, HandlerHealth, Level, LogLevel, Record};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{self, Write};
+use std::sync::{Arc, Condvar, Mutex, Once};
+use std::time::Duration;
+
+#[derive(Clone)]
+struct Intake {
+    agent: ureq::Agent,
+    url: String,
+    api_key: String,
+    service: Option<String>,
+    source: String,
+    host: String,
+    tags: Vec<String>,
+}
+impl Intake {
+    fn to_json(&self, record: &Record) -> String {
+        let status = match record.level {
+            level if level >= Level::FATAL => "alert",
+            level if level >= Level::CRITICAL => "critical",
+            level if level >= Level::ERROR => "error",
+            level if level >= Level::WARN => "warning",
+            level if level >= Level::SUCCESS => "notice",
+            level if level >= Level::INFO => "info",
+            _ => "debug",
+        };
+        let mut json = format!(
+            "{{\"message\":{},\"status\":\"{}\",\"date\":{},\"logger\":{{\"name\":{}}},\"ddsource\":{},\"hostname\":{}",
+            json_string(&record.message), status, json_string(&format_time(record.time)),
+            json_string(&record.logger), json_string(&self.source), json_string(&self.host),
+        );
+        if let Some(service) = &self.service {
+            json.push_str(&format!(",\"service\":{}", json_string(service)));
+        }
+        if !self.tags.is_empty() {
+            json.push_str(&format!(",\"ddtags\":{}", json_string(&self.tags.join(","))));
+        }
+        if !record.fields.is_empty() {
+            json.push_str(&format!(",\"fields\":{}", json_fields(&record.fields)));
+        }
+        if let Some(id) = &record.correlation_id {
+            json.push_str(&format!(",\"correlation_id\":{}", json_string(id)));
+        }
+        if let Some(id) = &record.span_id {
+            json.push_str(&format!(",\"span_id\":{}", json_string(id)));
+        }
+        if let Some(backtrace) = &record.backtrace {
+            json.push_str(&format!(",\"error\":{{\"stack\":{}}}", json_string(&backtrace.to_string())));
+        }
+        json.push('}');
+        json
+    }
+    /// Send a batch of records as one gzip compressed JSON array.
+    fn send(&self, records: &[Record]) -> io::Result<()> {
+        let json: Vec<String> = records.iter().map(|record| self.to_json(record)).collect();
+        let mut body = GzEncoder::new(Vec::new(), Compression::default());
+        body.write_all(format!("[{}]", json.join(",")).as_bytes())?;
+        let body = body.finish()?;
+        self.agent.post(&self.url)
+            .header("DD-API-KEY", &self.api_key)
+            .header("Content-Type", "application/json")
+            .header("Content-Encoding", "gzip")
+            .send(&body[..])
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+/// The records waiting to be sent, and a signal for the sender that a batch is full.
+#[derive(Default)]
+struct Batch {
+    records: Mutex<Vec<Record>>,
+    full: Condvar,
+}
+
+/// How many full batches may wait for the intake before further records are dropped.
+const MAX_PENDING_BATCHES: usize = 10;
+
+/// A [Handler](Handler) shipping records to the [Datadog](https://docs.datadoghq.com/logs/) HTTP log intake.
+///
+/// Records are collected and sent in gzip compressed batches by a background thread, when a batch is full, every few seconds and on [shutdown](crate::shutdown),
+/// so logging never waits for the intake.
+/// Records of batches that can't be sent, and records logged while ten full batches are still waiting to be sent, are dropped and counted in [stats](crate::stats).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{DatadogHandler, Level, Logger};
+/// use std::io::{Read, Write};
+/// use std::net::TcpListener;
+///
+/// // stands in for the Datadog intake
+/// let intake = TcpListener::bind("127.0.0.1:0").unwrap();
+/// let url = format!("http://{}/api/v2/logs", intake.local_addr().unwrap());
+/// let intake = std::thread::spawn(move || {
+///     let (mut connection, _) = intake.accept().unwrap();
+///     let mut request = Vec::new();
+///     let mut buffer = [0; 4096];
+///     while !request.windows(4).any(|end| end == b"\r\n\r\n") {
+///         let length = connection.read(&mut buffer).unwrap();
+///         request.extend_from_slice(&buffer[..length]);
+///     }
+///     connection.write_all(b"HTTP/1.1 202 Accepted\r\ncontent-length: 0\r\n\r\n").unwrap();
+///     String::from_utf8_lossy(&request).to_lowercase()
+/// });
+///
+/// let handler = DatadogHandler::new("my-api-key")
+///     .with_url(url)
+///     .with_service("shop")
+///     .with_tag("env:prod")
+///     .with_batch(1, std::time::Duration::from_secs(5));
+/// let logger = Logger::new("checkout");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(handler);
+/// logger.info("Order placed");
+///
+/// let request = intake.join().unwrap();
+/// assert!(request.starts_with("post /api/v2/logs"));
+/// assert!(request.contains("dd-api-key: my-api-key"));
+/// assert!(request.contains("content-encoding: gzip"));
+/// ```
+pub struct DatadogHandler {
+    intake: Arc<Intake>,
+    batch_size: usize,
+    interval: Duration,
+    batch: Arc<Batch>,
+    sender: Once,
+    id: HandlerId,
+}
+impl DatadogHandler {
+    /// Create a handler sending to the intake of the US1 site (`datadoghq.com`), in batches of up to 100 records at least every 5 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key`: The API key of your Datadog organization.
+    ///
+    /// returns: DatadogHandler
+    pub fn new(api_key: impl ToString) -> Self {
+        let config = ureq::Agent::config_builder().timeout_global(Some(Duration::from_secs(10))).build();
+        let intake = Intake {
+            agent: ureq::Agent::new_with_config(config),
+            url: "https://http-intake.logs.datadoghq.com/api/v2/logs".to_string(),
+            api_key: api_key.to_string(),
+            service: None,
+            source: "rust".to_string(),
+            host: crate::system::hostname().to_string(),
+            tags: Vec::new(),
+        };
+        Self {
+            intake: Arc::new(intake),
+            batch_size: 100,
+            interval: Duration::from_secs(5),
+            batch: Default::default(),
+            sender: Once::new(),
+            id: HandlerId::new(),
+        }
+    }
+    /// The intake to configure. A sender thread already started stops, as it can't reach the intake anymore,
+    /// and the next record starts one with the new settings.
+    fn intake(&mut self) -> &mut Intake {
+        self.sender = Once::new();
+        Arc::make_mut(&mut self.intake)
+    }
+    /// Send to the intake of another Datadog site, e.g. `datadoghq.eu` or `us5.datadoghq.com`.
+    pub fn with_site(mut self, site: &str) -> Self {
+        self.intake().url = format!("https://http-intake.logs.{}/api/v2/logs", site);
+        self
+    }
+    /// Send to another URL, e.g. a proxy.
+    pub fn with_url(mut self, url: impl ToString) -> Self {
+        self.intake().url = url.to_string();
+        self
+    }
+    /// Set the service the records belong to.
+    pub fn with_service(mut self, service: impl ToString) -> Self {
+        self.intake().service = Some(service.to_string());
+        self
+    }
+    /// Set the source of the records, used by Datadog to pick a processing pipeline. Defaults to `rust`.
+    pub fn with_source(mut self, source: impl ToString) -> Self {
+        self.intake().source = source.to_string();
+        self
+    }
+    /// Set the host the records come from. Defaults to the name of this machine.
+    pub fn with_host(mut self, host: impl ToString) -> Self {
+        self.intake().host = host.to_string();
+        self
+    }
+    /// Add a tag to all records, e.g. `env:prod`.
+    pub fn with_tag(mut self, tag: impl ToString) -> Self {
+        self.intake().tags.push(tag.to_string());
+        self
+    }
+    /// Set how many records are sent at once at most, and how long records may wait before they are sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`: The maximum number of records per batch.
+    /// * `interval`: How often to send the records collected so far.
+    ///
+    /// returns: DatadogHandler
+    pub fn with_batch(mut self, size: usize, interval: Duration) -> Self {
+        self.intake();
+        self.batch_size = size.max(1);
+        self.interval = interval;
+        self
+    }
+    /// Start the thread sending the batch when it is full or the interval has passed. It stops once the handler is gone.
+    fn start_sender(&self) {
+        let intake = Arc::downgrade(&self.intake);
+        let batch = Arc::downgrade(&self.batch);
+        let (size, interval) = (self.batch_size, self.interval);
+        let (id, description) = (self.id, self.describe());
+        std::thread::Builder::new()
+            .name("logging-datadog".to_string())
+            .spawn(move || {
+                while let (Some(intake), Some(batch)) = (intake.upgrade(), batch.upgrade()) {
+                    let records = batch.records.lock().expect("Datadog handler is poisoned");
+                    let (mut records, _) = batch.full.wait_timeout_while(records, interval, |records| records.len() < size)
+                        .expect("Datadog handler is poisoned");
+                    let length = records.len().min(size);
+                    let records: Vec<_> = records.drain(..length).collect();
+                    send(&intake, &records, id, &description);
+                }
+            })
+            .expect("failed to spawn the Datadog thread");
+    }
+}
+impl Handler for DatadogHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        self.sender.call_once(|| self.start_sender());
+        let mut records = self.batch.records.lock().expect("Datadog handler is poisoned");
+        if records.len() >= self.batch_size * MAX_PENDING_BATCHES {
+            drop(records);
+            self.id.record_dropped(&self.describe(), record);
+            return;
+        }
+        records.push(record.clone());
+        if records.len() >= self.batch_size {
+            self.batch.full.notify_one();
+        }
+    }
+    fn close(&self) {
+        let records = std::mem::take(&mut *self.batch.records.lock().expect("Datadog handler is poisoned"));
+        let description = self.describe();
+        for records in records.chunks(self.batch_size) {
+            send(&self.intake, records, self.id, &description);
+        }
+    }
+    fn describe(&self) -> HandlerConfig {
+        let mut config = HandlerConfig::new("DatadogHandler")
+            .with_setting("url", &self.intake.url)
+            .with_setting("batch_size", self.batch_size);
+        if let Some(service) = &self.intake.service {
+            config = config.with_setting("service", service);
+        }
+        config
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+
+fn send(intake: &Intake, records: &[Record], id: HandlerId, handler: &HandlerConfig) {
+    if records.is_empty() {
+        return;
+    }
+    if let Err(err) = intake.send(records) {
+        id.record_failure(handler, err);
+        for record in records {
+            id.record_dropped(handler, record);
+        }
+    }
+}
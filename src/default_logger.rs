@@ -0,0 +1,130 @@
+use crate::{Level, LogLevel, Logger};
+use std::cell::RefCell;
+use std::sync::RwLock;
+
+static DEFAULT_LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// The logger used by the free logging functions like [info](crate::info()). This is the root logger unless changed with [set_default_logger](set_default_logger).
+///
+/// returns: Logger
+pub fn default_logger() -> Logger {
+    DEFAULT_LOGGER.read().expect("Default logger is poisoned").clone().unwrap_or_else(|| Logger::new(""))
+}
+/// Set the logger used by the free logging functions like [info](crate::info()).
+///
+/// # Arguments
+///
+/// * `logger`: The new default logger.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// logging::set_default_logger(Logger::new("my_tool"));
+/// assert_eq!(logging::default_logger().name(), "my_tool");
+/// ```
+pub fn set_default_logger(logger: Logger) {
+    *DEFAULT_LOGGER.write().expect("Default logger is poisoned") = Some(logger);
+}
+
+/// Log a message through the [default logger](default_logger).
+///
+/// # Arguments
+///
+/// * `msg`: The message to be logged.
+/// * `level`: The level at which to log the message.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::Level;
+/// logging::set_level(Level::ALL);
+/// logging::log("Hello World", Level::INFO);
+/// logging::info("no Logger needed");
+/// ```
+pub fn log(msg: impl Into<String>, level: LogLevel) {
+    default_logger().log(msg, level)
+}
+/// Log a message at [Level::DEBUG](Level::DEBUG) through the [default logger](default_logger).
+pub fn debug(msg: impl Into<String>) {
+    log(msg, Level::DEBUG)
+}
+/// Log a message at [Level::INFO](Level::INFO) through the [default logger](default_logger).
+pub fn info(msg: impl Into<String>) {
+    log(msg, Level::INFO)
+}
+/// Log a message at [Level::SUCCESS](Level::SUCCESS) through the [default logger](default_logger).
+pub fn success(msg: impl Into<String>) {
+    log(msg, Level::SUCCESS)
+}
+/// Log a message at [Level::WARN](Level::WARN) through the [default logger](default_logger).
+pub fn warn(msg: impl Into<String>) {
+    log(msg, Level::WARN)
+}
+/// Log a message at [Level::ERROR](Level::ERROR) through the [default logger](default_logger).
+pub fn error(msg: impl Into<String>) {
+    log(msg, Level::ERROR)
+}
+/// Log a message at [Level::CRITICAL](Level::CRITICAL) through the [default logger](default_logger).
+pub fn critical(msg: impl Into<String>) {
+    log(msg, Level::CRITICAL)
+}
+/// Log a message at [Level::FATAL](Level::FATAL) through the [default logger](default_logger).
+pub fn fatal(msg: impl Into<String>) {
+    log(msg, Level::FATAL)
+}
+
+thread_local! {
+    static SCOPED_DEFAULT: RefCell<Option<Logger>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous thread-local default logger when dropped, even if the closure panics.
+struct ScopedDefaultGuard {
+    previous: Option<Logger>,
+}
+impl Drop for ScopedDefaultGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SCOPED_DEFAULT.with(|scoped| *scoped.borrow_mut() = previous);
+    }
+}
+
+/// Run a closure with the logger the macros without an explicit logger (like [info!](crate::info!)) log to overridden on the current thread.
+/// Without an override, they log to a logger named after the module they are called in.
+///
+/// # Arguments
+///
+/// * `logger`: The logger the macros should use.
+/// * `f`: The closure to run.
+///
+/// returns: R The result of the closure.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let task = Logger::new("worker::task_7");
+/// task.set_level(Level::ALL);
+/// task.add_handler(Arc::clone(&memory));
+///
+/// logging::with_default(task, || {
+///     logging::info!("processing");
+/// });
+/// assert_eq!(memory.records()[0].logger, "worker::task_7");
+/// ```
+pub fn with_default<R>(logger: Logger, f: impl FnOnce() -> R) -> R {
+    let previous = SCOPED_DEFAULT.with(|scoped| scoped.replace(Some(logger)));
+    let _guard = ScopedDefaultGuard { previous };
+    f()
+}
+/// The logger the macros without an explicit logger use: the thread-local override (see [with_default](with_default)) or the logger of the module.
+#[doc(hidden)]
+pub fn __macro_logger(module: &str) -> Logger {
+    SCOPED_DEFAULT.with(|scoped| scoped.borrow().clone()).unwrap_or_else(|| Logger::new(module))
+}
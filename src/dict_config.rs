@@ -0,0 +1,239 @@
+use crate::{ConfiguredHandler, ConsoleHandler, ConsoleStream, FileHandler, Handler, Level, LogLevel, PatternFormatter, RotatingFileHandler};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+fn invalid(message: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Configure the loggers from a JSON file structured like the dictionary of Python's `logging.config.dictConfig`.
+/// See [apply_dict_config](apply_dict_config) for what is supported.
+///
+/// # Arguments
+///
+/// * `path`: The path of the config file.
+///
+/// returns: Result<(), Error>
+pub fn load_dict_config(path: impl AsRef<Path>) -> io::Result<()> {
+    apply_dict_config(&std::fs::read_to_string(path)?)
+}
+
+/// Configure the loggers from a JSON document structured like the dictionary of Python's `logging.config.dictConfig`,
+/// for services ported from Python. Logger names are separated by `.` like in Python, so `app.db` configures the logger `app::db`.
+///
+/// Supported are:
+/// * `formatters` with a `format` using `%(asctime)s`, `%(name)s`, `%(levelname)s`, `%(levelno)s` and `%(message)s` (see [PatternFormatter](PatternFormatter)).
+/// * `handlers` of the classes `logging.StreamHandler` (with `stream`), `logging.FileHandler` (with `filename`), `logging.NullHandler`,
+///   `logging.handlers.RotatingFileHandler` (starting `filename.0`, `filename.1`, … at `maxBytes`)
+///   and `logging.handlers.TimedRotatingFileHandler` (starting `filename.<date>` every day), each with an optional `level` and `formatter`.
+/// * `loggers` and `root` with a `level`, `handlers` and `propagate`.
+/// * `disable_existing_loggers` (by default, like in Python, loggers created before and not configured are set to [NONE](Level::NONE))
+///   and `incremental` (only the levels are changed).
+///
+/// Levels are given by their Python names (`WARNING` is [WARN](Level::WARN), `NOTSET` keeps the level of the parent) or numbers.
+/// The handlers of the `root` (if given) replace the handlers of all loggers, like the `init_` presets. Nothing is changed if the config is invalid.
+///
+/// # Arguments
+///
+/// * `json`: The config.
+///
+/// returns: Result<(), Error>
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// let path = std::env::temp_dir().join(format!("logging-dict-config-{}.log", std::process::id()));
+/// let config = serde_json::json!({
+///     "version": 1,
+///     "formatters": {
+///         "simple": {"format": "%(levelname)s %(name)s: %(message)s"}
+///     },
+///     "handlers": {
+///         "file": {"class": "logging.FileHandler", "filename": path, "formatter": "simple", "level": "INFO"}
+///     },
+///     "loggers": {
+///         "app.db": {"level": "DEBUG", "handlers": ["file"], "propagate": false}
+///     },
+///     "root": {"level": "WARNING"}
+/// });
+/// logging::apply_dict_config(&config.to_string()).unwrap();
+///
+/// assert_eq!(Logger::new("app").level(), Level::WARN);
+/// let db = Logger::new("app::db");
+/// db.debug("connecting");
+/// db.info("connected");
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "INFO app::db: connected\n");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn apply_dict_config(json: &str) -> io::Result<()> {
+    let config: Value = serde_json::from_str(json).map_err(invalid)?;
+    match config.get("version").and_then(Value::as_u64) {
+        Some(1) => {}
+        Some(version) => return Err(invalid(format!("unsupported version {}", version))),
+        None => return Err(invalid("missing version")),
+    }
+    let empty = Map::new();
+    let section = |name: &str| match config.get(name) {
+        Some(section) => section.as_object().ok_or_else(|| invalid(format!("{} must be an object", name))),
+        None => Ok(&empty),
+    };
+    let incremental = config.get("incremental").and_then(Value::as_bool).unwrap_or(false);
+
+    let mut formatters: HashMap<&str, PatternFormatter> = HashMap::new();
+    for (name, formatter) in section("formatters")? {
+        let format = formatter.get("format").and_then(Value::as_str).unwrap_or("%(message)s");
+        formatters.insert(name, PatternFormatter::new(&translate_format(format)));
+    }
+    let mut handlers: HashMap<&str, Option<Arc<dyn Handler>>> = HashMap::new();
+    if !incremental {
+        for (name, handler) in section("handlers")? {
+            let handler = build_handler(handler, &formatters).map_err(|error| invalid(format!("handler {}: {}", name, error)))?;
+            handlers.insert(name, handler);
+        }
+    }
+    let lookup = |logger: &Value, name: &str| -> io::Result<Option<Vec<Arc<dyn Handler>>>> {
+        let Some(names) = logger.get("handlers") else { return Ok(None) };
+        let names = names.as_array().ok_or_else(|| invalid(format!("handlers of {} must be a list", name)))?;
+        let mut found = Vec::new();
+        for handler in names {
+            let handler = handler.as_str().unwrap_or_default();
+            match handlers.get(handler) {
+                Some(Some(handler)) => found.push(Arc::clone(handler)),
+                Some(None) => {}
+                None => return Err(invalid(format!("unknown handler '{}' in {}", handler, name))),
+            }
+        }
+        Ok(Some(found))
+    };
+
+    let root = config.get("root").unwrap_or(&Value::Null);
+    let root_level = match root.get("level") {
+        Some(level) => Some(parse_level(level, "root")?.unwrap_or(Level::ALL)),
+        None => None,
+    };
+    let root_handlers = match config.get("root") {
+        Some(root) if !incremental => Some(lookup(root, "root")?.unwrap_or_default()),
+        _ => None,
+    };
+    let mut loggers = Vec::new();
+    for (name, logger) in section("loggers")? {
+        let level = logger.get("level").map(|level| parse_level(level, name)).transpose()?.flatten();
+        let handlers = if incremental { None } else { lookup(logger, name)? };
+        let propagate = logger.get("propagate").and_then(Value::as_bool).unwrap_or(true);
+        loggers.push((name.replace('.', "::"), level, handlers, propagate));
+    }
+    loggers.sort_by_key(|(name, ..)| name.split("::").count());
+
+    let mut levels: Vec<(&str, LogLevel)> = Vec::new();
+    let existing: Vec<String> = crate::logger::all_loggers().iter()
+        .map(|logger| logger.read().expect("Logger is poisoned").name().to_string())
+        .collect();
+    if !incremental && config.get("disable_existing_loggers").and_then(Value::as_bool).unwrap_or(true) {
+        let configured = |name: &str| loggers.iter().any(|(configured, ..)| {
+            name == configured || name.strip_prefix(configured.as_str()).is_some_and(|rest| rest.starts_with("::"))
+        });
+        levels.extend(existing.iter().filter(|name| !name.is_empty() && !configured(name)).map(|name| (name.as_str(), Level::NONE)));
+    }
+    if let Some(level) = root_level {
+        levels.push(("", level));
+    }
+    levels.extend(loggers.iter().filter_map(|(name, level, ..)| Some((name.as_str(), (*level)?))));
+    if let Some(handlers) = root_handlers {
+        crate::logger::get_root().write().expect("Logger is poisoned").set_handlers(handlers);
+    }
+    crate::apply_levels(&levels);
+    for (name, _, handlers, propagate) in loggers {
+        let logger = crate::logger::get_logger(name);
+        let mut logger = logger.write().expect("Logger is poisoned");
+        match handlers {
+            Some(handlers) if !propagate => logger.set_handlers(handlers),
+            Some(handlers) => handlers.into_iter().for_each(|handler| logger.add_handler(handler)),
+            None if !propagate && !incremental => logger.set_handlers(Vec::new()),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parse a Python level name or number. `NOTSET` (or 0) gives `None`.
+fn parse_level(level: &Value, logger: &str) -> io::Result<Option<LogLevel>> {
+    let level = match level {
+        Value::Number(number) => match number.as_u64() {
+            Some(0) => None,
+            Some(10) => Some(Level::DEBUG),
+            Some(20) => Some(Level::INFO),
+            Some(30) => Some(Level::WARN),
+            Some(40) => Some(Level::ERROR),
+            Some(50) => Some(Level::CRITICAL),
+            _ => return Err(invalid(format!("unknown level {} for {}", number, logger))),
+        },
+        Value::String(name) => match name.to_ascii_uppercase().as_str() {
+            "NOTSET" => None,
+            "WARNING" => Some(Level::WARN),
+            _ => Some(Level::by_name(name).ok_or_else(|| invalid(format!("unknown level '{}' for {}", name, logger)))?),
+        },
+        _ => return Err(invalid(format!("invalid level for {}", logger))),
+    };
+    Ok(level)
+}
+
+/// Build a handler, or `None` for a `logging.NullHandler`.
+fn build_handler(handler: &Value, formatters: &HashMap<&str, PatternFormatter>) -> io::Result<Option<Arc<dyn Handler>>> {
+    let filename = || handler.get("filename").and_then(Value::as_str).ok_or_else(|| invalid("missing filename"));
+    let mut configured = match handler.get("class").and_then(Value::as_str) {
+        // like in Python, standard error unless standard output is asked for
+        Some("logging.StreamHandler") => match handler.get("stream").and_then(Value::as_str) {
+            Some("ext://sys.stdout") => ConfiguredHandler::new(ConsoleHandler::new()),
+            _ => ConfiguredHandler::new(ConsoleHandler::new().with_stream(ConsoleStream::Stderr)),
+        },
+        Some("logging.FileHandler") => ConfiguredHandler::new(FileHandler::new(filename()?)?),
+        Some("logging.handlers.RotatingFileHandler") => match handler.get("maxBytes").and_then(Value::as_u64).filter(|bytes| *bytes > 0) {
+            Some(bytes) => ConfiguredHandler::new(RotatingFileHandler::new(format!("{}.{{index}}", filename()?))?.with_max_size(bytes)),
+            None => ConfiguredHandler::new(FileHandler::new(filename()?)?),
+        },
+        Some("logging.handlers.TimedRotatingFileHandler") => ConfiguredHandler::new(RotatingFileHandler::new(format!("{}.{{date}}", filename()?))?),
+        Some("logging.NullHandler") => return Ok(None),
+        Some(class) => return Err(invalid(format!("unsupported class '{}'", class))),
+        None => return Err(invalid("missing class")),
+    };
+    if let Some(level) = handler.get("level") {
+        if let Some(level) = parse_level(level, "handler")? {
+            configured = configured.with_level(level);
+        }
+    }
+    if let Some(formatter) = handler.get("formatter") {
+        let name = formatter.as_str().unwrap_or_default();
+        let formatter = formatters.get(name).ok_or_else(|| invalid(format!("unknown formatter '{}'", name)))?;
+        configured = configured.with_formatter(formatter.clone());
+    }
+    Ok(Some(Arc::new(configured)))
+}
+
+/// Translate a Python `%`-style format into the placeholders of the [PatternFormatter](PatternFormatter).
+/// Widths and conversions (e.g. `%(levelname)-8s`) are dropped.
+fn translate_format(format: &str) -> String {
+    let mut translated = String::new();
+    let mut rest = format;
+    while let Some(start) = rest.find("%(") {
+        let Some(length) = rest[start..].find(')') else { break };
+        translated.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + length];
+        let after = &rest[start + length + 1..];
+        let conversion = after.find(|c: char| c.is_ascii_alphabetic()).map_or(after.len(), |index| index + 1);
+        match name {
+            "asctime" | "created" => translated.push_str("{time}"),
+            "name" => translated.push_str("{logger}"),
+            "levelname" => translated.push_str("{level}"),
+            "levelno" => translated.push_str("{level_number}"),
+            "message" => translated.push_str("{message}"),
+            _ => translated.push_str(&rest[start..start + length + 1 + conversion]),
+        }
+        rest = &after[conversion..];
+    }
+    translated.push_str(rest);
+    translated.replace("%%", "%")
+}
@@ -0,0 +1,152 @@
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerHealth, LogLevel, Record};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The length of an AES-GCM nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// A [Handler](Handler) that encrypts every record with AES-256-GCM before appending it to a file,
+/// so logs containing sensitive data can't be read by anyone without the key.
+///
+/// Every record is written as one line of hex: a random nonce followed by the encrypted record.
+/// Use [decrypt_log](decrypt_log) to read the file again.
+/// Like the [FileHandler](crate::FileHandler), the file can be reopened for log rotation.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{decrypt_log, EncryptedFileHandler, Level, Logger};
+/// let path = std::env::temp_dir().join(format!("logging-encrypted-{}.log", std::process::id()));
+/// let key = [7; 32];
+/// let logger = Logger::new("patients");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(EncryptedFileHandler::new(&path, key).unwrap());
+/// logger.info("admitted jane doe");
+///
+/// assert!(!std::fs::read_to_string(&path).unwrap().contains("jane"));
+/// let lines = decrypt_log(&path, key).unwrap();
+/// assert!(lines[0].ends_with("admitted jane doe"));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct EncryptedFileHandler {
+    path: PathBuf,
+    cipher: Aes256Gcm,
+    file: Mutex<File>,
+    id: HandlerId,
+}
+impl EncryptedFileHandler {
+    /// Create a handler appending to the file at the given path.
+    /// The file is created if it doesn't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to log to.
+    /// * `key`: The 256 bit key records are encrypted with.
+    ///
+    /// returns: Result<EncryptedFileHandler, Error>
+    pub fn new(path: impl AsRef<Path>, key: [u8; 32]) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open(&path)?;
+        Ok(Self {
+            path,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+            file: Mutex::new(file),
+            id: HandlerId::new(),
+        })
+    }
+    /// Like [new](EncryptedFileHandler::new), but reads the key as 64 hex digits from an environment variable,
+    /// so it doesn't have to be part of the code or configuration (e.g. generated with `openssl rand -hex 32`).
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to log to.
+    /// * `variable`: The environment variable containing the key.
+    ///
+    /// returns: Result<EncryptedFileHandler, Error> An error if the variable isn't set or isn't a valid key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::EncryptedFileHandler;
+    /// let path = std::env::temp_dir().join(format!("logging-encrypted-env-{}.log", std::process::id()));
+    /// assert!(EncryptedFileHandler::from_env(&path, "LOGGING_TEST_MISSING_KEY").is_err());
+    /// ```
+    pub fn from_env(path: impl AsRef<Path>, variable: &str) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+        let hex = std::env::var(variable).map_err(|_| invalid(format!("{} is not set", variable)))?;
+        let key = from_hex(hex.trim())
+            .and_then(|key| <[u8; 32]>::try_from(key).ok())
+            .ok_or_else(|| invalid(format!("{} is not a 256 bit key in hex", variable)))?;
+        Self::new(path, key)
+    }
+    /// The path of the file this handler logs to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+impl Handler for EncryptedFileHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let line = record.formatted.clone().unwrap_or_else(|| DefaultFormatter.format(record));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let Ok(encrypted) = self.cipher.encrypt(&nonce, line.as_bytes()) else {
+            self.id.record_failure(&self.describe(), "encryption failed");
+            return;
+        };
+        let hex: String = nonce.iter().chain(&encrypted).map(|byte| format!("{:02x}", byte)).collect();
+        let mut file = self.file.lock().expect("Encrypted file handler is poisoned");
+        if let Err(err) = writeln!(file, "{}", hex) {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn reopen(&self) {
+        let mut file = self.file.lock().expect("Encrypted file handler is poisoned");
+        match Self::open(&self.path) {
+            Ok(reopened) => *file = reopened,
+            Err(err) => self.id.record_failure(&self.describe(), err),
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+
+/// Decrypt a file written by an [EncryptedFileHandler](EncryptedFileHandler).
+///
+/// # Arguments
+///
+/// * `path`: The encrypted log file.
+/// * `key`: The key the file was written with.
+///
+/// returns: Result<Vec<String>, Error> The records, one per entry. An error if the file can't be read,
+/// the key is wrong or a line has been tampered with.
+pub fn decrypt_log(path: impl AsRef<Path>, key: [u8; 32]) -> io::Result<Vec<String>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let mut records = Vec::new();
+    for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("line {} can't be decrypted", number + 1));
+        let bytes = from_hex(line.trim()).filter(|bytes| bytes.len() >= NONCE_LEN).ok_or_else(invalid)?;
+        let (nonce, encrypted) = bytes.split_at(NONCE_LEN);
+        let decrypted = cipher.decrypt(Nonce::from_slice(nonce), encrypted).map_err(|_| invalid())?;
+        records.push(String::from_utf8(decrypted).map_err(|_| invalid())?);
+    }
+    Ok(records)
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok()).collect()
+}
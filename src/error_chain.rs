@@ -0,0 +1,42 @@
+use std::error::Error;
+
+/// Format an error together with all of its [sources](Error::source), one per line.
+/// Each cause is indented and prefixed with `caused by:`.
+///
+/// # Arguments
+///
+/// * `err`: The outermost error.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+///
+/// #[derive(Debug)]
+/// struct ConfigError(std::io::Error);
+/// impl fmt::Display for ConfigError {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "can't load config")
+///     }
+/// }
+/// impl std::error::Error for ConfigError {
+///     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+///         Some(&self.0)
+///     }
+/// }
+///
+/// let err = ConfigError(std::io::Error::other("disk on fire"));
+/// assert_eq!(logging::format_error_chain(&err), "can't load config\n  caused by: disk on fire");
+/// ```
+pub fn format_error_chain(err: &dyn Error) -> String {
+    let mut chain = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push_str("\n  caused by: ");
+        chain.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    chain
+}
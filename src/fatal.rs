@@ -0,0 +1,95 @@
+use crate::{DefaultFormatter, Formatter, Level, LogLevel, Record};
+use std::io::Write;
+use std::sync::RwLock;
+
+/// What happens after a record at [FATAL](Level::FATAL) or above has been logged, see [set_fatal_policy](set_fatal_policy).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FatalPolicy {
+    /// Keep running. This is the default.
+    #[default]
+    Continue,
+    /// Close all handlers (flushing buffered records) and exit the process with the given code.
+    Exit(i32),
+    /// Close all handlers (flushing buffered records) and abort the process, e.g. to get a core dump.
+    Abort,
+}
+
+static FATAL_POLICY: RwLock<FatalPolicy> = RwLock::new(FatalPolicy::Continue);
+
+/// Set what happens after a record at [FATAL](Level::FATAL) or above has been logged (and passed the level of its logger).
+/// By default, the program keeps running; with [Exit](FatalPolicy::Exit) or [Abort](FatalPolicy::Abort),
+/// all handlers are [closed](crate::shutdown) first, so no buffered record is lost.
+///
+/// # Arguments
+///
+/// * `policy`: The new policy.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```no_run
+/// use logging::{FatalPolicy, Level, Logger};
+/// logging::set_fatal_policy(FatalPolicy::Exit(70));
+/// logging::set_level(Level::ALL);
+/// // exits the process with code 70
+/// Logger::new("app").fatal("configuration is corrupt");
+/// ```
+pub fn set_fatal_policy(policy: FatalPolicy) {
+    *FATAL_POLICY.write().expect("Fatal policy is poisoned") = policy;
+}
+
+/// Apply the fatal policy after a record was logged at the given level.
+pub(crate) fn after_logged(level: LogLevel) {
+    if level < Level::FATAL {
+        return;
+    }
+    let policy = *FATAL_POLICY.read().expect("Fatal policy is poisoned");
+    match policy {
+        FatalPolicy::Continue => {}
+        FatalPolicy::Exit(code) => {
+            crate::shutdown();
+            std::process::exit(code)
+        }
+        FatalPolicy::Abort => {
+            crate::shutdown();
+            std::process::abort()
+        }
+    }
+}
+
+static STDERR_MIRROR_LEVEL: RwLock<Option<LogLevel>> = RwLock::new(None);
+
+/// Write every record at or above the given level to stderr as well, synchronously and before any handler sees it,
+/// so the most important records survive even if the handlers are misconfigured, failing or backed up.
+/// The records are written with the [DefaultFormatter](crate::DefaultFormatter) and aren't subject to handler filters.
+///
+/// Note that a [ConsoleHandler](crate::ConsoleHandler) prints these records too, so they may show up twice in a terminal.
+///
+/// # Arguments
+///
+/// * `level`: The minimum level to mirror, usually [CRITICAL](Level::CRITICAL) or [FATAL](Level::FATAL), or `None` to not mirror any (the default).
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// logging::set_stderr_mirror_level(Some(Level::CRITICAL));
+/// let logger = Logger::new("app");
+/// logger.set_level(Level::ALL);
+/// // written to stderr, whatever the handlers do with it
+/// logger.critical("database unreachable");
+/// # logging::set_stderr_mirror_level(None);
+/// ```
+pub fn set_stderr_mirror_level(level: Option<LogLevel>) {
+    *STDERR_MIRROR_LEVEL.write().expect("Stderr mirror level is poisoned") = level;
+}
+
+/// Write a record to stderr if it is at or above the mirror level.
+pub(crate) fn mirror(record: &Record) {
+    if STDERR_MIRROR_LEVEL.read().expect("Stderr mirror level is poisoned").is_some_and(|min| record.level >= min) {
+        let _ = writeln!(std::io::stderr().lock(), "{}", DefaultFormatter.format(record));
+    }
+}
@@ -0,0 +1,488 @@
+use std::any::{Any, TypeId};
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, RwLock};
+
+type Serializer = Arc<dyn Fn(&dyn Any) -> Option<FieldValue> + Send + Sync>;
+
+/// The value of a structured field of a [Record](crate::Record).
+///
+/// Values keep their type, so formats like JSON can write numbers and booleans as such, and filters can compare them.
+/// They are displayed as text, nested maps as `{key=value, key=value}`.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, ConfiguredHandler, FieldValue, Level, Logger, MemoryHandler, Record};
+/// use std::sync::Arc;
+///
+/// let slow = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("db");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(ConfiguredHandler::new(Arc::clone(&slow))
+///     .with_filter(|record: &Record| record.field("millis").and_then(FieldValue::as_f64).is_some_and(|millis| millis > 500.0)));
+///
+/// logger.log_fields("query", Level::INFO, fields! { millis = 12, cached = true });
+/// logger.log_fields("query", Level::INFO, fields! { millis = 830, cached = false });
+/// let records = slow.records();
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0].field("millis"), Some(&FieldValue::Int(830)));
+/// assert_eq!(records[0].field("cached").unwrap().to_string(), "false");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(untagged))]
+pub enum FieldValue {
+    /// A boolean.
+    Bool(bool),
+    /// An integer.
+    Int(i64),
+    /// A floating point number.
+    Float(f64),
+    /// Text.
+    Str(String),
+    /// Nested fields.
+    Map(Vec<(String, FieldValue)>),
+    /// A list of values, e.g. the causes of an [error](FieldValue::error).
+    List(Vec<FieldValue>),
+    /// A value computed only when the record is actually logged, see [lazy](FieldValue::lazy).
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_lazy", skip_deserializing))]
+    Lazy(LazyValue),
+}
+impl FieldValue {
+    /// A value that is computed only if the record it belongs to passes the level of the logger and is accepted by at least one handler,
+    /// so expensive values cost nothing while their level is disabled. [fields!](crate::fields) creates these for closures like `stats = || summary()`.
+    ///
+    /// The logger replaces lazy values with their result before handing the record to its handlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `compute`: Computes the value.
+    ///
+    /// returns: FieldValue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{fields, Level, Logger, MemoryHandler};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// static COMPUTED: AtomicUsize = AtomicUsize::new(0);
+    /// fn expensive_summary() -> String {
+    ///     COMPUTED.fetch_add(1, Ordering::Relaxed);
+    ///     "42 entries".to_string()
+    /// }
+    /// let memory = Arc::new(MemoryHandler::new(10));
+    /// let logger = Logger::new("cache");
+    /// logger.set_level(Level::INFO);
+    /// logger.add_handler(Arc::clone(&memory));
+    ///
+    /// logger.log_fields("cache state", Level::DEBUG, fields! { stats = || expensive_summary() });
+    /// assert_eq!(COMPUTED.load(Ordering::Relaxed), 0);
+    /// logger.log_fields("cache state", Level::INFO, fields! { stats = || expensive_summary() });
+    /// assert_eq!(COMPUTED.load(Ordering::Relaxed), 1);
+    /// assert_eq!(memory.records()[0].field("stats").unwrap(), "42 entries");
+    /// ```
+    pub fn lazy<V: Into<FieldValue>>(compute: impl Fn() -> V + Send + Sync + 'static) -> Self {
+        Self::Lazy(LazyValue(Arc::new(move || compute().into())))
+    }
+    /// An error as nested fields: `message` is the error itself and `chain` the list of its [sources](std::error::Error::source),
+    /// so structured formats like [JsonFormatter](crate::JsonFormatter) get machine-readable error details.
+    /// [fields!](crate::fields) creates these for values marked with `?`, like `err = ?error`.
+    ///
+    /// # Arguments
+    ///
+    /// * `err`: The error.
+    ///
+    /// returns: FieldValue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{fields, EcsFormatter, Formatter, JsonFormatter, Level, Record};
+    /// use std::fmt;
+    ///
+    /// #[derive(Debug)]
+    /// struct ConfigError(std::io::Error);
+    /// impl fmt::Display for ConfigError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         write!(f, "can't load config")
+    ///     }
+    /// }
+    /// impl std::error::Error for ConfigError {
+    ///     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    ///         Some(&self.0)
+    ///     }
+    /// }
+    ///
+    /// let err = ConfigError(std::io::Error::other("disk on fire"));
+    /// let mut record = Record::new(Level::ERROR, "Startup failed".to_string(), "app".to_string());
+    /// record.fields = fields! { err = ?err, path = "config.toml" };
+    /// assert_eq!(record.fields[0].1.to_string(), "{message=can't load config, chain=[disk on fire]}");
+    /// assert!(JsonFormatter.format(&record).contains(r#""err":{"message":"can't load config","chain":["disk on fire"]}"#));
+    /// let ecs: serde_json::Value = serde_json::from_str(&EcsFormatter.format(&record)).unwrap();
+    /// assert_eq!(ecs["error.message"], "can't load config");
+    /// assert_eq!(ecs["error.chain"][0], "disk on fire");
+    /// ```
+    pub fn error(err: &(dyn std::error::Error + '_)) -> Self {
+        let mut chain = Vec::new();
+        let mut source = err.source();
+        while let Some(cause) = source {
+            chain.push(Self::Str(cause.to_string()));
+            source = cause.source();
+        }
+        Self::Map(vec![("message".to_string(), Self::Str(err.to_string())), ("chain".to_string(), Self::List(chain))])
+    }
+    /// The message and the chain of causes, if this is an [error](FieldValue::error).
+    pub(crate) fn as_error(&self) -> Option<(&FieldValue, &[FieldValue])> {
+        match self.as_map()? {
+            [(message_key, message), (chain_key, FieldValue::List(chain))] if message_key == "message" && chain_key == "chain" => Some((message, chain)),
+            _ => None,
+        }
+    }
+    /// The value itself, computing it if it is [lazy](FieldValue::lazy), including lazy values nested in maps.
+    ///
+    /// returns: FieldValue
+    pub fn evaluate(self) -> Self {
+        match self {
+            Self::Lazy(lazy) => lazy.evaluate().evaluate(),
+            Self::Map(fields) => Self::Map(fields.into_iter().map(|(key, value)| (key, value.evaluate())).collect()),
+            Self::List(values) => Self::List(values.into_iter().map(Self::evaluate).collect()),
+            value => value,
+        }
+    }
+    /// Whether this value is [lazy](FieldValue::lazy) or contains a lazy value.
+    pub(crate) fn is_lazy(&self) -> bool {
+        match self {
+            Self::Lazy(_) => true,
+            Self::Map(fields) => fields.iter().any(|(_, value)| value.is_lazy()),
+            Self::List(values) => values.iter().any(Self::is_lazy),
+            _ => false,
+        }
+    }
+    /// The text, if this is a [Str](FieldValue::Str).
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+    /// The integer, if this is an [Int](FieldValue::Int).
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(number) => Some(*number),
+            _ => None,
+        }
+    }
+    /// The number, if this is an [Int](FieldValue::Int) or [Float](FieldValue::Float).
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(number) => Some(*number as f64),
+            Self::Float(number) => Some(*number),
+            _ => None,
+        }
+    }
+    /// The nested fields, if this is a [Map](FieldValue::Map).
+    pub fn as_map(&self) -> Option<&[(String, FieldValue)]> {
+        match self {
+            Self::Map(fields) => Some(fields),
+            _ => None,
+        }
+    }
+    /// The boolean, if this is a [Bool](FieldValue::Bool).
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Int(number) => write!(f, "{}", number),
+            Self::Float(number) => write!(f, "{}", number),
+            Self::Str(text) => f.write_str(text),
+            Self::Map(fields) => {
+                f.write_str("{")?;
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}={}", key, value)?;
+                }
+                f.write_str("}")
+            }
+            Self::List(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            Self::Lazy(lazy) => write!(f, "{}", lazy.evaluate()),
+        }
+    }
+}
+
+/// The computation of a [lazy](FieldValue::lazy) field value.
+/// Two lazy values are equal if they are the same computation.
+#[derive(Clone)]
+pub struct LazyValue(Arc<dyn Fn() -> FieldValue + Send + Sync>);
+impl LazyValue {
+    /// Compute the value.
+    ///
+    /// returns: FieldValue
+    pub fn evaluate(&self) -> FieldValue {
+        (self.0)()
+    }
+}
+impl std::fmt::Debug for LazyValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LazyValue(..)")
+    }
+}
+impl PartialEq for LazyValue {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+#[cfg(feature = "serde")]
+fn serialize_lazy<S: serde::Serializer>(lazy: &LazyValue, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&lazy.evaluate(), serializer)
+}
+
+impl PartialEq<str> for FieldValue {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+impl PartialEq<&str> for FieldValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+impl From<f32> for FieldValue {
+    fn from(value: f32) -> Self {
+        Self::Float(value as f64)
+    }
+}
+macro_rules! from_int {
+    ($($int:ty),*) => {
+        $(impl From<$int> for FieldValue {
+            fn from(value: $int) -> Self {
+                Self::Int(value as i64)
+            }
+        })*
+    };
+}
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+macro_rules! from_large_int {
+    ($($int:ty),*) => {
+        $(impl From<$int> for FieldValue {
+            /// Numbers too large for an `i64` are kept as text.
+            fn from(value: $int) -> Self {
+                i64::try_from(value).map_or_else(|_| Self::Str(value.to_string()), Self::Int)
+            }
+        })*
+    };
+}
+from_large_int!(u64, u128, i128, usize, isize);
+impl From<Vec<(String, FieldValue)>> for FieldValue {
+    fn from(fields: Vec<(String, FieldValue)>) -> Self {
+        Self::Map(fields)
+    }
+}
+impl From<Vec<FieldValue>> for FieldValue {
+    fn from(values: Vec<FieldValue>) -> Self {
+        Self::List(values)
+    }
+}
+
+/// Turns a value into a typed [FieldValue](FieldValue) in [fields!](crate::fields).
+/// Implemented for numbers, booleans, text and nested fields; values of other types are converted with [Display] instead.
+/// Implement it for your own types to log them as typed values.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, FieldValue, ToFieldValue};
+/// struct Money { cents: i64 }
+/// impl ToFieldValue for Money {
+///     fn to_field_value(&self) -> FieldValue {
+///         FieldValue::Float(self.cents as f64 / 100.0)
+///     }
+/// }
+/// assert_eq!(fields! { price = Money { cents: 1999 } }[0].1, FieldValue::Float(19.99));
+/// ```
+pub trait ToFieldValue {
+    /// Convert the value.
+    ///
+    /// returns: FieldValue
+    fn to_field_value(&self) -> FieldValue;
+}
+impl<T: ToFieldValue + ?Sized> ToFieldValue for &T {
+    fn to_field_value(&self) -> FieldValue {
+        (**self).to_field_value()
+    }
+}
+impl ToFieldValue for FieldValue {
+    fn to_field_value(&self) -> FieldValue {
+        self.clone()
+    }
+}
+impl ToFieldValue for str {
+    fn to_field_value(&self) -> FieldValue {
+        self.into()
+    }
+}
+impl ToFieldValue for String {
+    fn to_field_value(&self) -> FieldValue {
+        self.as_str().into()
+    }
+}
+impl ToFieldValue for Vec<(String, FieldValue)> {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Map(self.clone())
+    }
+}
+impl ToFieldValue for Vec<FieldValue> {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::List(self.clone())
+    }
+}
+macro_rules! to_field_value {
+    ($($type:ty),*) => {
+        $(impl ToFieldValue for $type {
+            fn to_field_value(&self) -> FieldValue {
+                (*self).into()
+            }
+        })*
+    };
+}
+to_field_value!(bool, f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// The serializers set with [set_field_serializer](set_field_serializer), by the type they serialize.
+static SERIALIZERS: RwLock<Vec<(TypeId, Serializer)>> = RwLock::new(Vec::new());
+
+/// Set how values of a type are turned into field values by [fields!](crate::fields), for types that don't implement [ToFieldValue](ToFieldValue),
+/// e.g. durations, IDs or decimals of other crates. This way they don't have to be converted at every place they are logged.
+///
+/// A serializer takes precedence over [Display] and [Debug]. Values of other types without a serializer are written with [Display] or [Debug],
+/// or as `<TypeName>` if they implement neither.
+/// Setting a serializer for a type again replaces the previous one.
+///
+/// # Arguments
+///
+/// * `serializer`: Turns a value into the value of the field.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, FieldValue};
+/// use std::time::Duration;
+///
+/// assert_eq!(fields! { took = Duration::from_millis(1500) }[0].1, "1.5s");
+/// logging::set_field_serializer(|duration: &Duration| duration.as_millis());
+/// assert_eq!(fields! { took = Duration::from_millis(1500) }[0].1, FieldValue::Int(1500));
+///
+/// // also for types implementing Display
+/// struct OrderId(u64);
+/// impl std::fmt::Display for OrderId {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "order-{}", self.0)
+///     }
+/// }
+/// assert_eq!(fields! { order = OrderId(7) }[0].1, "order-7");
+/// logging::set_field_serializer(|order: &OrderId| order.0);
+/// assert_eq!(fields! { order = OrderId(7) }[0].1, FieldValue::Int(7));
+/// ```
+pub fn set_field_serializer<T: Any, V: Into<FieldValue>>(serializer: impl Fn(&T) -> V + Send + Sync + 'static) {
+    let serializer: Serializer = Arc::new(move |value: &dyn Any| value.downcast_ref().map(|value| serializer(value).into()));
+    let mut serializers = SERIALIZERS.write().expect("Field serializers are poisoned");
+    serializers.retain(|(type_id, _)| *type_id != TypeId::of::<T>());
+    serializers.push((TypeId::of::<T>(), serializer));
+}
+/// Serialize a value with the serializer set for its type, if there is one.
+fn serialize<T: Any>(value: &T) -> Option<FieldValue> {
+    let serializers = SERIALIZERS.read().expect("Field serializers are poisoned");
+    let (_, serializer) = serializers.iter().find(|(type_id, _)| *type_id == TypeId::of::<T>())?;
+    serializer(value)
+}
+
+/// Picks how [fields!](crate::fields) converts a value at compile time: with `ToFieldValue` if possible, otherwise with a serializer or `Display`
+/// (only `Display` for borrowing types, which can't have a serializer), otherwise with a serializer or `Debug`, otherwise with a serializer only.
+#[doc(hidden)]
+pub mod __private {
+    use super::{FieldValue, ToFieldValue};
+    use std::any::Any;
+    use std::fmt::{Debug, Display};
+
+    pub struct Field<'a, T: ?Sized>(pub &'a T);
+
+    pub trait ViaTyped {
+        fn __field_value(&self) -> FieldValue;
+    }
+    impl<T: ToFieldValue + ?Sized> ViaTyped for &&&&Field<'_, T> {
+        fn __field_value(&self) -> FieldValue {
+            self.0.to_field_value()
+        }
+    }
+    pub trait ViaSerializerOrDisplay {
+        fn __field_value(&self) -> FieldValue;
+    }
+    impl<T: Any + Display> ViaSerializerOrDisplay for &&&Field<'_, T> {
+        fn __field_value(&self) -> FieldValue {
+            super::serialize(self.0).unwrap_or_else(|| FieldValue::Str(self.0.to_string()))
+        }
+    }
+    pub trait ViaDisplay {
+        fn __field_value(&self) -> FieldValue;
+    }
+    impl<T: ToString + ?Sized> ViaDisplay for &&Field<'_, T> {
+        fn __field_value(&self) -> FieldValue {
+            FieldValue::Str(self.0.to_string())
+        }
+    }
+    pub trait ViaDebug {
+        fn __field_value(&self) -> FieldValue;
+    }
+    impl<T: Any + Debug> ViaDebug for &Field<'_, T> {
+        fn __field_value(&self) -> FieldValue {
+            super::serialize(self.0).unwrap_or_else(|| FieldValue::Str(format!("{:?}", self.0)))
+        }
+    }
+    pub trait ViaSerializer {
+        fn __field_value(&self) -> FieldValue;
+    }
+    impl<T: Any> ViaSerializer for Field<'_, T> {
+        fn __field_value(&self) -> FieldValue {
+            super::serialize(self.0).unwrap_or_else(|| FieldValue::Str(format!("<{}>", crate::config::short_type_name::<T>())))
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerConfig, HandlerHealth, Level, LogLevel, Record};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+type Text = Arc<dyn Fn() -> String + Send + Sync>;
+
+/// How many bytes are buffered at most before they are written, regardless of the flush interval.
+const MAX_BUFFER: usize = 64 * 1024;
+
+/// When a [FileHandler](FileHandler) asks the operating system to write its data to the disk (`fsync`),
+/// so records survive a crash of the whole machine. Syncing is slow, so this trades durability against throughput.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Leave it to the operating system (the default).
+    #[default]
+    Never,
+    /// Sync after every [ERROR](Level::ERROR) or more severe record, so the records leading up to a failure aren't lost.
+    OnError,
+    /// Sync after every N records.
+    EveryRecords(u32),
+}
+
+struct Output {
+    file: File,
+    /// Records not written yet, if the handler is buffered.
+    buffer: Vec<u8>,
+    /// Records written since the last sync.
+    unsynced: u32,
+    /// Whether to lock the file for every write.
+    lock: bool,
+    /// Whether the footer has been written, see [close](Handler::close).
+    closed: bool,
+    /// The flush thread currently in charge. Threads of earlier [flush intervals](FileHandler::with_flush_interval) stop when they see a newer one.
+    flusher: u64,
+}
+impl Output {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if !self.lock {
+            return self.file.write_all(bytes);
+        }
+        self.file.lock()?;
+        let written = self.file.write_all(bytes);
+        self.file.unlock()?;
+        written
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let buffer = std::mem::take(&mut self.buffer);
+        self.write(&buffer)
+    }
+}
+
+/// A [Handler](Handler) that appends messages to a file.
+/// The file can be reopened (see [Handler::reopen](Handler::reopen)), so it works together with external log rotation.
+///
+/// Every record is written with a single append, so records from several processes sharing a file don't interleave
+/// on local file systems. Use [shared](FileHandler::shared) to additionally lock the file for each write.
+/// Writing can be made cheaper with a [flush interval](FileHandler::with_flush_interval) and more durable with a [sync policy](FileHandler::with_sync_policy).
+pub struct FileHandler {
+    path: PathBuf,
+    output: Arc<Mutex<Output>>,
+    buffered: bool,
+    sync: SyncPolicy,
+    header: Option<Text>,
+    footer: Option<Text>,
+    id: HandlerId,
+}
+impl FileHandler {
+    /// Create a handler appending to the file at the given path.
+    /// The file is created if it doesn't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to log to.
+    ///
+    /// returns: Result<FileHandler, Error>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{FileHandler, Level, Logger};
+    /// let path = std::env::temp_dir().join(format!("logging-file-{}.log", std::process::id()));
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(FileHandler::new(&path).unwrap());
+    /// logger.info("Hello World");
+    /// assert!(std::fs::read_to_string(&path).unwrap().contains("Hello World"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open(&path)?;
+        Ok(Self {
+            path,
+            output: Arc::new(Mutex::new(Output { file, buffer: Vec::new(), unsynced: 0, lock: false, closed: false, flusher: 0 })),
+            buffered: false,
+            sync: SyncPolicy::Never,
+            header: None,
+            footer: None,
+            id: HandlerId::new(),
+        })
+    }
+    /// Take an exclusive advisory lock on the file for every write,
+    /// so several processes can safely share one log file, even on file systems without atomic appends (e.g. NFS).
+    /// Only processes that lock the file as well are kept out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{FileHandler, Level, Logger};
+    /// let path = std::env::temp_dir().join(format!("logging-shared-{}.log", std::process::id()));
+    /// let logger = Logger::new("worker");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(FileHandler::new(&path).unwrap().shared());
+    /// logger.info("Hello World");
+    /// assert!(std::fs::read_to_string(&path).unwrap().contains("Hello World"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn shared(self) -> Self {
+        self.output.lock().expect("File handler is poisoned").lock = true;
+        self
+    }
+    /// Write a header every time the file is opened, i.e. right away and after every [reopen](Handler::reopen),
+    /// so every log file describes where it came from.
+    ///
+    /// # Arguments
+    ///
+    /// * `header`: Creates the header. A missing final newline is added.
+    ///
+    /// returns: FileHandler
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{FileHandler, Level, Logger};
+    /// let path = std::env::temp_dir().join(format!("logging-header-{}.log", std::process::id()));
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(FileHandler::new(&path).unwrap()
+    ///     .with_header(|| format!("# my_app started as pid {}", std::process::id()))
+    ///     .with_footer(|| "# my_app stopped".to_string()));
+    /// logger.info("Hello World");
+    /// logging::shutdown();
+    ///
+    /// let content = std::fs::read_to_string(&path).unwrap();
+    /// assert!(content.starts_with("# my_app started as pid"));
+    /// assert!(content.ends_with("# my_app stopped\n"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn with_header(mut self, header: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        self.header = Some(Arc::new(header));
+        let mut output = self.output.lock().expect("File handler is poisoned");
+        self.write_text(&mut output, self.header.as_ref());
+        drop(output);
+        self
+    }
+    /// Write a header with the application name and version, the process ID and the start time, e.g.
+    /// `# my_app 1.2.0 (pid 4242) started at 2024-05-01T12:30:00.000Z`. See [with_header](FileHandler::with_header).
+    ///
+    /// # Arguments
+    ///
+    /// * `app_name`: The name of the application.
+    /// * `version`: The version of the application, e.g. `env!("CARGO_PKG_VERSION")`.
+    ///
+    /// returns: FileHandler
+    pub fn with_default_header(self, app_name: impl ToString, version: impl ToString) -> Self {
+        let (app_name, version) = (app_name.to_string(), version.to_string());
+        self.with_header(move || format!(
+            "# {} {} (pid {}) started at {}",
+            app_name, version, std::process::id(), crate::formatter::format_time(SystemTime::now()),
+        ))
+    }
+    /// Write a footer when the handler is closed on a clean shutdown (see [shutdown](crate::shutdown)),
+    /// or when it is dropped without having been closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `footer`: Creates the footer. A missing final newline is added.
+    ///
+    /// returns: FileHandler
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{FileHandler, Handler, Level};
+    /// let path = std::env::temp_dir().join(format!("logging-footer-{}.log", std::process::id()));
+    /// let handler = FileHandler::new(&path).unwrap().with_footer(|| "# stopped".to_string());
+    /// handler.log(Level::INFO, "Hello World".to_string(), "foo".to_string());
+    /// handler.close();
+    /// drop(handler);
+    /// // written once, by close
+    /// assert!(std::fs::read_to_string(&path).unwrap().ends_with("Hello World\n# stopped\n"));
+    ///
+    /// let handler = FileHandler::new(&path).unwrap().with_footer(|| "# dropped".to_string());
+    /// drop(handler);
+    /// assert!(std::fs::read_to_string(&path).unwrap().ends_with("# stopped\n# dropped\n"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn with_footer(mut self, footer: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        self.footer = Some(Arc::new(footer));
+        self
+    }
+    /// Buffer records in memory and write them at most once per interval, instead of writing every record on its own.
+    /// This is a lot cheaper when logging many records, but records still in the buffer are lost if the program crashes.
+    /// The buffer is also written when it gets large, before a sync (see [with_sync_policy](FileHandler::with_sync_policy)),
+    /// on [reopen](Handler::reopen), on [shutdown](crate::shutdown) and when the handler is dropped.
+    /// Setting another interval replaces the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval`: How long records may stay in the buffer, e.g. 500 milliseconds.
+    ///
+    /// returns: FileHandler
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{FileHandler, Handler, Level, Logger, SyncPolicy};
+    /// use std::time::Duration;
+    /// let path = std::env::temp_dir().join(format!("logging-buffered-{}.log", std::process::id()));
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(FileHandler::new(&path).unwrap()
+    ///     .with_flush_interval(Duration::from_secs(60))
+    ///     .with_sync_policy(SyncPolicy::OnError));
+    /// logger.info("Hello World");
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+    /// // errors are synced, which writes the buffer first
+    /// logger.error("Something failed");
+    /// assert!(std::fs::read_to_string(&path).unwrap().contains("Hello World"));
+    ///
+    /// let handler = FileHandler::new(&path).unwrap().with_flush_interval(Duration::from_secs(60));
+    /// handler.log(Level::INFO, "Goodbye".to_string(), "foo".to_string());
+    /// drop(handler);
+    /// assert!(std::fs::read_to_string(&path).unwrap().contains("Goodbye"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.buffered = true;
+        let flusher = {
+            let mut output = self.output.lock().expect("File handler is poisoned");
+            output.flusher += 1;
+            output.flusher
+        };
+        let output = Arc::downgrade(&self.output);
+        let (id, description) = (self.id, self.describe());
+        std::thread::Builder::new()
+            .name("logging-flush".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(interval);
+                // stop once the handler is gone or another interval was set
+                let Some(output) = output.upgrade() else { break };
+                let mut output = output.lock().expect("File handler is poisoned");
+                if output.flusher != flusher {
+                    break;
+                }
+                let flushed = output.flush();
+                drop(output);
+                if let Err(err) = flushed {
+                    id.record_failure(&description, err);
+                }
+            })
+            .expect("failed to spawn the flush thread");
+        self
+    }
+    /// Set when the file is synced to the disk. See [SyncPolicy](SyncPolicy).
+    ///
+    /// # Arguments
+    ///
+    /// * `policy`: When to sync.
+    ///
+    /// returns: FileHandler
+    pub fn with_sync_policy(mut self, policy: SyncPolicy) -> Self {
+        self.sync = policy;
+        self
+    }
+    /// The path of the file this handler logs to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+    fn write_text(&self, output: &mut Output, text: Option<&Text>) {
+        let Some(text) = text else { return };
+        let mut text = text();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        let written = output.flush().and_then(|_| output.write(text.as_bytes()));
+        if let Err(err) = written {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    /// Write the footer and everything buffered, once.
+    fn finish(&self, output: &mut Output) {
+        if output.closed {
+            return;
+        }
+        output.closed = true;
+        self.write_text(output, self.footer.as_ref());
+        let mut flushed = output.flush().and_then(|_| output.file.flush());
+        if self.sync != SyncPolicy::Never {
+            flushed = flushed.and_then(|_| output.file.sync_all());
+        }
+        if let Err(err) = flushed {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn needs_sync(&self, output: &mut Output, level: LogLevel) -> bool {
+        match self.sync {
+            SyncPolicy::Never => false,
+            SyncPolicy::OnError => level >= Level::ERROR,
+            SyncPolicy::EveryRecords(records) => {
+                output.unsynced += 1;
+                output.unsynced >= records
+            }
+        }
+    }
+}
+
+impl Handler for FileHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut line = record.formatted.clone().unwrap_or_else(|| DefaultFormatter.format(record));
+        line.push('\n');
+        let mut output = self.output.lock().expect("File handler is poisoned");
+        let written = if self.buffered {
+            output.buffer.extend_from_slice(line.as_bytes());
+            if output.buffer.len() >= MAX_BUFFER { output.flush() } else { Ok(()) }
+        } else {
+            output.write(line.as_bytes())
+        };
+        let synced = if self.needs_sync(&mut output, record.level) {
+            output.unsynced = 0;
+            output.flush().and_then(|_| output.file.sync_data())
+        } else {
+            Ok(())
+        };
+        if let Err(err) = written.and(synced) {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn reopen(&self) {
+        let mut output = self.output.lock().expect("File handler is poisoned");
+        if let Err(err) = output.flush() {
+            self.id.record_failure(&self.describe(), err);
+        }
+        match Self::open(&self.path) {
+            Ok(reopened) => {
+                output.file = reopened;
+                self.write_text(&mut output, self.header.as_ref());
+            }
+            Err(err) => self.id.record_failure(&self.describe(), err),
+        }
+    }
+    fn close(&self) {
+        let mut output = self.output.lock().expect("File handler is poisoned");
+        self.finish(&mut output);
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("FileHandler").with_setting("path", self.path.display())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+impl Drop for FileHandler {
+    fn drop(&mut self) {
+        // write the footer and what is still buffered, the flush thread stops without doing so
+        let mut output = self.output.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.finish(&mut output);
+        if let Err(err) = output.flush() {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use crate::Record;
+
+/// Decides whether a record should be logged.
+///
+/// Any `Fn(&Record) -> bool` is a filter as well.
+pub trait Filter: Send + Sync {
+    /// Check a record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: The record about to be logged.
+    ///
+    /// returns: bool `true` if the record should be logged.
+    fn filter(&self, record: &Record) -> bool;
+}
+impl<F: Fn(&Record) -> bool + Send + Sync> Filter for F {
+    fn filter(&self, record: &Record) -> bool {
+        self(record)
+    }
+}
+
+/// A [Filter](Filter) on the module a record was logged in (see [Source](crate::Source)), independent of the logger,
+/// e.g. to silence a chatty module when all modules share one logger.
+///
+/// Modules are matched with their submodules, and the most specific rule wins, so a module can be allowed inside a denied one.
+/// Records without a source, i.e. ones not logged through the logging macros, are always logged.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{ConfiguredHandler, Level, Logger, MemoryHandler, ModuleFilter};
+/// use std::sync::Arc;
+///
+/// mod db {
+///     pub fn query(logger: &logging::Logger) {
+///         logging::info!(logger => "SELECT * FROM users");
+///     }
+///     pub mod migrations {
+///         pub fn run(logger: &logging::Logger) {
+///             logging::info!(logger => "migrated to version 3");
+///         }
+///     }
+/// }
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let filter = ModuleFilter::new()
+///     .deny(format!("{}::db", module_path!()))
+///     .allow(format!("{}::db::migrations", module_path!()));
+/// let logger = Logger::new("app");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(ConfiguredHandler::new(Arc::clone(&memory)).with_filter(filter));
+///
+/// db::query(&logger);
+/// db::migrations::run(&logger);
+/// logging::info!(logger => "started");
+///
+/// let messages: Vec<String> = memory.records().into_iter().map(|record| record.message).collect();
+/// assert_eq!(messages, ["migrated to version 3", "started"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ModuleFilter {
+    rules: Vec<(String, bool)>,
+}
+impl ModuleFilter {
+    /// Create a filter allowing all modules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Allow records from a module and its submodules.
+    ///
+    /// # Arguments
+    ///
+    /// * `module`: The full path of the module, e.g. `my_crate::db`.
+    ///
+    /// returns: ModuleFilter
+    pub fn allow(mut self, module: impl ToString) -> Self {
+        self.rules.push((module.to_string(), true));
+        self
+    }
+    /// Drop records from a module and its submodules.
+    ///
+    /// # Arguments
+    ///
+    /// * `module`: The full path of the module, e.g. `my_crate::db`.
+    ///
+    /// returns: ModuleFilter
+    pub fn deny(mut self, module: impl ToString) -> Self {
+        self.rules.push((module.to_string(), false));
+        self
+    }
+}
+impl Filter for ModuleFilter {
+    fn filter(&self, record: &Record) -> bool {
+        let Some(source) = &record.source else { return true };
+        self.rules.iter()
+            .filter(|(module, _)| {
+                source.module_path.strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .is_none_or(|(_, allowed)| *allowed)
+    }
+}
+
+/// A [Filter](Filter) on the name of the logger a record was logged with, using the glob patterns of [set_level_for](crate::set_level_for),
+/// e.g. to keep the handshakes of all network protocols out of one handler.
+///
+/// The rule set last of all matching ones wins. Loggers not matching any rule are logged.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{ConfiguredHandler, Level, Logger, LoggerFilter, MemoryHandler};
+/// use std::sync::Arc;
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let filter = LoggerFilter::new()
+///     .deny("net::*::handshake")
+///     .allow("net::tls::handshake");
+/// let net = Logger::new("net");
+/// net.set_level(Level::ALL);
+/// net.add_handler(ConfiguredHandler::new(Arc::clone(&memory)).with_filter(filter));
+///
+/// Logger::new("net::tcp::handshake").info("SYN");
+/// Logger::new("net::tls::handshake").info("ClientHello");
+/// Logger::new("net::tcp").info("connected");
+///
+/// let messages: Vec<String> = memory.records().into_iter().map(|record| record.message).collect();
+/// assert_eq!(messages, ["ClientHello", "connected"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LoggerFilter {
+    rules: Vec<(String, bool)>,
+}
+impl LoggerFilter {
+    /// Create a filter allowing all loggers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Allow records of the loggers matching a pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern of the logger names, e.g. `net::*::handshake`.
+    ///
+    /// returns: LoggerFilter
+    pub fn allow(mut self, pattern: impl ToString) -> Self {
+        self.rules.push((pattern.to_string(), true));
+        self
+    }
+    /// Drop records of the loggers matching a pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern of the logger names, e.g. `net::*::handshake`.
+    ///
+    /// returns: LoggerFilter
+    pub fn deny(mut self, pattern: impl ToString) -> Self {
+        self.rules.push((pattern.to_string(), false));
+        self
+    }
+}
+impl Filter for LoggerFilter {
+    fn filter(&self, record: &Record) -> bool {
+        self.rules.iter()
+            .rfind(|(pattern, _)| crate::logger::matches_pattern(pattern, &record.logger))
+            .is_none_or(|(_, allowed)| *allowed)
+    }
+}
+
+/// A [Filter](Filter) on the audience [tags](Record::tags) of a record, to route records independent of their level,
+/// e.g. only user-facing records to a TUI while a debug file gets everything.
+///
+/// Records with a denied tag are dropped. If any tags are allowed, a record needs at least one of them, so untagged records are dropped as well.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{info, ConfiguredHandler, Level, Logger, MemoryHandler, TagFilter};
+/// use std::sync::Arc;
+///
+/// let tui = Arc::new(MemoryHandler::new(10));
+/// let debug_file = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("editor");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(ConfiguredHandler::new(Arc::clone(&tui)).with_level(Level::INFO).with_filter(TagFilter::new().allow("user-facing")));
+/// logger.add_handler(Arc::clone(&debug_file));
+///
+/// info!(logger => tags: ["user-facing"], "saved {}", "notes.txt");
+/// info!(logger => "flushed 3 buffers");
+/// logging::debug!(logger => tags: ["user-facing"], "cursor at 3:14");
+///
+/// let shown: Vec<String> = tui.records().into_iter().map(|record| record.message).collect();
+/// assert_eq!(shown, ["saved notes.txt"]);
+/// assert_eq!(debug_file.records().len(), 3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TagFilter {
+    allowed: Vec<String>,
+    denied: Vec<String>,
+}
+impl TagFilter {
+    /// Create a filter allowing all records.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Allow records with a tag. Once a tag is allowed, records need one of the allowed tags.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag, e.g. `user-facing`.
+    ///
+    /// returns: TagFilter
+    pub fn allow(mut self, tag: impl ToString) -> Self {
+        self.allowed.push(tag.to_string());
+        self
+    }
+    /// Drop records with a tag, even if they have an allowed one as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag, e.g. `internal`.
+    ///
+    /// returns: TagFilter
+    pub fn deny(mut self, tag: impl ToString) -> Self {
+        self.denied.push(tag.to_string());
+        self
+    }
+}
+impl Filter for TagFilter {
+    fn filter(&self, record: &Record) -> bool {
+        !self.denied.iter().any(|tag| record.has_tag(tag))
+            && (self.allowed.is_empty() || self.allowed.iter().any(|tag| record.has_tag(tag)))
+    }
+}
@@ -0,0 +1,597 @@
+use crate::{FieldValue, Level, LogLevel, Record};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Turns a [Record](Record) into the text that is written by a handler.
+///
+/// Any `Fn(&Record) -> String` is a formatter as well.
+pub trait Formatter: Send + Sync {
+    /// Format a record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: The record to format.
+    ///
+    /// returns: String
+    fn format(&self, record: &Record) -> String;
+}
+impl<F: Fn(&Record) -> String + Send + Sync> Formatter for F {
+    fn format(&self, record: &Record) -> String {
+        self(record)
+    }
+}
+
+/// The format used by the built-in handlers: `LEVEL (logger): message key=value ...`.
+/// Levels without a registered name are named after the nearest one, e.g. `ERROR+5` (see [Level::name](Level::name)).
+/// If the record has a backtrace, it is appended on the following lines.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultFormatter;
+impl Formatter for DefaultFormatter {
+    fn format(&self, record: &Record) -> String {
+        let level_name = Level::name(record.level);
+        let mut line = format!("{} ({}): {}", level_name, record.logger, record.message);
+        push_details(&mut line, record);
+        line
+    }
+}
+/// Append what the [DefaultFormatter](DefaultFormatter) writes after the message: the fields, correlation and span ID and the backtrace.
+pub(crate) fn push_details(line: &mut String, record: &Record) {
+    for (key, value) in &record.fields {
+        line.push_str(&format!(" {}={}", key, value));
+    }
+    if let Some(id) = &record.correlation_id {
+        line.push_str(&format!(" correlation_id={}", id));
+    }
+    if let Some(id) = &record.span_id {
+        line.push_str(&format!(" span_id={}", id));
+    }
+    if let Some(backtrace) = &record.backtrace {
+        line.push_str(&format!("\n{}", backtrace));
+    }
+}
+
+/// The version of the structured output of this crate: the JSON of the [JsonFormatter](JsonFormatter) (and so the [JsonlFileHandler](crate::JsonlFileHandler))
+/// and the records of the [BinaryHandler](crate::BinaryHandler). It is part of every record written in these formats.
+///
+/// The formats evolve additively: new keys and elements may be added (and the version is increased when they are),
+/// but existing ones are never removed or renamed. Parsers should ignore what they don't know.
+/// The only change in meaning so far are the field values in version 2, so parsers supporting version 1 have to check the version.
+///
+/// # Versions
+///
+/// * `1`: Field values are always strings.
+/// * `2`: Field values keep their types: integers, floats and booleans are written as JSON numbers and booleans
+///   (native MessagePack integers, floats and booleans in binary), nested fields as objects (maps) and lists as arrays.
+///   Adds the span ID.
+/// * `3`: Adds the message template.
+/// * `4`: Adds the tags.
+pub const SCHEMA_VERSION: u32 = 4;
+
+/// Formats records as a single line of JSON, e.g.
+/// `{"schema_version":4,"time":"2024-05-01T12:30:00.000Z","sequence":42,"level":"INFO","level_number":10,"logger":"foo","message":"Hello World"}`.
+///
+/// Fields are added as a `fields` object keeping the types of their values (since schema version 2),
+/// the [template](Record::template), correlation ID, span ID and backtrace as `template`, `correlation_id`, `span_id` and `backtrace` if the record has them,
+/// and the [tags](Record::tags) as a `tags` list if it has any.
+/// The keys are stable, see [SCHEMA_VERSION](SCHEMA_VERSION).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Formatter, JsonFormatter, Record, Level};
+/// let mut record = Record::new(Level::INFO, "say \"hi\"".to_string(), "foo".to_string());
+/// record.fields.push(("user".to_string(), "jane".into()));
+/// record.fields.push(("attempts".to_string(), 3.into()));
+/// let json = JsonFormatter.format(&record);
+/// assert!(json.starts_with(r#"{"schema_version":4,"time":""#));
+/// assert!(json.ends_with(r#""level":"INFO","level_number":10,"logger":"foo","message":"say \"hi\"","fields":{"user":"jane","attempts":3}}"#));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonFormatter;
+impl Formatter for JsonFormatter {
+    fn format(&self, record: &Record) -> String {
+        let level_name = Level::name(record.level);
+        let mut line = format!(
+            "{{\"schema_version\":{},\"time\":{},\"sequence\":{},\"level\":{},\"level_number\":{},\"logger\":{},\"message\":{}",
+            SCHEMA_VERSION, json_string(&format_time(record.time)), record.sequence, json_string(&level_name), record.level,
+            json_string(&record.logger), json_string(&record.message),
+        );
+        if !record.fields.is_empty() {
+            line.push_str(&format!(",\"fields\":{}", json_fields(&record.fields)));
+        }
+        if let Some(id) = &record.correlation_id {
+            line.push_str(&format!(",\"correlation_id\":{}", json_string(id)));
+        }
+        if let Some(id) = &record.span_id {
+            line.push_str(&format!(",\"span_id\":{}", json_string(id)));
+        }
+        if let Some(backtrace) = &record.backtrace {
+            line.push_str(&format!(",\"backtrace\":{}", json_string(&backtrace.to_string())));
+        }
+        if let Some(template) = &record.template {
+            line.push_str(&format!(",\"template\":{}", json_string(template)));
+        }
+        if !record.tags.is_empty() {
+            let tags: Vec<String> = record.tags.iter().map(|tag| json_string(tag)).collect();
+            line.push_str(&format!(",\"tags\":[{}]", tags.join(",")));
+        }
+        line.push('}');
+        line
+    }
+}
+/// Formats records like the `json-file` logging driver of Docker, e.g.
+/// `{"log":"INFO (foo): Hello World\n","stream":"stdout","time":"2024-05-01T12:30:00.000Z"}`,
+/// so applications in containers writing their own log files can be read by the same pipelines as `docker logs`.
+///
+/// The `log` line is rendered by an inner formatter, the [DefaultFormatter](DefaultFormatter) unless set with [with_formatter](DockerJsonFormatter::with_formatter).
+/// Like the [ConsoleHandler](crate::ConsoleHandler), records at [ERROR](Level::ERROR) and above are marked as `stderr`.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{ConfiguredHandler, DockerJsonFormatter, FileHandler, Formatter, Level, Record};
+/// let record = Record::new(Level::ERROR, "Something failed".to_string(), "foo".to_string());
+/// let json = DockerJsonFormatter::new().format(&record);
+/// assert!(json.starts_with(r#"{"log":"ERROR (foo): Something failed\n","stream":"stderr","time":""#));
+///
+/// let path = std::env::temp_dir().join(format!("logging-docker-{}.log", std::process::id()));
+/// let handler = ConfiguredHandler::new(FileHandler::new(&path).unwrap()).with_formatter(DockerJsonFormatter::new());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct DockerJsonFormatter {
+    inner: Arc<dyn Formatter>,
+    stderr_level: LogLevel,
+}
+impl DockerJsonFormatter {
+    /// Create a formatter rendering the lines with the [DefaultFormatter](DefaultFormatter).
+    pub fn new() -> Self {
+        Self { inner: Arc::new(DefaultFormatter), stderr_level: Level::ERROR }
+    }
+    /// Render the `log` lines with another formatter.
+    pub fn with_formatter<F: Formatter + 'static>(mut self, formatter: F) -> Self {
+        self.inner = Arc::new(formatter);
+        self
+    }
+    /// Mark records at or above this level as `stderr` instead of [ERROR](Level::ERROR).
+    pub fn with_stderr_level(mut self, level: LogLevel) -> Self {
+        self.stderr_level = level;
+        self
+    }
+}
+impl Default for DockerJsonFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Formatter for DockerJsonFormatter {
+    fn format(&self, record: &Record) -> String {
+        let mut log = self.inner.format(record);
+        log.push('\n');
+        let stream = if record.level >= self.stderr_level { "stderr" } else { "stdout" };
+        format!(
+            "{{\"log\":{},\"stream\":\"{}\",\"time\":{}}}",
+            json_string(&log), stream, json_string(&format_time(record.time)),
+        )
+    }
+}
+
+/// Formats records as JSON following the [Elastic Common Schema](https://www.elastic.co/guide/en/ecs/current/index.html),
+/// so they are mapped correctly when shipped to Elasticsearch and Kibana, e.g.
+/// `{"@timestamp":"2024-05-01T12:30:00.000Z","log.level":"info","log.logger":"foo","message":"Hello World","ecs.version":"8.11.0",...}`.
+///
+/// * The correlation ID becomes `trace.id` and the span ID `span.id`.
+/// * An error field (`err = ?error` in [fields!](crate::fields)) becomes `error.message` and `error.chain`,
+///   otherwise a field named `error` becomes `error.message`. A backtrace becomes `error.stack_trace`.
+/// * All other fields are added as `labels`.
+/// * `process.pid` and `host.hostname` describe where the record came from.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, EcsFormatter, Formatter, Level, Record};
+/// let mut record = Record::new(Level::ERROR, "Payment failed".to_string(), "shop".to_string());
+/// record.fields = fields! { error = "card declined", user = "jane" };
+/// record.correlation_id = Some("4bf92f3577b34da6".to_string());
+/// let json: serde_json::Value = serde_json::from_str(&EcsFormatter.format(&record)).unwrap();
+/// assert_eq!(json["log.level"], "error");
+/// assert_eq!(json["error.message"], "card declined");
+/// assert_eq!(json["labels"]["user"], "jane");
+/// assert_eq!(json["trace.id"], "4bf92f3577b34da6");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EcsFormatter;
+impl Formatter for EcsFormatter {
+    fn format(&self, record: &Record) -> String {
+        let mut line = format!(
+            "{{\"@timestamp\":{},\"log.level\":{},\"log.logger\":{},\"message\":{},\"ecs.version\":\"8.11.0\",\"process.pid\":{},\"host.hostname\":{}",
+            json_string(&format_time(record.time)), json_string(&Level::name(record.level).to_lowercase()),
+            json_string(&record.logger), json_string(&record.message),
+            std::process::id(), json_string(crate::system::hostname()),
+        );
+        if let Some(id) = &record.correlation_id {
+            line.push_str(&format!(",\"trace.id\":{}", json_string(id)));
+        }
+        if let Some(id) = &record.span_id {
+            line.push_str(&format!(",\"span.id\":{}", json_string(id)));
+        }
+        let error = record.fields.iter().find(|(_, value)| value.as_error().is_some())
+            .or_else(|| record.fields.iter().find(|(key, _)| key == "error"));
+        if let Some((_, error)) = error {
+            match error.as_error() {
+                Some((message, chain)) => line.push_str(&format!(
+                    ",\"error.message\":{},\"error.chain\":{}", json_string(&message.to_string()), json_value(&FieldValue::List(chain.to_vec())),
+                )),
+                None => line.push_str(&format!(",\"error.message\":{}", json_string(&error.to_string()))),
+            }
+        }
+        if let Some(backtrace) = &record.backtrace {
+            line.push_str(&format!(",\"error.stack_trace\":{}", json_string(&backtrace.to_string())));
+        }
+        let labels: Vec<String> = record.fields.iter()
+            .filter(|(key, _)| Some(key) != error.map(|(key, _)| key))
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_string(&value.to_string())))
+            .collect();
+        if !labels.is_empty() {
+            line.push_str(&format!(",\"labels\":{{{}}}", labels.join(",")));
+        }
+        line.push('}');
+        line
+    }
+}
+
+/// Formats records as the structured JSON of Google Cloud Logging, so records printed to stdout on GKE or Cloud Run are parsed natively, e.g.
+/// `{"severity":"WARNING","message":"Disk almost full","time":"2024-05-01T12:30:00.000Z","logging.googleapis.com/labels":{"logger":"foo"}}`.
+///
+/// * The level is mapped to the severities of Cloud Logging, e.g. `SUCCESS` to `NOTICE` and `FATAL` to `ALERT`.
+/// * The logger and the fields are added as labels.
+/// * The [source](Record::source) of the record becomes the `sourceLocation`, with the module path as the function.
+///   Records without a source use the fields `file`, `line` and `function` instead, if present.
+/// * With a [project](GcpFormatter::with_project), the correlation ID is linked as the trace of the record and the span ID as its span.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, ConfiguredHandler, ConsoleHandler, Formatter, GcpFormatter, Level, Record};
+/// let mut record = Record::new(Level::WARN, "Disk almost full".to_string(), "foo".to_string());
+/// record.fields = fields! { file = "src/main.rs", line = 42 };
+/// let json: serde_json::Value = serde_json::from_str(&GcpFormatter::new().format(&record)).unwrap();
+/// assert_eq!(json["severity"], "WARNING");
+/// assert_eq!(json["logging.googleapis.com/labels"]["logger"], "foo");
+/// assert_eq!(json["logging.googleapis.com/sourceLocation"]["line"], "42");
+///
+/// // records logged with the macros know where they come from
+/// record.source = Some(logging::Source { module_path: "app::disk", file: "src/disk.rs", line: 7 });
+/// let json: serde_json::Value = serde_json::from_str(&GcpFormatter::new().format(&record)).unwrap();
+/// assert_eq!(json["logging.googleapis.com/sourceLocation"]["file"], "src/disk.rs");
+/// assert_eq!(json["logging.googleapis.com/sourceLocation"]["function"], "app::disk");
+///
+/// let handler = ConfiguredHandler::new(ConsoleHandler::new()).with_formatter(GcpFormatter::new().with_project("my-project"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GcpFormatter {
+    project: Option<String>,
+}
+impl GcpFormatter {
+    /// Create a formatter without a project, so correlation IDs are only added as labels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Link the correlation IDs of records to traces in this Google Cloud project (`projects/{project}/traces/{id}`).
+    pub fn with_project(mut self, project: impl ToString) -> Self {
+        self.project = Some(project.to_string());
+        self
+    }
+}
+impl Formatter for GcpFormatter {
+    fn format(&self, record: &Record) -> String {
+        let severity = match record.level {
+            level if level >= Level::FATAL => "ALERT",
+            level if level >= Level::CRITICAL => "CRITICAL",
+            level if level >= Level::ERROR => "ERROR",
+            level if level >= Level::WARN => "WARNING",
+            level if level >= Level::SUCCESS => "NOTICE",
+            level if level >= Level::INFO => "INFO",
+            _ => "DEBUG",
+        };
+        let mut message = record.message.clone();
+        if let Some(backtrace) = &record.backtrace {
+            // Error Reporting picks up stack traces from the message
+            message.push_str(&format!("\n{}", backtrace));
+        }
+        let mut line = format!(
+            "{{\"severity\":\"{}\",\"message\":{},\"time\":{}",
+            severity, json_string(&message), json_string(&format_time(record.time)),
+        );
+        let location = ["file", "line", "function"];
+        // the location fields are only a fallback for records without a source
+        let source: Vec<(&str, String)> = match &record.source {
+            Some(source) => vec![("file", source.file.to_string()), ("line", source.line.to_string()), ("function", source.module_path.to_string())],
+            None => location.iter().filter_map(|key| record.field(key).map(|value| (*key, value.to_string()))).collect(),
+        };
+        let mut labels = vec![format!("\"logger\":{}", json_string(&record.logger))];
+        labels.extend(record.fields.iter()
+            .filter(|(key, _)| record.source.is_some() || !location.contains(&key.as_str()))
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_string(&value.to_string()))));
+        if let Some(id) = &record.correlation_id {
+            match &self.project {
+                Some(project) => {
+                    line.push_str(&format!(
+                        ",\"logging.googleapis.com/trace\":{}", json_string(&format!("projects/{}/traces/{}", project, id)),
+                    ));
+                    if let Some(span) = &record.span_id {
+                        line.push_str(&format!(",\"logging.googleapis.com/spanId\":{}", json_string(span)));
+                    }
+                }
+                None => labels.push(format!("\"correlation_id\":{}", json_string(id))),
+            }
+        }
+        line.push_str(&format!(",\"logging.googleapis.com/labels\":{{{}}}", labels.join(",")));
+        let source: Vec<String> = source.iter().map(|(key, value)| format!("\"{}\":{}", key, json_string(value))).collect();
+        if !source.is_empty() {
+            line.push_str(&format!(",\"logging.googleapis.com/sourceLocation\":{{{}}}", source.join(",")));
+        }
+        line.push('}');
+        line
+    }
+}
+
+/// A field value as JSON. Numbers that JSON can't represent (infinity and NaN) are written as strings.
+pub(crate) fn json_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Bool(value) => value.to_string(),
+        FieldValue::Int(number) => number.to_string(),
+        FieldValue::Float(number) if number.is_finite() => format!("{:?}", number),
+        FieldValue::Float(number) => json_string(&number.to_string()),
+        FieldValue::Str(text) => json_string(text),
+        FieldValue::Map(fields) => json_fields(fields),
+        FieldValue::List(values) => format!("[{}]", values.iter().map(json_value).collect::<Vec<_>>().join(",")),
+        FieldValue::Lazy(lazy) => json_value(&lazy.evaluate()),
+    }
+}
+/// Fields as a JSON object.
+pub(crate) fn json_fields(fields: &[(String, FieldValue)]) -> String {
+    let fields: Vec<String> = fields.iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_value(value)))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+/// Quote and escape a string for JSON.
+pub(crate) fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            control if control.is_control() => quoted.push_str(&format!("\\u{:04x}", control as u32)),
+            other => quoted.push(other),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Text(String),
+    Time,
+    Level,
+    LevelNumber,
+    LevelCode,
+    LevelSymbol,
+    Logger,
+    Message,
+    Fields,
+    CorrelationId,
+    SpanId,
+    Sequence,
+    Template,
+    Tags,
+}
+
+/// Formats records according to a pattern of placeholders.
+///
+/// Supported placeholders:
+/// * `{time}`: When the record was logged, in RFC 3339 format (UTC).
+/// * `{level}`: The name of the level.
+/// * `{level_number}`: The numeric level.
+/// * `{level_code}`: The first letter of the level name, e.g. `D`, `I`, `W` or `E`.
+/// * `{level_symbol}`: The symbol set for the level with [with_symbol](PatternFormatter::with_symbol), or nothing.
+/// * `{logger}`: The name of the logger.
+/// * `{message}`: The message.
+/// * `{fields}`: The structured fields as `key=value` pairs.
+/// * `{correlation_id}`: The correlation ID, or `-` if there is none.
+/// * `{span_id}`: The span ID, or `-` if there is none.
+/// * `{sequence}`: The [sequence number](Record::sequence) of the record.
+/// * `{template}`: The [template](Record::template) of the message, or the message if it has none.
+/// * `{tags}`: The [tags](Record::tags) separated by `,`, or `-` if there are none.
+///
+/// Anything else is copied as is.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Formatter, PatternFormatter, Record, Level};
+/// let formatter = PatternFormatter::new("[{correlation_id}] {level} {logger}: {message}");
+/// let record = Record::new(Level::INFO, "Hello World".to_string(), "foo".to_string());
+/// assert_eq!(formatter.format(&record), "[-] INFO foo: Hello World");
+/// ```
+#[derive(Clone, Debug)]
+pub struct PatternFormatter {
+    segments: Vec<Segment>,
+    symbols: Vec<(LogLevel, String)>,
+}
+impl PatternFormatter {
+    /// Create a formatter from a pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern, e.g. `{time} {level} ({logger}): {message}`.
+    ///
+    /// returns: PatternFormatter
+    pub fn new(pattern: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            let Some(length) = rest[start..].find('}') else { break };
+            let placeholder = match &rest[start + 1..start + length] {
+                "time" => Segment::Time,
+                "level" => Segment::Level,
+                "level_number" => Segment::LevelNumber,
+                "level_code" => Segment::LevelCode,
+                "level_symbol" => Segment::LevelSymbol,
+                "logger" => Segment::Logger,
+                "message" => Segment::Message,
+                "fields" => Segment::Fields,
+                "correlation_id" => Segment::CorrelationId,
+                "span_id" => Segment::SpanId,
+                "sequence" => Segment::Sequence,
+                "template" => Segment::Template,
+                "tags" => Segment::Tags,
+                _ => {
+                    segments.push(Segment::Text(rest[..start + length + 1].to_string()));
+                    rest = &rest[start + length + 1..];
+                    continue;
+                }
+            };
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+            segments.push(placeholder);
+            rest = &rest[start + length + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+        Self { segments, symbols: Vec::new() }
+    }
+    /// Set the symbol `{level_symbol}` shows for records at or above a level (up to the next level with a symbol).
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The lowest level to show the symbol for.
+    /// * `symbol`: The symbol, e.g. an emoji.
+    ///
+    /// returns: PatternFormatter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Formatter, PatternFormatter, Record, Level};
+    /// let formatter = PatternFormatter::new("{level_symbol}{level_code} {message}")
+    ///     .with_symbol(Level::WARN, "! ")
+    ///     .with_symbol(Level::ERROR, "!! ");
+    /// let record = |level| Record::new(level, "disk full".to_string(), "foo".to_string());
+    /// assert_eq!(formatter.format(&record(Level::INFO)), "I disk full");
+    /// assert_eq!(formatter.format(&record(Level::WARN)), "! W disk full");
+    /// assert_eq!(formatter.format(&record(Level::FATAL)), "!! F disk full");
+    /// ```
+    pub fn with_symbol(mut self, level: LogLevel, symbol: impl ToString) -> Self {
+        self.symbols.retain(|(existing, _)| *existing != level);
+        self.symbols.push((level, symbol.to_string()));
+        self.symbols.sort_by_key(|(level, _)| *level);
+        self
+    }
+    /// Set an emoji for each of the built-in levels from [Level::DEBUG](Level::DEBUG) to [Level::FATAL](Level::FATAL).
+    ///
+    /// returns: PatternFormatter
+    pub fn with_default_symbols(self) -> Self {
+        self.with_symbol(Level::DEBUG, "🐛")
+            .with_symbol(Level::INFO, "💬")
+            .with_symbol(Level::SUCCESS, "✅")
+            .with_symbol(Level::WARN, "⚠️")
+            .with_symbol(Level::ERROR, "❌")
+            .with_symbol(Level::CRITICAL, "🔥")
+            .with_symbol(Level::FATAL, "💀")
+    }
+}
+impl Formatter for PatternFormatter {
+    fn format(&self, record: &Record) -> String {
+        let mut line = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => line.push_str(text),
+                Segment::Time => line.push_str(&format_time(record.time)),
+                Segment::Level => line.push_str(&Level::name(record.level)),
+                Segment::LevelNumber => line.push_str(&record.level.to_string()),
+                Segment::LevelCode => line.extend(Level::name(record.level).chars().next()),
+                Segment::LevelSymbol => line.push_str(self.symbols.iter()
+                    .rfind(|(level, _)| *level <= record.level)
+                    .map_or("", |(_, symbol)| symbol)),
+                Segment::Logger => line.push_str(&record.logger),
+                Segment::Message => line.push_str(&record.message),
+                Segment::Fields => line.push_str(&record.fields.iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect::<Vec<String>>()
+                    .join(" ")),
+                Segment::CorrelationId => line.push_str(record.correlation_id.as_deref().unwrap_or("-")),
+                Segment::SpanId => line.push_str(record.span_id.as_deref().unwrap_or("-")),
+                Segment::Sequence => line.push_str(&record.sequence.to_string()),
+                Segment::Template => line.push_str(record.template.as_deref().unwrap_or(&record.message)),
+                Segment::Tags if record.tags.is_empty() => line.push('-'),
+                Segment::Tags => line.push_str(&record.tags.join(",")),
+            }
+        }
+        line
+    }
+}
+
+/// Format a point in time as RFC 3339 in UTC with millisecond precision, e.g. `2024-05-01T12:30:00.000Z`.
+pub(crate) fn format_time(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = duration.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let seconds_of_day = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day,
+        seconds_of_day / 3600, seconds_of_day % 3600 / 60, seconds_of_day % 60,
+        duration.subsec_millis(),
+    )
+}
+/// Parse a time written by [format_time](format_time), e.g. `2024-05-01T12:30:00.000Z`. Fractions of any length and no fraction at all are accepted.
+pub(crate) fn parse_time(text: &str) -> Option<SystemTime> {
+    let text = text.strip_suffix('Z')?;
+    let (date, time) = text.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(str::parse::<u64>);
+    let (hours, minutes, seconds) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse::<u32>().ok()?;
+    let days = u64::try_from(days_from_civil(year, month as u32, day as u32)?).ok()?;
+    let seconds = days.checked_mul(86_400)?.checked_add(hours * 3600 + minutes * 60 + seconds)?;
+    UNIX_EPOCH.checked_add(std::time::Duration::new(seconds, nanos))
+}
+/// Convert a (year, month, day) date into days since the unix epoch, the inverse of [civil_from_days](civil_from_days).
+///
+/// returns: Option<i64>, `None` if the year is too large to count its days
+fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    let year = if month <= 2 { year.checked_sub(1)? } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(146_097)?.checked_add(day_of_era - 719_468)
+}
+/// Convert days since the unix epoch into a (year, month, day) date.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
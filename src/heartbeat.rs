@@ -0,0 +1,87 @@
+use crate::{Level, LogLevel, Logger};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// A running heartbeat, returned by [heartbeat](heartbeat). The heartbeat stops when this is dropped.
+#[must_use = "the heartbeat stops when it is dropped"]
+pub struct Heartbeat {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+impl Heartbeat {
+    /// Stop the heartbeat and wait for its thread to finish.
+    ///
+    /// returns: ()
+    pub fn stop(mut self) {
+        self.shutdown()
+    }
+    fn shutdown(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.shutdown()
+    }
+}
+
+/// Log an "alive" record periodically from a background thread, so downstream systems can tell a quiet process from a dead one.
+///
+/// The record has the fields `uptime_s` (the seconds since the heartbeat was started), `emitted` and `dropped`
+/// (the records emitted and dropped since the previous heartbeat, see [stats](crate::stats)) and `handler_errors` (the failures of handlers in total).
+///
+/// # Arguments
+///
+/// * `interval`: The time between two heartbeats. The first one is logged after one interval.
+/// * `logger`: The logger to log the heartbeats with.
+/// * `level`: The level to log the heartbeats at.
+///
+/// returns: Heartbeat
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let memory = Arc::new(MemoryHandler::new(100));
+/// let logger = Logger::new("heartbeat");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(Arc::clone(&memory));
+///
+/// let heartbeat = logging::heartbeat(Duration::from_millis(10), &logger, Level::INFO);
+/// std::thread::sleep(Duration::from_millis(100));
+/// heartbeat.stop();
+/// let records = memory.records();
+/// assert!(!records.is_empty());
+/// assert_eq!(records[0].message, "alive");
+/// assert!(records[0].field("uptime_s").is_some());
+/// ```
+pub fn heartbeat(interval: Duration, logger: &Logger, level: LogLevel) -> Heartbeat {
+    let (stop, stopped) = mpsc::channel::<()>();
+    let logger = logger.clone();
+    let thread = std::thread::Builder::new()
+        .name("logging-heartbeat".to_string())
+        .spawn(move || {
+            let start = Instant::now();
+            let mut previous = crate::stats().at_least(Level::MIN);
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                let stats = crate::stats();
+                let counts = stats.at_least(Level::MIN);
+                logger.log_fields("alive", level, vec![
+                    ("uptime_s".to_string(), start.elapsed().as_secs().into()),
+                    ("emitted".to_string(), counts.emitted.saturating_sub(previous.emitted).into()),
+                    ("dropped".to_string(), counts.dropped.saturating_sub(previous.dropped).into()),
+                    ("handler_errors".to_string(), stats.handler_errors.values().sum::<u64>().into()),
+                ]);
+                previous = counts;
+            }
+        })
+        .expect("failed to spawn the heartbeat thread");
+    Heartbeat { stop: Some(stop), thread: Some(thread) }
+}
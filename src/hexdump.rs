@@ -0,0 +1,47 @@
+use std::fmt::Write;
+
+/// The maximum number of bytes [Logger::debug_hexdump](crate::Logger::debug_hexdump) and [hexdump!](crate::hexdump) print.
+pub const HEXDUMP_MAX_LEN: usize = 4096;
+
+/// Format bytes as a table of offsets, hex values and ASCII characters, like `hexdump -C`.
+///
+/// # Arguments
+///
+/// * `bytes`: The bytes to format.
+/// * `max_len`: The maximum number of bytes to format. Any further bytes are only counted.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// let dump = logging::hexdump(b"Hello World\n", 4096);
+/// assert_eq!(dump, "\
+/// 00000000  48 65 6c 6c 6f 20 57 6f  72 6c 64 0a              |Hello World.|\n\
+/// 0000000c");
+/// assert!(logging::hexdump(&[0; 64], 16).ends_with("... 48 more bytes"));
+/// ```
+pub fn hexdump(bytes: &[u8], max_len: usize) -> String {
+    let shown = &bytes[..bytes.len().min(max_len)];
+    let mut dump = String::new();
+    for (line, chunk) in shown.chunks(16).enumerate() {
+        let _ = write!(dump, "{:08x} ", line * 16);
+        for index in 0..16 {
+            if index % 8 == 0 {
+                dump.push(' ');
+            }
+            match chunk.get(index) {
+                Some(byte) => { let _ = write!(dump, "{:02x} ", byte); }
+                None => dump.push_str("   "),
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(chunk.iter().map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }));
+        dump.push_str("|\n");
+    }
+    let _ = write!(dump, "{:08x}", shown.len());
+    if bytes.len() > shown.len() {
+        let _ = write!(dump, "\n... {} more bytes", bytes.len() - shown.len());
+    }
+    dump
+}
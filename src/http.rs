@@ -0,0 +1,422 @@
+//! Request logging for HTTP servers.
+//!
+//! [RequestLog](RequestLog) does the actual logging and can be used with any framework.
+//! With the `axum` feature, [axum_middleware](axum_middleware) plugs it into an axum router.
+//! With the `tower` feature, [LoggingLayer](LoggingLayer) does the same for any tower based stack (e.g. tonic or hyper).
+
+use crate::{correlation, FieldValue, Level, LogLevel, Logger};
+use std::fmt::Display;
+use std::time::Instant;
+
+/// The header a correlation ID is read from and written back to.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// The W3C Trace Context header a trace ID is read from. It takes precedence over the [REQUEST_ID_HEADER](REQUEST_ID_HEADER).
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// The correlation ID received with a request: the trace ID of a valid `traceparent` header, otherwise the request ID.
+#[cfg(any(feature = "axum", feature = "tower"))]
+fn received_id(traceparent: Option<&str>, request_id: Option<&str>) -> Option<String> {
+    traceparent.and_then(correlation::parse_traceparent)
+        .map(|(trace, _)| trace)
+        .or_else(|| request_id.map(str::to_string))
+}
+
+/// Logs the start and end of a single HTTP request.
+///
+/// Each request gets a logger named `http::<method>`, or `http::<method> <route>` if started with a route, a correlation ID and a new [span ID](correlation::set_span),
+/// which are attached to the start and finish records. Non-standard methods share the logger `http::<other>`. The path is only attached as the `path` field:
+/// loggers are never removed, so naming them after raw paths (e.g. `/users/1`, `/users/2`, …) would create a new one for every URL.
+/// Routes have to come from a bounded set, e.g. the routes of a router like `/users/{id}`.
+///
+/// # Examples
+///
+/// ```
+/// use logging::http::RequestLog;
+/// use logging::{correlation, Level};
+/// logging::set_level(Level::ALL);
+///
+/// let request = RequestLog::start("GET", "/users/7", None);
+/// assert_eq!(request.logger().name(), "http::GET");
+/// request.finish(200);
+///
+/// let request = RequestLog::start_routed("GET", "/users/{id}", "/users/7", None, Level::INFO, Vec::new());
+/// assert_eq!(request.logger().name(), "http::GET /users/{id}");
+/// correlation::with_id(request.correlation_id(), || {
+///     // handle the request
+/// });
+/// request.finish(200);
+/// ```
+pub struct RequestLog {
+    logger: Logger,
+    fields: Vec<(String, FieldValue)>,
+    level: LogLevel,
+    correlation_id: String,
+    span_id: String,
+    start: Instant,
+}
+impl RequestLog {
+    /// Log the start of a request at [Level::DEBUG](Level::DEBUG).
+    ///
+    /// # Arguments
+    ///
+    /// * `method`: The HTTP method, e.g. `GET`.
+    /// * `path`: The path of the request.
+    /// * `correlation_id`: The correlation ID received with the request, if any. Otherwise a new one is generated.
+    ///
+    /// returns: RequestLog
+    pub fn start(method: &str, path: &str, correlation_id: Option<String>) -> Self {
+        Self::start_with(method, path, correlation_id, Level::INFO, Vec::new())
+    }
+    /// Like [start](RequestLog::start), but with the level successful requests are logged at and extra fields for all records of this request.
+    ///
+    /// # Arguments
+    ///
+    /// * `method`: The HTTP method, e.g. `GET`.
+    /// * `path`: The path of the request.
+    /// * `correlation_id`: The correlation ID received with the request, if any. Otherwise a new one is generated.
+    /// * `level`: The level successful requests are logged at. Failed requests are logged at least at [Level::WARN](Level::WARN).
+    /// * `fields`: Fields attached to every record of this request.
+    ///
+    /// returns: RequestLog
+    pub fn start_with(method: &str, path: &str, correlation_id: Option<String>, level: LogLevel, fields: Vec<(String, FieldValue)>) -> Self {
+        Self::begin(method, None, path, correlation_id, level, fields)
+    }
+    /// Like [start_with](RequestLog::start_with), but with the logger named after the route of the request, e.g. `http::GET /users/{id}`.
+    /// The route is attached as the `route` field as well. `::` in the route is replaced with `:`, so it doesn't nest the logger.
+    ///
+    /// # Arguments
+    ///
+    /// * `method`: The HTTP method, e.g. `GET`.
+    /// * `route`: The route the request matched, e.g. `/users/{id}`. Never the raw path.
+    /// * `path`: The path of the request.
+    /// * `correlation_id`: The correlation ID received with the request, if any. Otherwise a new one is generated.
+    /// * `level`: The level successful requests are logged at. Failed requests are logged at least at [Level::WARN](Level::WARN).
+    /// * `fields`: Fields attached to every record of this request.
+    ///
+    /// returns: RequestLog
+    pub fn start_routed(method: &str, route: &str, path: &str, correlation_id: Option<String>, level: LogLevel, fields: Vec<(String, FieldValue)>) -> Self {
+        Self::begin(method, Some(route), path, correlation_id, level, fields)
+    }
+    fn begin(method: &str, route: Option<&str>, path: &str, correlation_id: Option<String>, level: LogLevel, fields: Vec<(String, FieldValue)>) -> Self {
+        let mut all_fields = vec![
+            ("method".to_string(), method.into()),
+            ("path".to_string(), path.into()),
+        ];
+        if let Some(route) = route {
+            all_fields.push(("route".to_string(), route.into()));
+        }
+        all_fields.extend(fields);
+        // clients can send any method, so only the standard ones get their own logger
+        let method = match method {
+            "GET" | "HEAD" | "POST" | "PUT" | "DELETE" | "CONNECT" | "OPTIONS" | "TRACE" | "PATCH" => method,
+            _ => "<other>",
+        };
+        let name = match route {
+            Some(route) => format!("http::{} {}", method, route.replace("::", ":")),
+            None => format!("http::{}", method),
+        };
+        let request = Self {
+            logger: Logger::new(name),
+            fields: all_fields,
+            level,
+            correlation_id: correlation_id.unwrap_or_else(correlation::new_id),
+            span_id: correlation::new_span_id(),
+            start: Instant::now(),
+        };
+        request.log("request started".to_string(), Level::DEBUG, Vec::new());
+        request
+    }
+    /// The logger of this request. Can be used to log from the request handler.
+    pub fn logger(&self) -> &Logger {
+        &self.logger
+    }
+    /// The correlation ID of this request.
+    pub fn correlation_id(&self) -> &str {
+        &self.correlation_id
+    }
+    /// The span ID of this request.
+    pub fn span_id(&self) -> &str {
+        &self.span_id
+    }
+    /// Log the end of the request with its status and latency.
+    /// Server errors are logged at [Level::ERROR](Level::ERROR), client errors at [Level::WARN](Level::WARN)
+    /// and everything else at [Level::INFO](Level::INFO) (or the level given to [start_with](RequestLog::start_with)).
+    ///
+    /// # Arguments
+    ///
+    /// * `status`: The status code of the response.
+    ///
+    /// returns: ()
+    pub fn finish(self, status: u16) {
+        let latency = self.start.elapsed();
+        let level = match status {
+            500.. => Level::ERROR,
+            400..=499 => Level::WARN.max(self.level),
+            _ => self.level,
+        };
+        self.log(
+            format!("request finished with {} in {:?}", status, latency),
+            level,
+            vec![
+                ("status".to_string(), status.into()),
+                ("latency_ms".to_string(), latency.as_millis().into()),
+            ],
+        );
+    }
+    /// Log that the request failed without a response at [Level::ERROR](Level::ERROR).
+    ///
+    /// # Arguments
+    ///
+    /// * `err`: Why the request failed.
+    ///
+    /// returns: ()
+    pub fn fail(self, err: &dyn Display) {
+        let latency = self.start.elapsed();
+        self.log(
+            format!("request failed after {:?}: {}", latency, err),
+            Level::ERROR,
+            vec![("latency_ms".to_string(), latency.as_millis().into())],
+        );
+    }
+    fn log(&self, msg: String, level: LogLevel, fields: Vec<(String, FieldValue)>) {
+        let fields = self.fields.iter().cloned().chain(fields).collect();
+        let _id = correlation::set(&self.correlation_id);
+        let _span = correlation::set_span(&self.span_id);
+        self.logger.log_fields(msg, level, fields);
+    }
+}
+
+/// An axum middleware logging every request with a [RequestLog](RequestLog).
+///
+/// The correlation ID is taken from the [TRACEPARENT_HEADER](TRACEPARENT_HEADER) or [REQUEST_ID_HEADER](REQUEST_ID_HEADER) if the request has one,
+/// set together with the span ID of the request for everything logged while handling it, and returned in the request ID header of the response.
+/// The logger is named after the matched route (e.g. `http::GET /users/{id}`). Requests not matching any route (e.g. 404s) share `http::<method> <unmatched>`,
+/// with their path only in the `path` field.
+///
+/// # Examples
+///
+/// ```
+/// use axum::{middleware, routing::get, Router};
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// use tower::ServiceExt;
+/// # use std::future::Future;
+///
+/// let app: Router = Router::new()
+///     .route("/users/{id}", get(|| async { "Hello World" }))
+///     .layer(middleware::from_fn(logging::http::axum_middleware));
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// Logger::new("http").set_level(Level::ALL);
+/// Logger::new("http").add_handler(Arc::clone(&memory));
+/// let future = app.oneshot(axum::http::Request::get("/wp-admin.php").body(axum::body::Body::empty()).unwrap());
+/// # let mut future = std::pin::pin!(future);
+/// # let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+/// # assert!(future.as_mut().poll(&mut context).is_ready());
+/// let record = memory.records().pop().unwrap();
+/// assert_eq!(record.logger, "http::GET <unmatched>");
+/// assert_eq!(record.field("path").unwrap().to_string(), "/wp-admin.php");
+/// ```
+#[cfg(feature = "axum")]
+pub async fn axum_middleware(request: axum::extract::Request, next: axum::middleware::Next) -> axum::response::Response {
+    let route = match request.extensions().get::<axum::extract::MatchedPath>() {
+        Some(route) => route.as_str().to_string(),
+        None => "<unmatched>".to_string(),
+    };
+    let correlation_id = {
+        let header = |name: &str| request.headers().get(name).and_then(|value| value.to_str().ok());
+        received_id(header(TRACEPARENT_HEADER), header(REQUEST_ID_HEADER))
+    };
+    let log = RequestLog::start_routed(request.method().as_str(), &route, request.uri().path(), correlation_id, Level::INFO, Vec::new());
+    let mut response = correlation::scope(log.correlation_id(), next.run(request)).with_span(log.span_id()).await;
+    if let Ok(id) = axum::http::HeaderValue::from_str(log.correlation_id()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, id);
+    }
+    log.finish(response.status().as_u16());
+    response
+}
+
+#[cfg(feature = "tower")]
+pub use self::tower::{LoggingLayer, LoggingService, ResponseFuture};
+
+#[cfg(feature = "tower")]
+mod tower {
+    use super::{received_id, RequestLog, REQUEST_ID_HEADER, TRACEPARENT_HEADER};
+    use crate::{correlation, FieldValue, Level, LogLevel};
+    use ::http::request::Parts;
+    use ::http::{Request, Response};
+    use std::fmt::{self, Debug, Display};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{ready, Context, Poll};
+    use tower_layer::Layer;
+    use tower_service::Service;
+
+    /// A [tower Layer](Layer) logging every request passing through it with a [RequestLog](RequestLog).
+    ///
+    /// The correlation ID is taken from the [TRACEPARENT_HEADER](TRACEPARENT_HEADER) or [REQUEST_ID_HEADER](REQUEST_ID_HEADER) if the request has one
+    /// and is set together with the span ID of the request while the inner service handles it.
+    /// The requests are logged by method (`http::GET`) unless a route is extracted with [with_route](LoggingLayer::with_route).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::http::LoggingLayer;
+    /// use logging::Level;
+    /// use tower::{service_fn, Layer, Service};
+    /// # use std::future::Future;
+    ///
+    /// let mut service = LoggingLayer::new()
+    ///     .with_level(Level::DEBUG)
+    ///     .with_field("service", "users")
+    ///     .with_header_field("user-agent")
+    ///     .with_route(|request| match request.uri.path().strip_prefix("/users/") {
+    ///         Some(_) => "/users/{id}".to_string(),
+    ///         None => "<other>".to_string(),
+    ///     })
+    ///     .layer(service_fn(|_request: http::Request<()>| async {
+    ///         Ok::<_, std::convert::Infallible>(http::Response::new("Hello World"))
+    ///     }));
+    ///
+    /// let future = service.call(http::Request::get("/users/7").body(()).unwrap());
+    /// # let mut future = std::pin::pin!(future);
+    /// # let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    /// # assert!(future.as_mut().poll(&mut context).is_ready());
+    /// ```
+    #[derive(Clone)]
+    pub struct LoggingLayer {
+        level: LogLevel,
+        fields: Vec<(String, FieldValue)>,
+        header_fields: Vec<String>,
+        route: Option<Arc<RouteExtractor>>,
+    }
+    /// Extracts the route of a request, see [with_route](LoggingLayer::with_route).
+    type RouteExtractor = dyn Fn(&Parts) -> String + Send + Sync;
+    impl Debug for LoggingLayer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("LoggingLayer")
+                .field("level", &self.level)
+                .field("fields", &self.fields)
+                .field("header_fields", &self.header_fields)
+                .field("route", &self.route.is_some())
+                .finish()
+        }
+    }
+    impl Default for LoggingLayer {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+    impl LoggingLayer {
+        /// Create a layer logging successful requests at [Level::INFO](Level::INFO).
+        pub fn new() -> Self {
+            Self {
+                level: Level::INFO,
+                fields: Vec::new(),
+                header_fields: Vec::new(),
+                route: None,
+            }
+        }
+        /// Set the level successful requests are logged at.
+        pub fn with_level(mut self, level: LogLevel) -> Self {
+            self.level = level;
+            self
+        }
+        /// Attach a fixed field to every record.
+        pub fn with_field(mut self, key: impl ToString, value: impl Into<FieldValue>) -> Self {
+            self.fields.push((key.to_string(), value.into()));
+            self
+        }
+        /// Attach the value of a request header (if present) as a field to every record of the request.
+        pub fn with_header_field(mut self, header: impl ToString) -> Self {
+            self.header_fields.push(header.to_string().to_ascii_lowercase());
+            self
+        }
+        /// Name the logger of every request after its route, see [RequestLog::start_routed](RequestLog::start_routed).
+        /// The extractor has to map the requests to a bounded set of routes (e.g. `/users/{id}`, not `/users/7`),
+        /// as every route gets its own logger.
+        pub fn with_route<F: Fn(&Parts) -> String + Send + Sync + 'static>(mut self, route: F) -> Self {
+            self.route = Some(Arc::new(route));
+            self
+        }
+    }
+    impl<S> Layer<S> for LoggingLayer {
+        type Service = LoggingService<S>;
+        fn layer(&self, inner: S) -> Self::Service {
+            LoggingService { inner, layer: self.clone() }
+        }
+    }
+
+    /// The service created by a [LoggingLayer](LoggingLayer).
+    #[derive(Clone, Debug)]
+    pub struct LoggingService<S> {
+        inner: S,
+        layer: LoggingLayer,
+    }
+    impl<S, RequestBody, ResponseBody> Service<Request<RequestBody>> for LoggingService<S>
+    where
+        S: Service<Request<RequestBody>, Response = Response<ResponseBody>>,
+        S::Error: Display,
+    {
+        type Response = S::Response;
+        type Error = S::Error;
+        type Future = ResponseFuture<S::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+        fn call(&mut self, request: Request<RequestBody>) -> Self::Future {
+            let header = |name: &str| request.headers().get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let mut fields = self.layer.fields.clone();
+            fields.extend(self.layer.header_fields.iter()
+                .filter_map(|name| header(name).map(|value| (name.clone(), value.into()))));
+            let correlation_id = received_id(header(TRACEPARENT_HEADER).as_deref(), header(REQUEST_ID_HEADER).as_deref());
+            let (parts, body) = request.into_parts();
+            let (method, path) = (parts.method.as_str(), parts.uri.path());
+            let log = match &self.layer.route {
+                Some(route) => RequestLog::start_routed(method, &route(&parts), path, correlation_id, self.layer.level, fields),
+                None => RequestLog::start_with(method, path, correlation_id, self.layer.level, fields),
+            };
+            let request = Request::from_parts(parts, body);
+            let future = {
+                let _id = correlation::set(log.correlation_id());
+                let _span = correlation::set_span(log.span_id());
+                self.inner.call(request)
+            };
+            ResponseFuture { future, log: Some(log) }
+        }
+    }
+
+    /// The future returned by a [LoggingService](LoggingService). Logs the end of the request once the response is ready.
+    pub struct ResponseFuture<F> {
+        future: F,
+        log: Option<RequestLog>,
+    }
+    impl<F, ResponseBody, E> Future for ResponseFuture<F>
+    where
+        F: Future<Output = Result<Response<ResponseBody>, E>>,
+        E: Display,
+    {
+        type Output = F::Output;
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            // SAFETY: the inner future is never moved out of the pinned struct.
+            let this = unsafe { self.get_unchecked_mut() };
+            let log = this.log.as_ref().expect("ResponseFuture polled after completion");
+            let result = {
+                let _id = correlation::set(log.correlation_id());
+                let _span = correlation::set_span(log.span_id());
+                ready!(unsafe { Pin::new_unchecked(&mut this.future) }.poll(cx))
+            };
+            if let Some(log) = this.log.take() {
+                match &result {
+                    Ok(response) => log.finish(response.status().as_u16()),
+                    Err(err) => log.fail(err),
+                }
+            }
+            Poll::Ready(result)
+        }
+    }
+}
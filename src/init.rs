@@ -0,0 +1,146 @@
+use crate::formatter::format_time;
+use crate::{ConfiguredHandler, ConsoleHandler, DefaultFormatter, Formatter, JsonFormatter, Level, LogLevel, Logger, Record};
+use std::sync::Arc;
+
+/// The environment variable read by [init_from_env](init_from_env).
+const ENV_VARIABLE: &str = "RUST_LOG";
+/// The environment variable overriding the [profile](profile).
+const PROFILE_VARIABLE: &str = "LOGGING_PROFILE";
+
+fn install(handler: Arc<dyn crate::Handler>, level: LogLevel) {
+    let mut root = crate::logger::get_root().write().expect("Logger poisoned");
+    root.set_handlers(vec![handler]);
+    root.set_level(level);
+}
+
+/// Log everything at or above [Level::INFO](Level::INFO) to the console.
+/// Replaces the handlers of all loggers with a single [ConsoleHandler](ConsoleHandler), so calling this (or another `init_` preset) twice doesn't print everything twice.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// logging::init_simple();
+/// logging::info!("Hello World");
+/// ```
+pub fn init_simple() {
+    install(Arc::new(ConsoleHandler::new()), Level::INFO)
+}
+
+/// Like [init_simple](init_simple), but every line starts with the time the record was logged at, e.g. `2024-05-01T12:30:00.000Z INFO (foo): Hello World`.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// logging::init_timed();
+/// logging::info!("Hello World");
+/// ```
+pub fn init_timed() {
+    let handler = ConfiguredHandler::new(ConsoleHandler::new())
+        .with_formatter(|record: &Record| format!("{} {}", format_time(record.time), DefaultFormatter.format(record)));
+    install(Arc::new(handler), Level::INFO)
+}
+
+/// Like [init_simple](init_simple), but with the levels taken from the `RUST_LOG` environment variable.
+///
+/// The variable holds comma separated directives: a level on its own sets the root level,
+/// `logger=level` the level of a logger and its children, e.g. `warn,my_app=debug,my_app::db=info`.
+/// Logger names may be glob patterns like `*::db=debug` (see [set_level_for](crate::set_level_for)).
+/// Levels are given by name (case-insensitive) or number. Without the variable, the level is [Level::INFO](Level::INFO).
+/// Invalid directives are skipped with a warning.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// std::env::set_var("RUST_LOG", "warn,my_app::db=debug");
+/// logging::init_from_env();
+/// assert_eq!(Logger::new("my_app").level(), Level::WARN);
+/// assert_eq!(Logger::new("my_app::db").level(), Level::DEBUG);
+/// ```
+pub fn init_from_env() {
+    init_simple();
+    let Ok(directives) = std::env::var(ENV_VARIABLE) else { return };
+    let parse = |level: &str| level.parse().ok().or_else(|| Level::by_name(level));
+    let mut loggers = Vec::new();
+    let mut invalid = Vec::new();
+    for directive in directives.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+        let (name, level) = directive.split_once('=').unwrap_or(("", directive));
+        match parse(level.trim()) {
+            Some(level) => loggers.push((name.trim(), level)),
+            None => invalid.push(directive),
+        }
+    }
+    crate::apply_levels(&loggers);
+    for directive in invalid {
+        Logger::new("").warn(format!("invalid {} directive '{}'", ENV_VARIABLE, directive));
+    }
+}
+
+/// A preset of level, handler and format for a kind of environment, see [profile](profile).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    /// Coloured, human-readable lines with the time, from [DEBUG](Level::DEBUG) up, for local development.
+    Dev,
+    /// One [JSON](JsonFormatter) object per line on standard output only, from [INFO](Level::INFO) up, for log collectors.
+    Prod,
+    /// Plain lines on standard output (captured by the test harness and shown for failing tests), at all levels.
+    Test,
+}
+impl Profile {
+    /// Parse the name of a profile (`dev`, `prod` or `test`, case-insensitive). `development` and `production` are accepted as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the profile.
+    ///
+    /// returns: Option<Profile>
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "dev" | "development" => Some(Self::Dev),
+            "prod" | "production" => Some(Self::Prod),
+            "test" => Some(Self::Test),
+            _ => None,
+        }
+    }
+}
+
+/// Set up logging according to a profile, replacing the handlers of all loggers like the `init_` presets.
+/// If the `LOGGING_PROFILE` environment variable names a profile (see [Profile::by_name](Profile::by_name)), that one is used instead,
+/// so a program can default to [Dev](Profile::Dev) and be switched to [Prod](Profile::Prod) when deployed.
+///
+/// # Arguments
+///
+/// * `profile`: The profile to use unless the environment variable says otherwise.
+///
+/// returns: Profile The profile that was set up.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, Profile};
+/// std::env::set_var("LOGGING_PROFILE", "prod");
+/// assert_eq!(logging::profile(Profile::Dev), Profile::Prod);
+/// assert_eq!(Logger::new("app").level(), Level::INFO);
+/// // prints {"schema_version":4,"time":"...","level":"INFO",...,"message":"started"}
+/// logging::info!("started");
+/// ```
+pub fn profile(profile: Profile) -> Profile {
+    let profile = std::env::var(PROFILE_VARIABLE).ok()
+        .and_then(|name| Profile::by_name(&name))
+        .unwrap_or(profile);
+    match profile {
+        Profile::Dev => install(Arc::new(ConsoleHandler::new().with_time(true).with_level_width(7)), Level::DEBUG),
+        Profile::Prod => {
+            let console = ConsoleHandler::new().with_colour(false).with_stderr_level(None);
+            install(Arc::new(ConfiguredHandler::new(console).with_formatter(JsonFormatter)), Level::INFO)
+        }
+        Profile::Test => install(Arc::new(ConsoleHandler::new().with_colour(false).with_stderr_level(None)), Level::ALL),
+    }
+    profile
+}
@@ -0,0 +1,64 @@
+use crate::{FileHandler, Formatter, Handler, JsonFormatter, LogLevel, Record};
+use std::io;
+use std::path::Path;
+
+/// A [Handler](Handler) appending records to a file as JSON Lines: one [JSON object](JsonFormatter) per line.
+/// This is the format expected by the file sources of log shippers like vector or fluent-bit.
+///
+/// Records are always written as JSON, even if they were already [formatted](Record::formatted) otherwise.
+/// Like the [FileHandler](FileHandler), the file can be reopened for log rotation.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{JsonlFileHandler, Level, Logger};
+/// let path = std::env::temp_dir().join(format!("logging-jsonl-{}.log", std::process::id()));
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(JsonlFileHandler::new(&path).unwrap());
+/// logger.info("Hello\nWorld");
+/// let content = std::fs::read_to_string(&path).unwrap();
+/// assert_eq!(content.lines().count(), 1);
+/// assert!(content.contains(r#""message":"Hello\nWorld""#));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct JsonlFileHandler {
+    file: FileHandler,
+}
+impl JsonlFileHandler {
+    /// Create a handler appending to the file at the given path.
+    /// The file is created if it doesn't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to log to.
+    ///
+    /// returns: Result<JsonlFileHandler, Error>
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self { file: FileHandler::new(path)? })
+    }
+    /// Lock the file for every write, see [FileHandler::shared](FileHandler::shared).
+    pub fn shared(self) -> Self {
+        Self { file: self.file.shared() }
+    }
+    /// The path of the file this handler logs to.
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+impl Handler for JsonlFileHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut record = record.clone();
+        record.formatted = Some(JsonFormatter.format(&record));
+        self.file.handle(&record)
+    }
+    fn reopen(&self) {
+        self.file.reopen()
+    }
+    fn close(&self) {
+        self.file.close()
+    }
+}
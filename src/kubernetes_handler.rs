@@ -0,0 +1,124 @@
+use crate::{Handler, HandlerConfig, HandlerHealth, LogLevel, Record};
+use std::sync::Arc;
+
+/// The fields added by default and the environment variables they are read from.
+const DEFAULT_VARIABLES: [(&str, &str); 5] = [
+    ("k8s.pod.name", "POD_NAME"),
+    ("k8s.namespace.name", "POD_NAMESPACE"),
+    ("k8s.node.name", "NODE_NAME"),
+    ("k8s.pod.ip", "POD_IP"),
+    ("k8s.container.name", "CONTAINER_NAME"),
+];
+
+/// Adds Kubernetes metadata to every record before passing it on to another handler, so logs collected from a cluster
+/// can be attributed to their pod without an agent enriching them.
+///
+/// The metadata is read once from environment variables, which are usually set with the downward API:
+///
+/// | Field                | Variable         |
+/// |----------------------|------------------|
+/// | `k8s.pod.name`       | `POD_NAME`       |
+/// | `k8s.namespace.name` | `POD_NAMESPACE`  |
+/// | `k8s.node.name`      | `NODE_NAME`      |
+/// | `k8s.pod.ip`         | `POD_IP`         |
+/// | `k8s.container.name` | `CONTAINER_NAME` |
+///
+/// Variables that aren't set are skipped, and fields the record already has aren't replaced.
+///
+/// ```yaml
+/// env:
+///   - name: POD_NAME
+///     valueFrom: { fieldRef: { fieldPath: metadata.name } }
+///   - name: POD_NAMESPACE
+///     valueFrom: { fieldRef: { fieldPath: metadata.namespace } }
+///   - name: NODE_NAME
+///     valueFrom: { fieldRef: { fieldPath: spec.nodeName } }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use logging::{KubernetesHandler, Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// std::env::set_var("POD_NAME", "shop-7d9f8-x2x4z");
+/// std::env::set_var("POD_NAMESPACE", "production");
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("shop");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(KubernetesHandler::new(Arc::clone(&memory)).with_variable("team", "TEAM"));
+/// logger.info("Hello World");
+///
+/// let record = &memory.records()[0];
+/// assert_eq!(record.field("k8s.pod.name").unwrap(), "shop-7d9f8-x2x4z");
+/// assert_eq!(record.field("k8s.namespace.name").unwrap(), "production");
+/// assert_eq!(record.field("team"), None);
+/// ```
+#[derive(Clone)]
+pub struct KubernetesHandler {
+    inner: Arc<dyn Handler>,
+    fields: Vec<(String, String)>,
+}
+impl KubernetesHandler {
+    /// Wrap a handler, reading the metadata from the default variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: The handler receiving the enriched records.
+    ///
+    /// returns: KubernetesHandler
+    pub fn new<T: Handler + 'static>(inner: T) -> Self {
+        let handler = Self { inner: Arc::new(inner), fields: Vec::new() };
+        DEFAULT_VARIABLES.iter().fold(handler, |handler, (field, variable)| handler.with_variable(field, variable))
+    }
+    /// Add a field from another environment variable, e.g. for labels exposed through the downward API.
+    ///
+    /// # Arguments
+    ///
+    /// * `field`: The name of the field.
+    /// * `variable`: The environment variable to read the value from. Nothing is added if it isn't set.
+    ///
+    /// returns: KubernetesHandler
+    pub fn with_variable(mut self, field: impl ToString, variable: &str) -> Self {
+        if let Ok(value) = std::env::var(variable) {
+            self.fields.push((field.to_string(), value));
+        }
+        self
+    }
+    /// The fields added to every record.
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+}
+impl Handler for KubernetesHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        if self.fields.is_empty() {
+            return self.inner.handle(record);
+        }
+        let mut record = record.clone();
+        for (key, value) in &self.fields {
+            if record.field(key).is_none() {
+                record.fields.push((key.clone(), value.as_str().into()));
+            }
+        }
+        self.inner.handle(&record)
+    }
+    fn reopen(&self) {
+        self.inner.reopen()
+    }
+    fn close(&self) {
+        self.inner.close()
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        self.inner.accepts(record)
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("KubernetesHandler").with_inner(self.inner.describe())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        self.inner.health()
+    }
+}
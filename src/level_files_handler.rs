@@ -0,0 +1,114 @@
+use crate::{ConfiguredHandler, FileHandler, Handler, HandlerConfig, Level, LogLevel, Record};
+use std::io;
+use std::ops::RangeBounds;
+use std::path::Path;
+
+/// Writes records to separate files depending on their level, e.g. `debug.log` and `error.log`,
+/// like the per-level file handlers of many Python logging configurations.
+///
+/// Every file is a [FileHandler](FileHandler) behind a [ConfiguredHandler](ConfiguredHandler) with a
+/// [level range](ConfiguredHandler::with_levels). If ranges overlap, records are written to every file they fall into.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, LevelFilesHandler, Logger};
+/// let dir = std::env::temp_dir().join(format!("logging-levels-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let handler = LevelFilesHandler::new()
+///     .with_file(..Level::WARN, dir.join("app.log")).unwrap()
+///     .with_file(Level::WARN.., dir.join("problems.log")).unwrap();
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(handler);
+/// logger.info("Hello World");
+/// logger.error("Something failed");
+///
+/// assert_eq!(std::fs::read_to_string(dir.join("app.log")).unwrap(), "INFO (foo): Hello World\n");
+/// assert_eq!(std::fs::read_to_string(dir.join("problems.log")).unwrap(), "ERROR (foo): Something failed\n");
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct LevelFilesHandler {
+    files: Vec<ConfiguredHandler>,
+}
+impl LevelFilesHandler {
+    /// Create a handler without any files. Add them with [with_file](LevelFilesHandler::with_file).
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Write one file per registered level into a directory, named after the level, e.g. `warn.log`.
+    /// Every file receives the records from its level up to the next registered level, e.g. `error.log` gets levels 40 to 49.
+    /// Records below the lowest level go to its file as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`: The directory to create the files in. It has to exist.
+    ///
+    /// returns: Result<LevelFilesHandler, Error>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Level, LevelFilesHandler, Logger};
+    /// let dir = std::env::temp_dir().join(format!("logging-per-level-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(LevelFilesHandler::per_level(&dir).unwrap());
+    /// logger.log("Almost an error", 45);
+    /// assert!(std::fs::read_to_string(dir.join("error.log")).unwrap().contains("Almost an error"));
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn per_level(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let levels: Vec<(LogLevel, String)> = Level::get_levels().into_iter()
+            .filter(|(level, _)| *level != Level::MIN && *level != Level::MAX)
+            .collect();
+        let mut handler = Self::new();
+        for (index, (level, name)) in levels.iter().enumerate() {
+            let start = if index == 0 { Level::MIN } else { *level };
+            let end = levels.get(index + 1).map_or(Level::MAX, |(next, _)| next - 1);
+            let path = dir.as_ref().join(format!("{}.log", name.to_lowercase()));
+            handler = handler.with_file(start..=end, path)?;
+        }
+        Ok(handler)
+    }
+    /// Add a file receiving the records in a range of levels.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels`: The range of levels, e.g. `Level::WARN..`.
+    /// * `path`: The file to write to. It is created if it doesn't exist yet.
+    ///
+    /// returns: Result<LevelFilesHandler, Error>
+    pub fn with_file(mut self, levels: impl RangeBounds<LogLevel>, path: impl AsRef<Path>) -> io::Result<Self> {
+        self.files.push(ConfiguredHandler::new(FileHandler::new(path)?).with_levels(levels));
+        Ok(self)
+    }
+}
+impl Handler for LevelFilesHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        for file in &self.files {
+            file.handle(record);
+        }
+    }
+    fn reopen(&self) {
+        for file in &self.files {
+            file.reopen();
+        }
+    }
+    fn close(&self) {
+        for file in &self.files {
+            file.close();
+        }
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        self.files.iter().any(|file| file.accepts(record))
+    }
+    fn describe(&self) -> HandlerConfig {
+        self.files.iter().fold(HandlerConfig::new("LevelFilesHandler"), |config, file| config.with_inner(file.describe()))
+    }
+}
@@ -1,14 +1,149 @@
 // mod logger_old;
 mod logger;
 mod macros;
+mod file_handler;
+mod rotating_file_handler;
+mod level_files_handler;
+mod subtree_files_handler;
+mod jsonl_file_handler;
+mod csv_handler;
+mod binary_handler;
+mod chrome_trace_handler;
+mod memory_handler;
+mod default_logger;
+mod init;
+mod record;
+mod formatter;
+mod filter;
+mod configured_handler;
+mod stopwatch;
+mod progress;
+mod hexdump;
+mod pretty;
+mod log_result;
+mod error_chain;
+mod redacting_handler;
+mod sanitizing_handler;
+mod kubernetes_handler;
+mod tcp_handler;
+mod syslog_handler;
+mod circuit_breaker_handler;
+mod aggregating_handler;
+mod async_handler;
+mod stats;
+mod heartbeat;
+mod startup;
+mod crash_reporter;
+mod fatal;
+mod subscribe;
+mod replay;
+mod config;
+mod fields;
+mod system;
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+mod anyhow_support;
+#[cfg(feature = "audit")]
+mod audit_handler;
+#[cfg(feature = "encryption")]
+mod encrypted_file_handler;
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(windows)]
+mod named_pipe_handler;
+#[cfg(feature = "notifications")]
+mod notification_handler;
+#[cfg(feature = "datadog")]
+mod datadog_handler;
+#[cfg(feature = "rtt")]
+mod rtt_handler;
+#[cfg(feature = "log4rs")]
+mod log4rs_config;
+#[cfg(feature = "dict_config")]
+mod dict_config;
 #[allow(non_snake_case)]
 pub mod Level;
+#[cfg(feature = "admin")]
+pub mod admin;
+#[cfg(all(unix, feature = "signals"))]
+pub mod signals;
+pub mod correlation;
+pub mod testing;
+pub mod http;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
 
 use std::sync::{Arc, RwLock};
 
 #[cfg(feature = "coloured_output")]
 use ansi_term::Color;
 
+pub use file_handler::{FileHandler, SyncPolicy};
+pub use rotating_file_handler::RotatingFileHandler;
+pub use level_files_handler::LevelFilesHandler;
+pub use subtree_files_handler::SubtreeFilesHandler;
+pub use jsonl_file_handler::JsonlFileHandler;
+pub use csv_handler::CsvHandler;
+pub use binary_handler::{BinaryHandler, RecordReader};
+pub use replay::{read_log_file, replay, JsonlReader};
+pub use chrome_trace_handler::ChromeTraceHandler;
+pub use memory_handler::{MemoryHandler, MemoryQuery};
+pub use default_logger::{critical, debug, default_logger, error, fatal, info, log, set_default_logger, success, warn, with_default};
+#[doc(hidden)]
+pub use default_logger::__macro_logger;
+pub use init::{init_from_env, init_simple, init_timed, profile, Profile};
+pub use record::{Record, Source};
+pub use formatter::{DefaultFormatter, DockerJsonFormatter, EcsFormatter, Formatter, GcpFormatter, JsonFormatter, PatternFormatter, SCHEMA_VERSION};
+pub use filter::{Filter, LoggerFilter, ModuleFilter, TagFilter};
+pub use configured_handler::ConfiguredHandler;
+pub use stopwatch::Stopwatch;
+pub use progress::Progress;
+pub use hexdump::{hexdump, HEXDUMP_MAX_LEN};
+pub use pretty::pretty_debug;
+pub use log_result::{LogOption, LogResult};
+pub use error_chain::format_error_chain;
+pub use redacting_handler::RedactingHandler;
+pub use sanitizing_handler::SanitizingHandler;
+pub use kubernetes_handler::KubernetesHandler;
+pub use tcp_handler::TcpHandler;
+pub use syslog_handler::{SyslogFormat, SyslogHandler};
+pub use circuit_breaker_handler::CircuitBreakerHandler;
+pub use aggregating_handler::AggregatingHandler;
+pub use async_handler::{AsyncAdapter, AsyncHandler, AsyncRuntime};
+#[cfg(feature = "audit")]
+pub use audit_handler::{AuditHandler, AuditStatus};
+#[cfg(feature = "encryption")]
+pub use encrypted_file_handler::{decrypt_log, EncryptedFileHandler};
+#[cfg(feature = "tls")]
+pub use tls::TlsConfig;
+#[cfg(windows)]
+pub use named_pipe_handler::NamedPipeHandler;
+#[cfg(feature = "notifications")]
+pub use notification_handler::NotificationHandler;
+#[cfg(feature = "datadog")]
+pub use datadog_handler::DatadogHandler;
+#[cfg(feature = "rtt")]
+pub use rtt_handler::RttHandler;
+#[cfg(feature = "log4rs")]
+pub use log4rs_config::{apply_log4rs_config, load_log4rs_config};
+#[cfg(feature = "dict_config")]
+pub use dict_config::{apply_dict_config, load_dict_config};
+pub use fields::{set_field_serializer, FieldValue, LazyValue, ToFieldValue};
+#[doc(hidden)]
+pub use fields::__private as __fields;
+pub use config::{export_config, ConfigDocument, HandlerConfig, LoggerConfig};
+pub use heartbeat::{heartbeat, Heartbeat};
+pub use startup::{log_startup_info, StartupInfo};
+pub use crash_reporter::CrashReporter;
+pub use fatal::{set_fatal_policy, set_stderr_mirror_level, FatalPolicy};
+pub use subscribe::{subscribe, RecordStream};
+pub use stats::{handler_health, record_dropped, record_handler_error, record_handler_failure, set_drop_report_interval, stats, Counts, HandlerHealth, HealthState, Stats};
+#[cfg(feature = "anyhow")]
+pub use anyhow_support::format_anyhow;
+#[cfg(feature = "eyre")]
+pub use anyhow_support::format_eyre;
+#[cfg(feature = "macros")]
+pub use logging_macros::{instrument, test};
+
 pub type LogLevel = i32;
 
 
@@ -17,14 +152,17 @@ pub type LogLevel = i32;
 /// Loggers are in a hierarchical structure, so sections of loggers can be turned on and off. 
 pub struct Logger {
     inner: Arc<RwLock<logger::Logger>>,
+    fields: Vec<(String, FieldValue)>,
+    tags: Vec<String>,
 }
 impl Logger {
     /// Create a new logger.
     /// 
     /// # Arguments 
     /// 
-    /// * `name`: The name of the logger. 
-    /// Sub-logger can be created with a dot, so that `logging::Logger::new("foo::bar");` is a sub-logger of `logging::Logger::new("foo");`
+    /// * `name`: The name of the logger.
+    ///   Sub-logger can be created with `::`, so that `logging::Logger::new("foo::bar");` is a sub-logger of `logging::Logger::new("foo");`.
+    ///   The empty name refers to the root logger.
     /// 
     /// 
     /// returns: Logger 
@@ -32,11 +170,13 @@ impl Logger {
     /// # Examples 
     /// 
     /// ```
-    /// let logger = logging::Logger::new("foo.bar");
+    /// let logger = logging::Logger::new("foo::bar");
     /// ```
     pub fn new(name: impl ToString) -> Self {
         Logger {
             inner: logger::get_logger(name.to_string()),
+            fields: Vec::new(),
+            tags: Vec::new(),
         }
     }
     /// Log a message.
@@ -52,15 +192,215 @@ impl Logger {
     /// 
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
+    /// logging::set_level(Level::ALL);
+    /// let logger = logging::Logger::new("foo");
+    /// logger.log("Hello World", Level::INFO);
+    /// ```
+    pub fn log(&self, msg: impl Into<String>, level: LogLevel) {
+        self.log_fields(msg, level, Vec::new())
+    }
+    /// Log a message at a level given by its name, looked up with [Level::by_name](Level::by_name),
+    /// e.g. for plugins or scripts that only know the names of the levels.
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The name of the level, e.g. `AUDIT` (case-insensitive).
+    /// * `msg`: The message to be logged.
+    ///
+    /// returns: Result<(), UnknownLevel> An error if no level of that name is registered, in which case nothing is logged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Level, Logger};
+    /// Level::add_level(45, "AUDIT".to_string());
+    /// let logger = Logger::new("plugins::backup");
+    /// logger.set_level(Level::ALL);
+    /// logger.log_named("audit", "backup deleted").unwrap();
+    /// assert_eq!(logger.log_named("verbose", "lots of details").unwrap_err().to_string(), "unknown level 'verbose'");
+    /// ```
+    pub fn log_named(&self, level: &str, msg: impl Into<String>) -> Result<(), Level::UnknownLevel> {
+        let level = Level::by_name(level).ok_or_else(|| Level::UnknownLevel(level.to_string()))?;
+        self.log(msg, level);
+        Ok(())
+    }
+    /// Log a message with structured key-value fields.
+    /// Values of fields on the scrub list (see [set_scrubbed_fields](set_scrubbed_fields)) are replaced with `***`.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg`: The message to be logged.
+    /// * `level`: The level at which to log the message.
+    /// * `fields`: The fields attached to the message. See [fields!](fields) for a shorthand.
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{fields, Level};
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.log("Hello World".to_string(), Level::INFO);
+    /// // prints "INFO (foo): user logged in user=jane attempts=3"
+    /// logger.log_fields("user logged in", Level::INFO, fields!{ user = "jane", attempts = 3 });
+    /// ```
+    pub fn log_fields(&self, msg: impl Into<String>, level: LogLevel, fields: Vec<(String, FieldValue)>) {
+        self.dispatch(msg.into(), level, fields, None, None, Vec::new())
+    }
+    /// Log a message given as a template with the arguments kept separately, so structured backends can group the records by the template
+    /// (see [Record::template](Record::template)) while text handlers show the rendered message.
+    ///
+    /// Every `{name}` in the template is replaced with the value of the argument `name`. Placeholders without an argument are left as they are,
+    /// and `{{` and `}}` stand for `{` and `}`. The arguments are attached as fields as well,
+    /// and the message is only rendered if the level is enabled, after scrubbing (see [set_scrubbed_fields](set_scrubbed_fields)).
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The level at which to log the message.
+    /// * `template`: The template of the message, e.g. `user {user} did {action}`.
+    /// * `args`: The values of the placeholders. See [fields!](fields) for a shorthand.
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
     /// ```
-    pub fn log(&self, msg: String, level: LogLevel) {
+    /// use logging::{fields, Level, Logger, MemoryHandler};
+    /// use std::sync::Arc;
+    /// let memory = Arc::new(MemoryHandler::new(10));
+    /// let logger = Logger::new("audit");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(Arc::clone(&memory));
+    ///
+    /// logger.log_template(Level::INFO, "user {user} did {action}", fields! { user = "jane", action = "login" });
+    /// let record = &memory.records()[0];
+    /// assert_eq!(record.message, "user jane did login");
+    /// assert_eq!(record.template.as_deref(), Some("user {user} did {action}"));
+    /// assert_eq!(record.field("user").unwrap().to_string(), "jane");
+    /// ```
+    pub fn log_template(&self, level: LogLevel, template: impl Into<String>, args: Vec<(String, FieldValue)>) {
+        self.dispatch(String::new(), level, args, None, Some(template.into()), Vec::new())
+    }
+    fn dispatch(&self, msg: String, level: LogLevel, fields: Vec<(String, FieldValue)>, source: Option<Source>, template: Option<String>, mut tags: Vec<String>) {
+        let fields = if self.fields.is_empty() {
+            fields
+        } else {
+            self.fields.iter().cloned().chain(fields).collect()
+        };
+        for tag in self.tags.iter().rev() {
+            if !tags.contains(tag) {
+                tags.insert(0, tag.clone());
+            }
+        }
         let locked = self.inner.read().expect("Logger is poisoned");
-        locked.log(msg, level)
+        let logged = level >= locked.level();
+        locked.log_fields(msg, level, fields, source, template, tags);
+        drop(locked);
+        if logged {
+            fatal::after_logged(level);
+        }
+    }
+    /// Log preformatted arguments, as created by [format_args!].
+    /// The message is only turned into a `String` if the level is enabled, so disabled records don't allocate.
+    /// This is what the logging macros like [info!](info!) use.
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The level at which to log the message.
+    /// * `args`: The message.
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::Level;
+    /// let logger = logging::Logger::new("foo");
+    /// logger.set_level(Level::INFO);
+    /// // never formatted
+    /// logger.log_args(Level::DEBUG, format_args!("{:?}", vec![0u8; 1024]));
+    /// logger.log_args(Level::INFO, format_args!("Hello {}", "World"));
+    /// ```
+    pub fn log_args(&self, level: LogLevel, args: std::fmt::Arguments) {
+        if level >= self.level() {
+            self.log(args.to_string(), level)
+        }
+    }
+    /// Like [log_args](Logger::log_args), but records where the message was logged. Used by the logging macros.
+    #[doc(hidden)]
+    pub fn __log_args_at(&self, level: LogLevel, args: std::fmt::Arguments, source: Source) {
+        self.__log_args_tagged_at(level, args, source, &[])
+    }
+    /// Like [__log_args_at](Logger::__log_args_at), but with [tags](Record::tags). Used by the `tags:` form of the logging macros.
+    #[doc(hidden)]
+    pub fn __log_args_tagged_at(&self, level: LogLevel, args: std::fmt::Arguments, source: Source, tags: &[&str]) {
+        if level >= self.level() {
+            let tags = tags.iter().map(|tag| tag.to_string()).collect();
+            self.dispatch(args.to_string(), level, Vec::new(), Some(source), None, tags)
+        }
+    }
+    /// Derive a logger that attaches the given fields to every record, in front of the fields of the individual call.
+    /// The derived logger is the same logger in the hierarchy, so it shares name, level and handlers with this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields`: The fields to attach to every record.
+    ///
+    /// returns: Logger
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Level, Logger, MemoryHandler};
+    /// use std::sync::Arc;
+    /// let memory = Arc::new(MemoryHandler::new(10));
+    /// let logger = Logger::new("storage");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(Arc::clone(&memory));
+    ///
+    /// let shard = logger.with_fields([("component", "db"), ("shard", "3")]);
+    /// shard.info("compacted");
+    /// assert_eq!(memory.records()[0].field("shard").unwrap(), "3");
+    /// ```
+    pub fn with_fields<K: ToString, V: Into<FieldValue>>(&self, fields: impl IntoIterator<Item = (K, V)>) -> Logger {
+        let mut logger = self.clone();
+        logger.fields.extend(fields.into_iter().map(|(key, value)| (key.to_string(), value.into())));
+        logger
+    }
+    /// Derive a logger that attaches the given audience tags to every record, in addition to the tags of the individual call (see [Record::tags](Record::tags)).
+    /// Like with [with_fields](Logger::with_fields), the derived logger is the same logger in the hierarchy.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags`: The tags to attach to every record, e.g. `user-facing`.
+    ///
+    /// returns: Logger
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Level, Logger, MemoryHandler};
+    /// use std::sync::Arc;
+    /// let memory = Arc::new(MemoryHandler::new(10));
+    /// let logger = Logger::new("editor");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(Arc::clone(&memory));
+    ///
+    /// let status = logger.with_tags(["user-facing"]);
+    /// status.info("saved notes.txt");
+    /// assert!(memory.records()[0].has_tag("user-facing"));
+    /// ```
+    pub fn with_tags<T: ToString>(&self, tags: impl IntoIterator<Item = T>) -> Logger {
+        let mut logger = self.clone();
+        for tag in tags {
+            let tag = tag.to_string();
+            if !logger.tags.contains(&tag) {
+                logger.tags.push(tag);
+            }
+        }
+        logger
     }
     /// Debug a message or value. Equal to [log](Logger::log)(msg, [Level::DEBUG](Level::DEBUG)).
     /// 
@@ -74,13 +414,13 @@ impl Logger {
     /// 
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.debug("Hello World".to_string());
+    /// logger.debug("Hello World");
     /// ```
-    pub fn debug(&self, msg: String) {
+    pub fn debug(&self, msg: impl Into<String>) {
         self.log(msg, Level::DEBUG)
     }
     /// Log an information. Equal to [log](Logger::log)(msg, [Level::INFO](Level::INFO)).
@@ -95,13 +435,13 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.info("Hello World".to_string());
+    /// logger.info("Hello World");
     /// ```
-    pub fn info(&self, msg: String) {
+    pub fn info(&self, msg: impl Into<String>) {
         self.log(msg, Level::INFO)
     }
     /// Log a success. Equal to [log](Logger::log)(msg, [Level::SUCCESS](Level::SUCCESS)).
@@ -116,13 +456,13 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.success("Hello World".to_string());
+    /// logger.success("Hello World");
     /// ```
-    pub fn success(&self, msg: String) {
+    pub fn success(&self, msg: impl Into<String>) {
         self.log(msg, Level::SUCCESS)
     }
 
@@ -138,13 +478,13 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.success("Hello World".to_string());
+    /// logger.success("Hello World");
     /// ```
-    pub fn warn(&self, msg: String) {
+    pub fn warn(&self, msg: impl Into<String>) {
         self.log(msg, Level::WARN);
     }
     /// Log an error. Equal to [log](Logger::log)(msg, [Level::ERROR](Level::ERROR)).
@@ -159,15 +499,36 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.error("Hello World".to_string());
+    /// logger.error("Hello World");
     /// ```
-    pub fn error(&self, msg: String) {
+    pub fn error(&self, msg: impl Into<String>) {
         self.log(msg, Level::ERROR)
     }
+    /// Log an error and all of its causes at [Level::ERROR](Level::ERROR) (see [format_error_chain](format_error_chain)).
+    ///
+    /// # Arguments
+    ///
+    /// * `err`: The error to be logged.
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::Level;
+    /// let logger = logging::Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// if let Err(err) = std::fs::read_to_string("/does/not/exist") {
+    ///     logger.error_chain(&err);
+    /// }
+    /// ```
+    pub fn error_chain(&self, err: &dyn std::error::Error) {
+        self.error(format_error_chain(err))
+    }
 
     /// Log a message when something goes critically wrong. Equal to [log](Logger::log)(msg, [Level::CRITICAL](Level::CRITICAL)).
     ///
@@ -181,13 +542,13 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.critical("Hello World".to_string());
+    /// logger.critical("Hello World");
     /// ```
-    pub fn critical(&self, msg: String) {
+    pub fn critical(&self, msg: impl Into<String>) {
         self.log(msg, Level::CRITICAL)
     }
     /// Log a message when something goes fatally wrong. Equal to [log](Logger::log)(msg, [Level::FATAL](Level::FATAL)).
@@ -202,15 +563,38 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo");
-    /// logger.fatal("Hello World".to_string());
+    /// logger.fatal("Hello World");
     /// ```
-    pub fn fatal(&self, msg: String) {
+    pub fn fatal(&self, msg: impl Into<String>) {
         self.log(msg, Level::FATAL)
     }
+    /// Log bytes as a hex dump at [Level::DEBUG](Level::DEBUG) (see [hexdump](hexdump)).
+    /// At most [HEXDUMP_MAX_LEN](HEXDUMP_MAX_LEN) bytes are dumped.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes`: The bytes to dump.
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::Level;
+    /// let logger = logging::Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.debug_hexdump(b"GET / HTTP/1.1\r\n");
+    /// ```
+    pub fn debug_hexdump(&self, bytes: &[u8]) {
+        if Level::DEBUG < self.level() {
+            return;
+        }
+        self.debug(format!("{} bytes:\n{}", bytes.len(), hexdump(bytes, HEXDUMP_MAX_LEN)))
+    }
     /// Set the minimum Level the logger and all children log at.
     ///
     /// # Arguments
@@ -223,42 +607,42 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// let logger = logging::Logger::new("foo");
     /// logger.set_level(Level::ALL);
     /// // will be logged
-    /// logger.debug("Hello World".to_string());
+    /// logger.debug("Hello World");
     ///
     /// logger.set_level(Level::INFO);
     /// // will not be logged
-    /// logger.debug("Hello World".to_string());
+    /// logger.debug("Hello World");
     /// // will be logged
-    /// logger.info("Hello World".to_string());
+    /// logger.info("Hello World");
     /// ```
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// use logging::ConsoleHandler;
+    /// logging::add_handler(ConsoleHandler::new());
     /// let parent = logging::Logger::new("foo");
-    /// let child = logging::Logger::new("foo.bar");
+    /// let child = logging::Logger::new("foo::bar");
     /// parent.set_level(Level::INFO);
     /// // will be logged
-    /// child.info("Hello World".to_string());
+    /// child.info("Hello World");
     /// // will not be logged
-    /// child.debug("Hello World".to_string());
+    /// child.debug("Hello World");
     /// child.set_level(Level::DEBUG);
     /// // will be logged
-    /// child.debug("Hello World".to_string());
+    /// child.debug("Hello World");
     /// // will not be logged
-    /// parent.debug("Hello World".to_string())
+    /// parent.debug("Hello World")
     /// ```
     pub fn set_level(&self, new_level: LogLevel) {
         let mut locked = self.inner.write().expect("Logger is poisoned");
         locked.set_level(new_level)
     }
     /// Add a handler to this logger and all children (similar to [set_level](Logger::set_level)).
-    /// Handlers are used to actually log the messages, e.g. the [CONSOLE_HANDLER](CONSOLE_HANDLER) will log messages to the console.
+    /// Handlers are used to actually log the messages, e.g. the [ConsoleHandler](ConsoleHandler) will log messages to the console.
     /// without any handlers, the messages will not be saved/printed/etc.
     ///
     /// # Arguments
@@ -271,23 +655,82 @@ impl Logger {
     ///
     /// ```
     /// use logging::Level;
-    /// use logging::CONSOLE_HANDLER;
+    /// use logging::ConsoleHandler;
     ///
     /// logging::set_level(Level::ALL);
     /// let logger = logging::Logger::new("foo".to_string());
     ///
     /// // will do nothing
-    /// logger.info("This won't print".to_string());
+    /// logger.info("This won't print");
     ///
-    /// logging::add_handler(&CONSOLE_HANDLER);
+    /// logging::add_handler(ConsoleHandler::new());
     ///
     /// // now it will print to the console
-    /// logger.info("This will print to the console. Maybe even in a coloured output (if you have that feature enabled).".to_string())
+    /// logger.info("This will print to the console. Maybe even in a coloured output (if you have that feature enabled).")
     /// ```
     pub fn add_handler<T: Handler + 'static>(&self, handler: T) {
         let mut locked = self.inner.write().expect("Logger is poisoned");
         locked.add_handler(Arc::new(handler))
     }
+    /// The full name of the logger, e.g. `foo::bar`. The root logger has an empty name.
+    ///
+    /// returns: String
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let logger = logging::Logger::new("foo::bar");
+    /// assert_eq!(logger.name(), "foo::bar");
+    /// assert_eq!(logging::Logger::new("").name(), "");
+    /// ```
+    pub fn name(&self) -> String {
+        self.inner.read().expect("Logger is poisoned").name().to_string()
+    }
+    /// The minimum level this logger currently logs at.
+    ///
+    /// returns: LogLevel
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::Level;
+    /// let logger = logging::Logger::new("foo");
+    /// logger.set_level(Level::WARN);
+    /// assert_eq!(logger.level(), Level::WARN);
+    /// ```
+    pub fn level(&self) -> LogLevel {
+        self.inner.read().expect("Logger is poisoned").level()
+    }
+    /// Disconnect this logger and its children from the hierarchy, e.g. when a plugin is unloaded.
+    ///
+    /// The loggers keep the level and handlers they have, but no longer get the levels and handlers set on their parents
+    /// and no longer show up in [loggers](loggers) or the [config export](export_config).
+    /// They are dropped together with their handlers once the last [Logger](Logger) referring to them is dropped.
+    /// [Logger::new](Logger::new) with the same name afterwards creates a new logger, inheriting from its parent again.
+    /// The root logger can't be detached.
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Level, Logger, MemoryHandler};
+    /// use std::sync::Arc;
+    /// let plugin = Logger::new("plugins::spellcheck");
+    /// plugin.set_level(Level::INFO);
+    /// plugin.detach();
+    /// assert!(logging::find_logger("plugins::spellcheck").is_none());
+    ///
+    /// // handlers added to the parent don't reach the detached logger anymore
+    /// let memory = Arc::new(MemoryHandler::new(10));
+    /// Logger::new("plugins").add_handler(Arc::clone(&memory));
+    /// plugin.info("unloading");
+    /// assert!(memory.records().is_empty());
+    /// assert_eq!(plugin.level(), Level::INFO);
+    /// ```
+    pub fn detach(&self) {
+        logger::detach(&self.inner)
+    }
 }
 /// A handler for loggers.
 /// These handle the messages and are responsible for logging the messages to whatever medium they are made to log to.
@@ -306,11 +749,11 @@ pub trait Handler: Send + Sync {
     /// # Examples
     ///
     /// ```
-    /// use logging::{Logger, Level, Handler};
+    /// use logging::{Logger, Level, Handler, LogLevel};
     ///
     /// struct ConsoleHandler {}
     /// impl Handler for ConsoleHandler {
-    ///     fn log(&self, level: Level, message: String, logger: String) {
+    ///     fn log(&self, level: LogLevel, message: String, logger: String) {
     ///         println!("{} {:?}: {}", logger, level, message);
     ///     }
     /// }
@@ -318,25 +761,203 @@ pub trait Handler: Send + Sync {
     /// logger.set_level(Level::ALL);
     ///
     /// // does nothing
-    /// logger.info("won't log".to_string());
+    /// logger.info("won't log");
     ///
-    /// logger.add_handler(&ConsoleHandler{});
+    /// logger.add_handler(ConsoleHandler{});
     /// // will log
-    /// logger.info("will print to console".to_string());
+    /// logger.info("will print to console");
     ///
     /// ```
     fn log(&self, level: LogLevel, message: String, logger: String);
+    /// Handle a [Record](Record).
+    /// This is what the loggers call. By default, it passes the level, message and logger name on to [log](Handler::log).
+    /// Handlers that need more information about the message (e.g. the time it was logged at) should override this.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: The record to log.
+    ///
+    /// returns: ()
+    fn handle(&self, record: &Record) {
+        self.log(record.level, record.message.clone(), record.logger.clone())
+    }
+    /// Reopen whatever the handler writes to, e.g. after the log file has been moved away by logrotate.
+    /// Handlers without anything to reopen can ignore this.
+    ///
+    /// returns: ()
+    fn reopen(&self) {}
+    /// Finish up before the program exits, e.g. write a footer and flush buffered records.
+    /// Called by [shutdown](shutdown). Handlers without anything to finish can ignore this.
+    ///
+    /// returns: ()
+    fn close(&self) {}
+    /// Whether the handler would do anything with a record, used by [would_log](would_log) to find out if logging is worth it.
+    /// The record has an empty message. Handlers that drop records, e.g. because of their level or filters, should override this.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: The record that would be logged.
+    ///
+    /// returns: bool `true` by default.
+    fn accepts(&self, _record: &Record) -> bool {
+        true
+    }
+    /// Describe the handler for [export_config](export_config). By default, only the name of its type is given.
+    /// Handlers with settings worth knowing about (e.g. the file they write to) or wrapping other handlers should override this.
+    ///
+    /// returns: HandlerConfig
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new(config::short_type_name::<Self>())
+    }
+    /// Report how the handler is doing, e.g. for a [CircuitBreakerHandler](CircuitBreakerHandler) to notice failures, including those on background threads.
+    /// The built-in handlers report their entry of [handler_health](handler_health), handlers wrapping another one report the health of the inner handler.
+    ///
+    /// returns: Option<HandlerHealth> `None` by default, for handlers that don't keep track of their health.
+    fn health(&self) -> Option<HandlerHealth> {
+        None
+    }
+}
+/// Shares one handler between several loggers, e.g. to attach a [FileHandler](FileHandler) to two separate subtrees.
+impl<T: Handler + ?Sized> Handler for Arc<T> {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        (**self).log(level, message, logger)
+    }
+    fn handle(&self, record: &Record) {
+        (**self).handle(record)
+    }
+    fn reopen(&self) {
+        (**self).reopen()
+    }
+    fn close(&self) {
+        (**self).close()
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        (**self).accepts(record)
+    }
+    fn describe(&self) -> HandlerConfig {
+        (**self).describe()
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        (**self).health()
+    }
 }
+/// The stream a [ConsoleHandler](ConsoleHandler) writes to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsoleStream {
+    /// Standard output. With the `std_err` feature, records at [ERROR](Level::ERROR) and above are written to standard error as well
+    /// (see [with_stderr_level](ConsoleHandler::with_stderr_level)).
+    Stdout,
+    /// Standard error only.
+    Stderr,
+}
+
 /// A default implementation of [Handler](Handler).
 /// Logs to the console in a potentially coloured output (if you have the coloured_output feature enabled).
-pub struct ConsoleHandler;
+///
+/// Lines look like the ones of the [DefaultFormatter](DefaultFormatter) unless the record has been [formatted](Record::formatted) already,
+/// e.g. by a [ConfiguredHandler](ConfiguredHandler). The layout can be changed with the `with_` methods.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{ConsoleHandler, ConsoleStream, Level, Logger};
+/// let handler = ConsoleHandler::new()
+///     .with_time(true)
+///     .with_logger_name(false)
+///     .with_level_width(8)
+///     .with_stream(ConsoleStream::Stderr)
+///     .with_colour(false);
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(handler);
+/// // prints e.g. "2024-05-01T12:30:00.000Z INFO    : Hello World" to stderr
+/// logger.info("Hello World");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ConsoleHandler {
+    time: bool,
+    logger_name: bool,
+    stream: ConsoleStream,
+    stderr_level: Option<LogLevel>,
+    level_width: usize,
+    colour: bool,
+}
+impl Default for ConsoleHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl ConsoleHandler {
+    /// Create a handler printing `LEVEL (logger): message` lines to standard output, coloured with the `coloured_output` feature.
+    pub fn new() -> Self {
+        Self {
+            time: false,
+            logger_name: true,
+            stream: ConsoleStream::Stdout,
+            stderr_level: cfg!(feature = "std_err").then_some(Level::ERROR),
+            level_width: 0,
+            colour: cfg!(feature = "coloured_output"),
+        }
+    }
+    /// Start every line with the time the record was logged at (RFC 3339, UTC).
+    pub fn with_time(mut self, time: bool) -> Self {
+        self.time = time;
+        self
+    }
+    /// Show the name of the logger after the level. On by default.
+    pub fn with_logger_name(mut self, logger_name: bool) -> Self {
+        self.logger_name = logger_name;
+        self
+    }
+    /// Set the stream to write to.
+    pub fn with_stream(mut self, stream: ConsoleStream) -> Self {
+        self.stream = stream;
+        self
+    }
+    /// Write records at or above this level to standard error as well when writing to [standard output](ConsoleStream::Stdout),
+    /// or none with `None`. [ERROR](Level::ERROR) with the `std_err` feature, otherwise `None`.
+    pub fn with_stderr_level(mut self, level: Option<LogLevel>) -> Self {
+        self.stderr_level = level;
+        self
+    }
+    /// Pad the level names to this width, so the messages line up.
+    pub fn with_level_width(mut self, width: usize) -> Self {
+        self.level_width = width;
+        self
+    }
+    /// Colour the lines by level. Only has an effect with the `coloured_output` feature, where it is on by default.
+    pub fn with_colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+    fn line(&self, record: &Record) -> String {
+        if let Some(formatted) = &record.formatted {
+            return formatted.clone();
+        }
+        let mut line = String::new();
+        if self.time {
+            line.push_str(&formatter::format_time(record.time));
+            line.push(' ');
+        }
+        line.push_str(&format!("{:<width$}", Level::name(record.level), width = self.level_width));
+        if self.logger_name {
+            line.push_str(&format!(" ({})", record.logger));
+        }
+        line.push_str(": ");
+        line.push_str(&record.message);
+        formatter::push_details(&mut line, record);
+        line
+    }
+}
 impl Handler for ConsoleHandler {
     fn log(&self, level: LogLevel, message: String, logger_name: String) {
-        let level_name = Level::get_level(level).unwrap_or(level.to_string());
-        let log_str = format!("{} ({}): {}", level_name, logger_name, message);
+        self.handle(&Record::new(level, message, logger_name))
+    }
+    fn handle(&self, record: &Record) {
+        let log_str = self.line(record);
         #[cfg(feature = "coloured_output")]
-        let log_str = {
-            match level {
+        let log_str = if self.colour {
+            match record.level {
                 Level::DEBUG => Color::Blue.normal(),
                 Level::INFO => Color::Yellow.normal(),
                 Level::SUCCESS => Color::Green.normal(),
@@ -345,14 +966,28 @@ impl Handler for ConsoleHandler {
                 Level::CRITICAL => Color::Red.bold(),
                 Level::FATAL => Color::Red.bold().underline(),
                 _ => Color::White.normal(),
-            }.paint(log_str)
+            }.paint(log_str).to_string()
+        } else {
+            log_str
         };
-        #[cfg(feature = "std_err")]
-        if level >= Level::ERROR {
+        if self.stream == ConsoleStream::Stderr {
+            eprintln!("{}", log_str);
+            return;
+        }
+        if self.stderr_level.is_some_and(|level| record.level >= level) {
             eprintln!("{}", log_str);
         }
         println!("{}", log_str);
     }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("ConsoleHandler")
+            .with_setting("stream", format!("{:?}", self.stream))
+            .with_setting("stderr_level", self.stderr_level.map_or("-".to_string(), Level::name))
+            .with_setting("time", self.time)
+            .with_setting("logger_name", self.logger_name)
+            .with_setting("level_width", self.level_width)
+            .with_setting("colour", self.colour)
+    }
 }
 
 /// Set the level globally to all loggers.
@@ -366,20 +1001,164 @@ impl Handler for ConsoleHandler {
 /// # Examples 
 /// 
 /// ```
-/// use logging::{Level, Logger, CONSOLE_HANDLER};
+/// use logging::{Level, Logger, ConsoleHandler};
 /// let logger = Logger::new("foo");
-/// logger.add_handler(&CONSOLE_HANDLER);
+/// logger.add_handler(ConsoleHandler::new());
 /// logger.set_level(Level::CRITICAL);
 /// // won't log
-/// logger.info("This won't log".to_string());
+/// logger.info("This won't log");
 ///
 /// logging::set_level(Level::ALL);
 /// // will log.
-/// logger.info("This will log".to_string());
+/// logger.info("This will log");
 /// ```
 pub fn set_level(level: LogLevel) {
     logger::get_root().write().expect("Logger poisoned").set_level(level)
 }
+/// Set the level of all loggers whose name matches a glob pattern, including loggers created later on.
+///
+/// Patterns are split at `::` like logger names, and `*` matches any part of a single segment,
+/// so `net::*::handshake` matches `net::tcp::handshake` but not `net::handshake`, and `*::db` matches `app::db` and `jobs::db`.
+/// Like with [Logger::set_level](Logger::set_level), the children of a matching logger get the level as well.
+/// If several patterns match a new logger, the one set last wins.
+/// A name without `*` simply sets the level of that logger.
+///
+/// # Arguments
+///
+/// * `pattern`: The pattern of the logger names.
+/// * `level`: The new minimum level of the matching loggers.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// logging::set_level(Level::INFO);
+/// let tcp = Logger::new("net::tcp::handshake");
+/// logging::set_level_for("net::*::handshake", Level::WARN);
+/// assert_eq!(tcp.level(), Level::WARN);
+/// // loggers created later on get the level as well
+/// assert_eq!(Logger::new("net::udp::handshake").level(), Level::WARN);
+/// assert_eq!(Logger::new("net::udp").level(), Level::INFO);
+/// ```
+pub fn set_level_for(pattern: &str, level: LogLevel) {
+    logger::apply_levels(&[(pattern, level)])
+}
+/// Mute a logger and all its children, e.g. a chatty library using this crate, by setting them to [NONE](Level::NONE) and dropping their handlers.
+///
+/// The levels and handlers they had are kept and restored by [unsilence](unsilence). While silenced, the loggers of the subtree stay muted:
+/// level and handler changes to them (e.g. by the library itself or by [set_level](set_level)) only take effect once they are unsilenced,
+/// and loggers created in the subtree later on are silenced as well. Silencing a logger that doesn't exist yet creates it.
+///
+/// # Arguments
+///
+/// * `name`: The full name of the logger to silence together with its children.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let noisy = Logger::new("noisy_dep");
+/// noisy.set_level(Level::DEBUG);
+/// noisy.add_handler(Arc::clone(&memory));
+///
+/// logging::silence_subtree("noisy_dep");
+/// noisy.info("polling");
+/// Logger::new("noisy_dep::pool").debug("idle");
+/// assert_eq!(noisy.level(), Level::NONE);
+/// assert!(memory.records().is_empty());
+///
+/// logging::unsilence("noisy_dep");
+/// assert_eq!(noisy.level(), Level::DEBUG);
+/// noisy.info("polling");
+/// assert_eq!(memory.records().len(), 1);
+/// ```
+pub fn silence_subtree(name: &str) {
+    logger::get_logger(name.to_string()).write().expect("Logger is poisoned").silence()
+}
+/// Undo [silence_subtree](silence_subtree): restore the levels and handlers of a silenced logger and all its children,
+/// including the changes made while they were silenced. Nothing happens if the logger isn't silenced.
+///
+/// # Arguments
+///
+/// * `name`: The full name of the silenced logger.
+///
+/// returns: ()
+pub fn unsilence(name: &str) {
+    if let Some(logger) = logger::find_logger(name) {
+        logger.write().expect("Logger is poisoned").unsilence()
+    }
+}
+/// Set the levels of several loggers at once, e.g. from a configuration file.
+///
+/// All levels are applied while holding the lock of the logger hierarchy once, so no other configuration change
+/// and no new logger can happen in between. Parents are set before their children, so the order of the entries doesn't matter,
+/// and every logger ends up with the level of the most specific entry. Names may be glob patterns (see [set_level_for](set_level_for)).
+///
+/// # Arguments
+///
+/// * `levels`: The names or patterns of the loggers with their new minimum level. The empty name is the root logger.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// logging::apply_levels(&[("app::db", Level::DEBUG), ("", Level::WARN)]);
+/// assert_eq!(Logger::new("app").level(), Level::WARN);
+/// assert_eq!(Logger::new("app::db").level(), Level::DEBUG);
+/// assert_eq!(Logger::new("app::db::pool").level(), Level::DEBUG);
+/// ```
+pub fn apply_levels(levels: &[(&str, LogLevel)]) {
+    logger::apply_levels(levels)
+}
+/// Whether a record would be logged, without logging anything: the logger has to log at the level,
+/// and at least one of its handlers has to accept the record (see [Handler::accepts](Handler::accepts)),
+/// e.g. with the level and filters of a [ConfiguredHandler](ConfiguredHandler).
+/// This allows to skip collecting expensive data nobody would see.
+///
+/// Filters are checked with a record without message or fields, so filters looking at those may give a different result once the record is logged.
+///
+/// # Arguments
+///
+/// * `logger`: The full name of the logger. Loggers that don't exist yet aren't created.
+/// * `level`: The level of the record.
+///
+/// returns: bool
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// let logger = Logger::new("app::db::query");
+/// logger.set_level(Level::INFO);
+/// logger.add_handler(MemoryHandler::new(10));
+/// assert!(!logging::would_log("app::db::query", Level::DEBUG));
+///
+/// if logging::would_log("app::db::query", Level::INFO) {
+///     let plan = "Seq Scan on users"; // expensive to get
+///     logger.info(format!("query plan: {}", plan));
+/// }
+///
+/// // loggers that don't exist yet are answered for with what they would inherit, without being created
+/// assert!(logging::would_log("app::db::query::slow", Level::INFO));
+/// assert!(logging::find_logger("app::db::query::slow").is_none());
+/// ```
+pub fn would_log(logger: &str, level: LogLevel) -> bool {
+    logger::inspect(logger, |logger_level, handlers| {
+        if level < logger_level {
+            return false;
+        }
+        let record = Record::unsequenced(level, String::new(), logger.to_string());
+        handlers.iter().any(|handler| handler.accepts(&record))
+    })
+}
 /// Globally add a handler to all loggers.
 /// 
 /// # Arguments 
@@ -391,21 +1170,241 @@ pub fn set_level(level: LogLevel) {
 /// # Examples 
 /// 
 /// ```
-/// use logging::{CONSOLE_HANDLER, Logger, Level};
-/// use logging::Level::CRITICAL;
+/// use logging::{ConsoleHandler, Logger, Level};
 /// logging::set_level(Level::ALL);
 /// let logger = Logger::new("foo");
 /// let logger2 = Logger::new("bar");
 /// // only adds for 'logger'
-/// logger.add_handler(&CONSOLE_HANDLER);
-/// logger.debug("Will log.".to_string());
-/// logger2.debug("Won't log.".to_string());
+/// logger.add_handler(ConsoleHandler::new());
+/// logger.debug("Will log.");
+/// logger2.debug("Won't log.");
 ///
 /// // adds it to all
-/// logging::add_handler(&CONSOLE_HANDLER);
-/// logger.debug("Will log twice, as the handler was added twice.".to_string());
-/// logger2.debug("Will now also log.".to_string());
+/// logging::add_handler(ConsoleHandler::new());
+/// logger.debug("Will log twice, as the handler was added twice.");
+/// logger2.debug("Will now also log.");
 /// ```
 pub fn add_handler<T: Handler + 'static>(handler: T) {
     logger::get_root().write().expect("Logger poisoned").add_handler(Arc::new(handler));
-}
\ No newline at end of file
+}
+/// All loggers that have been created so far.
+/// The root logger comes first, followed by all other loggers depth first in alphabetical order.
+///
+/// returns: Vec<Logger>
+///
+/// # Examples
+///
+/// ```
+/// use logging::Logger;
+/// Logger::new("foo::bar");
+/// Logger::new("baz");
+/// let names: Vec<String> = logging::loggers().iter().map(Logger::name).collect();
+/// assert_eq!(names, ["", "baz", "foo", "foo::bar"]);
+/// ```
+pub fn loggers() -> Vec<Logger> {
+    logger::all_loggers().into_iter()
+        .map(|inner| Logger { inner, fields: Vec::new(), tags: Vec::new() })
+        .collect()
+}
+/// Get a logger only if it has already been created.
+/// Unlike [Logger::new](Logger::new), this never adds a new logger to the hierarchy.
+///
+/// # Arguments
+///
+/// * `name`: The full name of the logger.
+///
+/// returns: Option<Logger>
+///
+/// # Examples
+///
+/// ```
+/// use logging::Logger;
+/// assert!(logging::find_logger("foo").is_none());
+/// Logger::new("foo");
+/// assert!(logging::find_logger("foo").is_some());
+/// ```
+pub fn find_logger(name: &str) -> Option<Logger> {
+    logger::find_logger(name).map(|inner| Logger { inner, fields: Vec::new(), tags: Vec::new() })
+}
+/// Reopen all handlers that are attached to any logger (see [Handler::reopen](Handler::reopen)).
+/// Every handler is reopened once, even if it is attached to multiple loggers.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// let path = std::env::temp_dir().join(format!("logging-reopen-{}.log", std::process::id()));
+/// logging::add_handler(logging::FileHandler::new(&path).unwrap());
+/// logging::set_level(logging::Level::ALL);
+/// let logger = logging::Logger::new("foo");
+///
+/// let rotated = path.with_extension("log.1");
+/// std::fs::rename(&path, &rotated).unwrap();
+/// logging::reopen_handlers();
+/// logger.info("Logged to the new file");
+///
+/// assert!(std::fs::read_to_string(&path).unwrap().contains("Logged to the new file"));
+/// # std::fs::remove_file(&path).unwrap();
+/// # std::fs::remove_file(&rotated).unwrap();
+/// ```
+pub fn reopen_handlers() {
+    for handler in logger::all_handlers() {
+        handler.reopen();
+    }
+}
+/// Close all handlers that are attached to any logger (see [Handler::close](Handler::close)), e.g. right before the program exits.
+/// Every handler is closed once, even if it is attached to multiple loggers.
+///
+/// returns: ()
+pub fn shutdown() {
+    for handler in logger::all_handlers() {
+        handler.close();
+    }
+}
+/// Capture a backtrace for every record at or above the given level, so formatters can show where the record came from.
+/// Backtraces are captured regardless of the `RUST_BACKTRACE` environment variable.
+///
+/// # Arguments
+///
+/// * `level`: The minimum level to capture backtraces for, or `None` to not capture any (the default).
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// logging::set_backtrace_level(Some(Level::CRITICAL));
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// // printed with a backtrace
+/// logger.fatal("Out of memory");
+/// // printed without
+/// logger.info("Hello World");
+/// ```
+pub fn set_backtrace_level(level: Option<LogLevel>) {
+    *logger::BACKTRACE_LEVEL.write().expect("Backtrace level is poisoned") = level;
+}
+/// Set the fields whose values are replaced with `***` before any handler sees them, e.g. `password` or `authorization`.
+/// Keys are compared case-insensitively.
+///
+/// # Arguments
+///
+/// * `keys`: The keys of the fields to scrub. Replaces the previous list.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, Level, Logger};
+/// logging::set_scrubbed_fields(["password", "authorization"]);
+/// logging::set_level(Level::ALL);
+/// // prints "INFO (foo): login user=jane password=***"
+/// Logger::new("foo").log_fields("login", Level::INFO, fields!{ user = "jane", password = "hunter2" });
+/// ```
+pub fn set_scrubbed_fields<I: IntoIterator<Item = S>, S: ToString>(keys: I) {
+    *logger::SCRUBBED_FIELDS.write().expect("Scrubbed fields are poisoned") = keys.into_iter()
+        .map(|key| key.to_string())
+        .collect();
+}
+/// Limit the size of messages before any handler sees them, e.g. to protect network handlers from accidentally huge messages.
+/// Longer messages are cut at a character boundary and marked with `…[truncated N bytes]`.
+///
+/// # Arguments
+///
+/// * `max_len`: The maximum length of a message in bytes (not counting the marker), or `None` for no limit (the default).
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(Arc::clone(&memory));
+///
+/// logging::set_max_message_len(Some(5));
+/// logger.info("Hello World");
+/// assert_eq!(memory.records()[0].message, "Hello…[truncated 6 bytes]");
+/// # logging::set_max_message_len(None);
+/// ```
+pub fn set_max_message_len(max_len: Option<usize>) {
+    *logger::MAX_MESSAGE_LEN.write().expect("Maximum message length is poisoned") = max_len;
+}
+/// The lowest level the logging macros like [debug!](debug!) log at in this build; calls below it compile to nothing.
+///
+/// In debug builds, this is [Level::MIN](Level::MIN). In release builds, it can be raised with one of the features
+/// `release_max_level_debug`, `release_max_level_info`, `release_max_level_success`, `release_max_level_warn`, `release_max_level_error`,
+/// `release_max_level_critical`, `release_max_level_fatal` and `release_max_level_off` (nothing), named like the features of the `log` crate.
+/// If several are enabled, the most restrictive one wins, and without any of them it stays [Level::MIN](Level::MIN).
+/// [hexdump!](hexdump!) and [time!](time!) are compiled out the same way. Logging through [Logger](Logger) methods is not affected.
+///
+/// # Examples
+///
+/// ```
+/// use logging::Level;
+/// # #[cfg(debug_assertions)]
+/// assert_eq!(logging::STATIC_MIN_LEVEL, Level::MIN);
+/// ```
+pub const STATIC_MIN_LEVEL: LogLevel = if cfg!(debug_assertions) {
+    Level::MIN
+} else if cfg!(feature = "release_max_level_off") {
+    Level::NONE
+} else if cfg!(feature = "release_max_level_fatal") {
+    Level::FATAL
+} else if cfg!(feature = "release_max_level_critical") {
+    Level::CRITICAL
+} else if cfg!(feature = "release_max_level_error") {
+    Level::ERROR
+} else if cfg!(feature = "release_max_level_warn") {
+    Level::WARN
+} else if cfg!(feature = "release_max_level_success") {
+    Level::SUCCESS
+} else if cfg!(feature = "release_max_level_info") {
+    Level::INFO
+} else if cfg!(feature = "release_max_level_debug") {
+    Level::DEBUG
+} else {
+    Level::MIN
+};
+/// Handle records strictly one at a time, in the order they were logged in, across all threads.
+/// Every record is numbered (see [Record::sequence](Record::sequence)) and passed to all of its handlers before the next record is numbered,
+/// so output of multiple threads appears in the true order of the records, at the cost of throughput. Off by default.
+///
+/// Records logged by handlers while handling a record (e.g. reports about failures) are handled right away.
+///
+/// # Arguments
+///
+/// * `strict`: Whether to handle records one at a time.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// let memory = Arc::new(MemoryHandler::new(1000));
+/// let logger = Logger::new("workers");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(Arc::clone(&memory));
+///
+/// logging::set_strict_ordering(true);
+/// std::thread::scope(|scope| {
+///     for worker in 0..4 {
+///         let logger = &logger;
+///         scope.spawn(move || (0..100).for_each(|step| logger.info(format!("worker {} step {}", worker, step))));
+///     }
+/// });
+/// logging::set_strict_ordering(false);
+/// let records = memory.records();
+/// assert!(records.windows(2).all(|pair| pair[0].sequence < pair[1].sequence));
+/// ```
+pub fn set_strict_ordering(strict: bool) {
+    logger::STRICT_ORDERING.store(strict, std::sync::atomic::Ordering::Relaxed);
+}
@@ -0,0 +1,226 @@
+use crate::{ConfiguredHandler, ConsoleHandler, ConsoleStream, FileHandler, Handler, JsonFormatter, Level, LogLevel, PatternFormatter, RotatingFileHandler};
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+fn invalid(message: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Configure the loggers from a log4rs YAML config file, for programs moving over from log4rs. See [apply_log4rs_config](apply_log4rs_config) for what is supported.
+///
+/// # Arguments
+///
+/// * `path`: The path of the config file.
+///
+/// returns: Result<(), Error>
+pub fn load_log4rs_config(path: impl AsRef<Path>) -> io::Result<()> {
+    apply_log4rs_config(&std::fs::read_to_string(path)?)
+}
+
+/// Configure the loggers from a log4rs YAML config.
+///
+/// A subset of log4rs is understood:
+/// * `appenders` of the kinds `console` (with `target`), `file` (with `path`) and `rolling_file` (with `path` and a `compound` policy).
+///   A rolling file writes to the files of the `fixed_window` roller's pattern, counting up, and starts a new one at the `size` trigger's limit
+///   (see [RotatingFileHandler](RotatingFileHandler)). Without a roller, it writes to `path` only.
+/// * `encoder`s with a `pattern` (`{d}`, `{l}`, `{m}`, `{t}`, `{M}` and `{n}`, see [PatternFormatter](PatternFormatter)) or of the kind `json`.
+/// * `threshold` filters.
+/// * The `root` and `loggers` with a `level` (`trace` is [DEBUG](Level::DEBUG), `off` is [NONE](Level::NONE)), `appenders` and `additive`.
+///
+/// Anything else (e.g. `refresh_rate`) is ignored. The root's appenders replace the handlers of all loggers, like the `init_` presets.
+/// Nothing is changed if the config is invalid.
+///
+/// # Arguments
+///
+/// * `yaml`: The config.
+///
+/// returns: Result<(), Error>
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// let path = std::env::temp_dir().join(format!("logging-log4rs-{}.log", std::process::id()));
+/// let yaml = format!(r#"
+/// appenders:
+///   requests:
+///     kind: file
+///     path: {:?}
+///     encoder:
+///       pattern: "{{l}} {{t}} - {{m}}{{n}}"
+/// root:
+///   level: warn
+/// loggers:
+///   app::requests:
+///     level: info
+///     appenders:
+///       - requests
+///     additive: false
+/// "#, path);
+/// logging::apply_log4rs_config(&yaml).unwrap();
+///
+/// assert_eq!(Logger::new("app").level(), Level::WARN);
+/// Logger::new("app::requests").info("GET /");
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "INFO app::requests - GET /\n");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn apply_log4rs_config(yaml: &str) -> io::Result<()> {
+    let config: Value = serde_yaml::from_str(yaml).map_err(invalid)?;
+    let empty = Mapping::new();
+    let appenders = match config.get("appenders") {
+        Some(appenders) => appenders.as_mapping().ok_or_else(|| invalid("appenders must be a mapping"))?,
+        None => &empty,
+    };
+    let mut handlers: HashMap<&str, Arc<dyn Handler>> = HashMap::new();
+    for (name, appender) in appenders {
+        let name = name.as_str().ok_or_else(|| invalid("appender names must be strings"))?;
+        let handler = appender_handler(appender).map_err(|error| invalid(format!("appender {}: {}", name, error)))?;
+        handlers.insert(name, handler);
+    }
+    let lookup = |section: &Value, name: &str| -> io::Result<Option<Vec<Arc<dyn Handler>>>> {
+        let Some(appenders) = section.get("appenders") else { return Ok(None) };
+        let appenders = appenders.as_sequence().ok_or_else(|| invalid(format!("appenders of {} must be a list", name)))?;
+        appenders.iter()
+            .map(|appender| {
+                let appender = appender.as_str().unwrap_or_default();
+                handlers.get(appender).cloned().ok_or_else(|| invalid(format!("unknown appender '{}' in {}", appender, name)))
+            })
+            .collect::<io::Result<_>>()
+            .map(Some)
+    };
+
+    let root = config.get("root").unwrap_or(&Value::Null);
+    let root_level = root.get("level").map(|level| parse_level(level, "root")).transpose()?;
+    let root_handlers = lookup(root, "root")?.unwrap_or_default();
+    let mut loggers = Vec::new();
+    if let Some(section) = config.get("loggers") {
+        let section = section.as_mapping().ok_or_else(|| invalid("loggers must be a mapping"))?;
+        for (name, logger) in section {
+            let name = name.as_str().ok_or_else(|| invalid("logger names must be strings"))?;
+            let level = logger.get("level").map(|level| parse_level(level, name)).transpose()?;
+            let handlers = lookup(logger, name)?;
+            let additive = logger.get("additive").and_then(Value::as_bool).unwrap_or(true);
+            loggers.push((name, level, handlers, additive));
+        }
+    }
+    loggers.sort_by_key(|(name, ..)| name.split("::").count());
+
+    crate::logger::get_root().write().expect("Logger is poisoned").set_handlers(root_handlers);
+    let mut levels: Vec<(&str, LogLevel)> = loggers.iter().filter_map(|(name, level, ..)| Some((*name, (*level)?))).collect();
+    if let Some(level) = root_level {
+        levels.push(("", level));
+    }
+    crate::apply_levels(&levels);
+    for (name, _, handlers, additive) in loggers {
+        let logger = crate::logger::get_logger(name.to_string());
+        let mut logger = logger.write().expect("Logger is poisoned");
+        match handlers {
+            Some(handlers) if !additive => logger.set_handlers(handlers),
+            Some(handlers) => handlers.into_iter().for_each(|handler| logger.add_handler(handler)),
+            None if !additive => logger.set_handlers(Vec::new()),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse_level(level: &Value, logger: &str) -> io::Result<LogLevel> {
+    let name = level.as_str().unwrap_or_default();
+    match name.to_ascii_lowercase().as_str() {
+        "trace" => Ok(Level::DEBUG),
+        "off" => Ok(Level::NONE),
+        _ => Level::by_name(name).ok_or_else(|| invalid(format!("unknown level '{}' for {}", name, logger))),
+    }
+}
+
+fn appender_handler(appender: &Value) -> io::Result<Arc<dyn Handler>> {
+    let path = || appender.get("path").and_then(Value::as_str).ok_or_else(|| invalid("missing path"));
+    let handler = match appender.get("kind").and_then(Value::as_str) {
+        Some("console") => match appender.get("target").and_then(Value::as_str) {
+            Some("stderr") => ConfiguredHandler::new(ConsoleHandler::new().with_stream(ConsoleStream::Stderr)),
+            _ => ConfiguredHandler::new(ConsoleHandler::new()),
+        },
+        Some("file") => ConfiguredHandler::new(FileHandler::new(path()?)?),
+        Some("rolling_file") => {
+            let policy = appender.get("policy").unwrap_or(&Value::Null);
+            let pattern = policy.get("roller").filter(|roller| roller.get("kind").and_then(Value::as_str) == Some("fixed_window"))
+                .and_then(|roller| roller.get("pattern")).and_then(Value::as_str);
+            let handler = match pattern {
+                Some(pattern) => {
+                    let handler = RotatingFileHandler::new(pattern.replace("{}", "{index}"))?;
+                    let limit = policy.get("trigger").and_then(|trigger| trigger.get("limit"));
+                    match limit {
+                        Some(limit) => handler.with_max_size(parse_size(limit)?),
+                        None => handler,
+                    }
+                }
+                None => RotatingFileHandler::new(path()?)?,
+            };
+            ConfiguredHandler::new(handler)
+        }
+        Some(kind) => return Err(invalid(format!("unsupported kind '{}'", kind))),
+        None => return Err(invalid("missing kind")),
+    };
+    let handler = match appender.get("encoder") {
+        Some(encoder) if encoder.get("kind").and_then(Value::as_str) == Some("json") => handler.with_formatter(JsonFormatter),
+        Some(encoder) => match encoder.get("pattern").and_then(Value::as_str) {
+            Some(pattern) => handler.with_formatter(PatternFormatter::new(&translate_pattern(pattern))),
+            None => handler,
+        },
+        None => handler,
+    };
+    let mut handler = handler;
+    for filter in appender.get("filters").and_then(Value::as_sequence).into_iter().flatten() {
+        match filter.get("kind").and_then(Value::as_str) {
+            Some("threshold") => handler = handler.with_level(parse_level(filter.get("level").unwrap_or(&Value::Null), "threshold filter")?),
+            kind => return Err(invalid(format!("unsupported filter kind '{}'", kind.unwrap_or_default()))),
+        }
+    }
+    Ok(Arc::new(handler))
+}
+
+/// Parse a log4rs size like `10 mb` or `1024` into bytes.
+fn parse_size(size: &Value) -> io::Result<u64> {
+    if let Some(bytes) = size.as_u64() {
+        return Ok(bytes);
+    }
+    let size = size.as_str().unwrap_or_default().trim().to_ascii_lowercase();
+    let split = size.find(|c: char| !c.is_ascii_digit()).unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let factor: u64 = match unit.trim() {
+        "" | "b" => 1,
+        "kb" | "kib" => 1 << 10,
+        "mb" | "mib" => 1 << 20,
+        "gb" | "gib" => 1 << 30,
+        "tb" | "tib" => 1 << 40,
+        unit => return Err(invalid(format!("unknown size unit '{}'", unit))),
+    };
+    number.parse::<u64>().map(|number| number * factor).map_err(|_| invalid(format!("invalid size '{}'", size)))
+}
+
+/// Translate a log4rs pattern into the placeholders of the [PatternFormatter](PatternFormatter).
+/// Format specifications and arguments (e.g. `{l:<5}` or `{d(%H:%M)}`) are dropped. The trailing newline is written by the handlers anyway.
+fn translate_pattern(pattern: &str) -> String {
+    let mut translated = String::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else { break };
+        translated.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..start + length];
+        let name = placeholder.split([':', '(']).next().unwrap_or_default();
+        match name {
+            "d" | "date" => translated.push_str("{time}"),
+            "l" | "level" => translated.push_str("{level}"),
+            "m" | "message" => translated.push_str("{message}"),
+            "t" | "target" | "M" | "module" => translated.push_str("{logger}"),
+            "n" => translated.push('\n'),
+            _ => translated.push_str(&rest[start..start + length + 1]),
+        }
+        rest = &rest[start + length + 1..];
+    }
+    translated.push_str(rest);
+    translated.strip_suffix('\n').map(str::to_string).unwrap_or(translated)
+}
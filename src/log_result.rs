@@ -0,0 +1,74 @@
+use crate::{Level, LogLevel, Logger};
+use std::fmt::Display;
+
+/// Log errors while passing the [Result](Result) on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, LogResult, Logger};
+///
+/// fn parse(logger: &Logger, input: &str) -> Result<i32, std::num::ParseIntError> {
+///     // logs the error at ERROR and still returns it
+///     let number = input.parse::<i32>().log_err(logger)?;
+///     Ok(number)
+/// }
+///
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// assert!(parse(&logger, "not a number").is_err());
+/// ```
+pub trait LogResult {
+    /// Log the error at [Level::ERROR](Level::ERROR), if there is one.
+    fn log_err(self, logger: &Logger) -> Self;
+    /// Log the error at [Level::WARN](Level::WARN), if there is one.
+    fn warn_err(self, logger: &Logger) -> Self;
+    /// Log the error at the given level, if there is one.
+    fn log_err_at(self, logger: &Logger, level: LogLevel) -> Self;
+}
+impl<T, E: Display> LogResult for Result<T, E> {
+    fn log_err(self, logger: &Logger) -> Self {
+        self.log_err_at(logger, Level::ERROR)
+    }
+    fn warn_err(self, logger: &Logger) -> Self {
+        self.log_err_at(logger, Level::WARN)
+    }
+    fn log_err_at(self, logger: &Logger, level: LogLevel) -> Self {
+        if let Err(err) = &self {
+            logger.log(err.to_string(), level);
+        }
+        self
+    }
+}
+
+/// Log missing values while passing the [Option](Option) on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, LogOption, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+///
+/// let port = std::env::var("SOME_UNSET_PORT").ok()
+///     .warn_none(&logger, "SOME_UNSET_PORT is not set, using 8080")
+///     .unwrap_or_else(|| "8080".to_string());
+/// assert_eq!(port, "8080");
+/// ```
+pub trait LogOption {
+    /// Log the message at [Level::WARN](Level::WARN) if there is no value.
+    fn warn_none(self, logger: &Logger, msg: impl Display) -> Self;
+    /// Log the message at the given level if there is no value.
+    fn log_none(self, logger: &Logger, level: LogLevel, msg: impl Display) -> Self;
+}
+impl<T> LogOption for Option<T> {
+    fn warn_none(self, logger: &Logger, msg: impl Display) -> Self {
+        self.log_none(logger, Level::WARN, msg)
+    }
+    fn log_none(self, logger: &Logger, level: LogLevel, msg: impl Display) -> Self {
+        if self.is_none() {
+            logger.log(msg.to_string(), level);
+        }
+        self
+    }
+}
@@ -1,8 +1,42 @@
-use crate::{ConsoleHandler, Handler, Level, LogLevel};
+use crate::{FieldValue, Handler, Level, LogLevel, Record, Source};
+use std::cell::Cell;
 use std::collections::HashMap;
-use std::sync::{Arc, OnceLock, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, RwLock};
 
-static ROOT: OnceLock<RwLock<Logger>> = OnceLock::new();
+static ROOT: OnceLock<Arc<RwLock<Logger>>> = OnceLock::new();
+pub(crate) static BACKTRACE_LEVEL: RwLock<Option<LogLevel>> = RwLock::new(None);
+pub(crate) static SCRUBBED_FIELDS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+pub(crate) static MAX_MESSAGE_LEN: RwLock<Option<usize>> = RwLock::new(None);
+/// Whether records are numbered and handled one at a time, see [set_strict_ordering](crate::set_strict_ordering).
+pub(crate) static STRICT_ORDERING: AtomicBool = AtomicBool::new(false);
+/// Held from numbering a record until all handlers have handled it in strict ordering mode.
+static ORDER: Mutex<()> = Mutex::new(());
+thread_local! {
+    /// Whether the current thread holds the order lock, so records logged by handlers don't deadlock.
+    static HOLDS_ORDER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Holds the order lock for the current thread in strict ordering mode.
+struct OrderGuard(#[allow(dead_code)] MutexGuard<'static, ()>);
+impl OrderGuard {
+    fn acquire() -> Option<Self> {
+        if !STRICT_ORDERING.load(Ordering::Relaxed) || HOLDS_ORDER.get() {
+            return None;
+        }
+        let guard = ORDER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        HOLDS_ORDER.set(true);
+        Some(Self(guard))
+    }
+}
+impl Drop for OrderGuard {
+    fn drop(&mut self) {
+        HOLDS_ORDER.set(false);
+    }
+}
+
+/// The levels set for patterns with [set_level_for](crate::set_level_for), applied to loggers created later on, in the order they were set.
+static PATTERN_LEVELS: RwLock<Vec<(String, LogLevel)>> = RwLock::new(Vec::new());
 
 
 pub(crate) struct Logger {
@@ -10,30 +44,132 @@ pub(crate) struct Logger {
     handlers: Vec<Arc<dyn Handler>>,
     name: Box<str>,
     children: HashMap<String, Arc<RwLock<Logger>>>,
+    /// The level and handlers of a silenced logger, restored when it is unsilenced, see [silence_subtree](crate::silence_subtree).
+    /// Changes while silenced go here instead.
+    silenced: Option<(LogLevel, Vec<Arc<dyn Handler>>)>,
 }
 impl Logger {
-    pub(crate) fn log(&self, msg: String, level: LogLevel) -> () {
-        if level < self.level {
+    /// Log a record. With a template, the message is rendered from the template and the (scrubbed) fields instead.
+    pub(crate) fn log_fields(&self, mut msg: String, level: LogLevel, mut fields: Vec<(String, FieldValue)>, source: Option<Source>, template: Option<String>, tags: Vec<String>) {
+        if level < self.level() {
             return;
         }
+        crate::stats::record_emitted(self.name(), level);
+        let scrubbed = SCRUBBED_FIELDS.read().expect("Scrubbed fields are poisoned");
+        for (key, value) in &mut fields {
+            if scrubbed.iter().any(|scrubbed| scrubbed.eq_ignore_ascii_case(key)) {
+                *value = FieldValue::from("***");
+            }
+        }
+        drop(scrubbed);
+        if let Some(template) = &template {
+            msg = render_template(template, &fields);
+        }
+        if let Some(max_len) = *MAX_MESSAGE_LEN.read().expect("Maximum message length is poisoned") {
+            truncate(&mut msg, max_len);
+        }
+        let order = OrderGuard::acquire();
+        let mut record = Record::new(level, msg, self.name().to_string());
+        record.fields = fields;
+        record.source = source;
+        record.template = template;
+        record.tags = tags;
+        if BACKTRACE_LEVEL.read().expect("Backtrace level is poisoned").is_some_and(|min| level >= min) {
+            record.capture_backtrace();
+        }
+        crate::fatal::mirror(&record);
+        if record.fields.iter().any(|(_, value)| value.is_lazy()) && self.handlers.iter().any(|handler| handler.accepts(&record)) {
+            record.fields = std::mem::take(&mut record.fields).into_iter().map(|(key, value)| (key, value.evaluate())).collect();
+        }
+        crate::testing::record(&record);
         for handler in &self.handlers {
-            handler.log(level, msg.clone(), self.name.to_string());
+            handler.handle(&record);
         }
+        crate::subscribe::publish(&record);
+        drop(order);
+        crate::stats::report_drops();
+    }
+    pub(crate) fn name(&self) -> &str {
+        self.name.strip_prefix("::").unwrap_or(&self.name)
+    }
+    /// The level of this logger on the current thread, which is the level of a [capture](crate::testing::capture) if one is active.
+    pub(crate) fn level(&self) -> LogLevel {
+        crate::testing::level().unwrap_or(self.level)
     }
     pub(crate) fn set_level(&mut self, level: LogLevel) {
-        self.level = level;
+        match &mut self.silenced {
+            Some((saved, _)) => *saved = level,
+            None => self.level = level,
+        }
         for child in self.children.values_mut() {
             let mut lock = child.write().expect("Logger is poisoned");
             lock.set_level(level);
         }
     }
+    pub(crate) fn handlers(&self) -> &[Arc<dyn Handler>] {
+        &self.handlers
+    }
     pub(crate) fn add_handler(&mut self, handler: Arc<dyn Handler>) {
-        self.handlers.push(handler.clone());
+        match &mut self.silenced {
+            Some((_, saved)) => saved.push(handler.clone()),
+            None => self.handlers.push(handler.clone()),
+        }
         for child in self.children.values_mut() {
             let mut lock = child.write().expect("Logger is poisoned");
             lock.add_handler(handler.clone());
         }
     }
+    /// Replaces the handlers of this logger and all children.
+    pub(crate) fn set_handlers(&mut self, handlers: Vec<Arc<dyn Handler>>) {
+        for child in self.children.values_mut() {
+            let mut lock = child.write().expect("Logger is poisoned");
+            lock.set_handlers(handlers.clone());
+        }
+        match &mut self.silenced {
+            Some((_, saved)) => *saved = handlers,
+            None => self.handlers = handlers,
+        }
+    }
+    /// Set this logger and all children to [NONE](Level::NONE) without handlers, keeping their level and handlers for [unsilence](Self::unsilence).
+    pub(crate) fn silence(&mut self) {
+        if self.silenced.is_none() {
+            self.silenced = Some((self.level, std::mem::take(&mut self.handlers)));
+            self.level = Level::NONE;
+        }
+        for child in self.children.values_mut() {
+            child.write().expect("Logger is poisoned").silence();
+        }
+    }
+    /// Restore the level and handlers of this logger and all children from before they were silenced.
+    pub(crate) fn unsilence(&mut self) {
+        if let Some((level, handlers)) = self.silenced.take() {
+            self.level = level;
+            self.handlers = handlers;
+        }
+        for child in self.children.values_mut() {
+            child.write().expect("Logger is poisoned").unsilence();
+        }
+    }
+    /// Collects all descendants of this logger (not including itself), depth first.
+    pub(crate) fn descendants(&self, out: &mut Vec<Arc<RwLock<Self>>>) {
+        let mut names: Vec<&String> = self.children.keys().collect();
+        names.sort();
+        for name in names {
+            let child = &self.children[name];
+            out.push(Arc::clone(child));
+            child.read().expect("Logger is poisoned").descendants(out);
+        }
+    }
+    fn find_child(&self, name: &str) -> Option<Arc<RwLock<Self>>> {
+        let remaining = &name[self.name.len()..];
+        let sub_name = remaining.strip_prefix("::")?.split("::").next()?;
+        let sub_logger = Arc::clone(self.children.get(sub_name)?);
+        if sub_name.len() + "::".len() == remaining.len() {
+            return Some(sub_logger);
+        }
+        let lock = sub_logger.read().expect("Logger is poisoned");
+        lock.find_child(name)
+    }
     fn get_child(&mut self, name: String) -> Arc<RwLock<Self>> {
         let remaining = &name[self.name.len()..];
         assert!(remaining.starts_with("::"), "invalid internal name. Logger passed to the wrong sublogger");
@@ -41,11 +177,25 @@ impl Logger {
         let sub_logger = match self.children.get(sub_name) {
             Some(sub_logger) => Arc::clone(sub_logger),
             None => {
+                let name = format!("{}::{}", self.name, sub_name);
+                let (parent_level, parent_handlers) = match &self.silenced {
+                    Some((level, handlers)) => (*level, handlers),
+                    None => (self.level, &self.handlers),
+                };
+                let level = PATTERN_LEVELS.read().expect("Pattern levels are poisoned").iter()
+                    .rfind(|(pattern, _)| matches_pattern(pattern, &name["::".len()..]))
+                    .map_or(parent_level, |(_, level)| *level);
+                // children of a silenced logger are silenced as well
+                let (level, handlers, silenced) = match &self.silenced {
+                    Some(_) => (Level::NONE, Vec::new(), Some((level, parent_handlers.clone()))),
+                    None => (level, parent_handlers.clone(), None),
+                };
                 let logger = Arc::new(RwLock::new(Self {
-                    level: self.level,
-                    handlers: self.handlers.clone(),
-                    name: format!("{}::{}", self.name, sub_name).into_boxed_str(),
+                    level,
+                    handlers,
+                    name: name.into_boxed_str(),
                     children: HashMap::new(),
+                    silenced,
                 }));
                 self.children.insert(sub_name.to_string(), Arc::clone(&logger));
                 logger
@@ -60,12 +210,151 @@ impl Logger {
     }
 }
 pub(crate) fn get_logger(name: String) -> Arc<RwLock<Logger>> {
+    if name.is_empty() {
+        return Arc::clone(get_root());
+    }
     get_root().write().expect("Logger is poisoned")
         .get_child(format!("::{name}"))
 }
-pub(crate) fn get_root<'a>() -> &'a RwLock<Logger> {
+/// Looks up a logger without creating it.
+pub(crate) fn find_logger(name: &str) -> Option<Arc<RwLock<Logger>>> {
+    if name.is_empty() {
+        return Some(Arc::clone(get_root()));
+    }
+    get_root().read().expect("Logger is poisoned")
+        .find_child(&format!("::{name}"))
+}
+/// Look at the level and handlers of a logger without creating it.
+/// A logger that doesn't exist yet gets what it would have if it was created now:
+/// the handlers of its nearest existing ancestor, and its level or the level set for a pattern matching it or the loggers in between.
+pub(crate) fn inspect<R>(name: &str, f: impl FnOnce(LogLevel, &[Arc<dyn Handler>]) -> R) -> R {
+    let mut ancestor = name;
+    let logger = loop {
+        if let Some(logger) = find_logger(ancestor) {
+            break logger;
+        }
+        ancestor = ancestor.rsplit_once("::").map_or("", |(parent, _)| parent);
+    };
+    let lock = logger.read().expect("Logger is poisoned");
+    if ancestor.len() == name.len() {
+        return f(lock.level(), lock.handlers());
+    }
+    if lock.silenced.is_some() {
+        // children of a silenced logger are silenced as well
+        return f(crate::testing::level().unwrap_or(Level::NONE), &[]);
+    }
+    let mut level = lock.level;
+    let patterns = PATTERN_LEVELS.read().expect("Pattern levels are poisoned");
+    let mut end = if ancestor.is_empty() { 0 } else { ancestor.len() + "::".len() };
+    loop {
+        end = name[end..].find("::").map_or(name.len(), |index| end + index);
+        let created = &name[..end];
+        if let Some((_, pattern_level)) = patterns.iter().rfind(|(pattern, _)| matches_pattern(pattern, created)) {
+            level = *pattern_level;
+        }
+        if end == name.len() {
+            break;
+        }
+        end += "::".len();
+    }
+    drop(patterns);
+    f(crate::testing::level().unwrap_or(level), lock.handlers())
+}
+/// Remove a logger and its children from the hierarchy, see [Logger::detach](crate::Logger::detach).
+/// Nothing happens if the logger was detached already, even if another logger with the same name was created since.
+pub(crate) fn detach(logger: &Arc<RwLock<Logger>>) {
+    let name = logger.read().expect("Logger is poisoned").name().to_string();
+    let (parent, sub_name) = name.rsplit_once("::").unwrap_or(("", &name));
+    if sub_name.is_empty() {
+        // the root logger
+        return;
+    }
+    let Some(parent) = find_logger(parent) else { return };
+    let mut parent = parent.write().expect("Logger is poisoned");
+    if parent.children.get(sub_name).is_some_and(|child| Arc::ptr_eq(child, logger)) {
+        parent.children.remove(sub_name);
+    }
+}
+/// All registered loggers, starting with the root and then depth first in alphabetical order.
+pub(crate) fn all_loggers() -> Vec<Arc<RwLock<Logger>>> {
+    let root = get_root();
+    let mut loggers = vec![Arc::clone(root)];
+    root.read().expect("Logger is poisoned").descendants(&mut loggers);
+    loggers
+}
+/// Every handler attached to any logger, each one only once.
+pub(crate) fn all_handlers() -> Vec<Arc<dyn Handler>> {
+    let mut handlers: Vec<Arc<dyn Handler>> = Vec::new();
+    for logger in all_loggers() {
+        let lock = logger.read().expect("Logger is poisoned");
+        for handler in &lock.handlers {
+            if !handlers.iter().any(|known| Arc::ptr_eq(known, handler)) {
+                handlers.push(Arc::clone(handler));
+            }
+        }
+    }
+    handlers
+}
+/// Set the levels of several loggers or patterns while holding the lock of the root logger,
+/// so no other configuration change or new logger happens in between. Parents are set before their children.
+pub(crate) fn apply_levels(levels: &[(&str, LogLevel)]) {
+    let mut levels = levels.to_vec();
+    levels.sort_by_key(|(name, _)| if name.is_empty() { 0 } else { name.split("::").count() });
+    let mut root = get_root().write().expect("Logger is poisoned");
+    for (name, level) in levels {
+        if name.is_empty() {
+            root.set_level(level);
+        } else if name.contains('*') {
+            let mut patterns = PATTERN_LEVELS.write().expect("Pattern levels are poisoned");
+            patterns.retain(|(known, _)| known != name);
+            patterns.push((name.to_string(), level));
+            drop(patterns);
+            let mut loggers = Vec::new();
+            root.descendants(&mut loggers);
+            for logger in loggers {
+                let mut lock = logger.write().expect("Logger is poisoned");
+                if matches_pattern(name, lock.name()) {
+                    lock.set_level(level);
+                }
+            }
+        } else {
+            root.get_child(format!("::{name}")).write().expect("Logger is poisoned").set_level(level);
+        }
+    }
+}
+/// Whether a logger name matches a glob pattern.
+/// Both are split at `::`, and every segment of the name has to match the segment of the pattern at the same position,
+/// where `*` matches any number of characters within one segment.
+pub(crate) fn matches_pattern(pattern: &str, name: &str) -> bool {
+    if pattern.is_empty() || name.is_empty() {
+        return pattern == name;
+    }
+    let mut patterns = pattern.split("::");
+    let mut segments = name.split("::");
+    loop {
+        match (patterns.next(), segments.next()) {
+            (Some(pattern), Some(segment)) if matches_segment(pattern, segment) => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+fn matches_segment(pattern: &str, segment: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else { return pattern == segment };
+    let Some(mut remaining) = segment.strip_prefix(prefix) else { return false };
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let suffix = parts.pop().unwrap_or_default();
+    for part in parts {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= suffix.len() && remaining.ends_with(suffix)
+}
+pub(crate) fn get_root<'a>() -> &'a Arc<RwLock<Logger>> {
     ROOT.get_or_init(|| {
-        RwLock::new(Logger {
+        Arc::new(RwLock::new(Logger {
             #[cfg(not(feature = "default_log_all"))]
             level: Level::NONE,
             #[cfg(feature = "default_log_all")]
@@ -73,9 +362,52 @@ pub(crate) fn get_root<'a>() -> &'a RwLock<Logger> {
             #[cfg(not(feature = "default_log_console"))]
             handlers: vec![],
             #[cfg(feature = "default_log_console")]
-            handlers: vec![Arc::new(ConsoleHandler)],
+            handlers: vec![Arc::new(crate::ConsoleHandler::new())],
             name: Box::from(""),
             children: HashMap::new(),
-        })
+            silenced: None,
+        }))
     })
-}
\ No newline at end of file
+}
+/// Replace the `{name}` placeholders of a template with the values of the fields, see [log_template](crate::Logger::log_template).
+fn render_template(template: &str, fields: &[(String, FieldValue)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            rendered.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let value = rest.find('}')
+            .filter(|_| rest.starts_with('{'))
+            .and_then(|end| fields.iter().find(|(key, _)| *key == rest[1..end]).map(|(_, value)| (end, value)));
+        match value {
+            Some((end, value)) => {
+                rendered.push_str(&value.to_string());
+                rest = &rest[end + 1..];
+            }
+            None => {
+                rendered.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+/// Cut a message down to at most `max_len` bytes, marking how much was removed.
+pub(crate) fn truncate(msg: &mut String, max_len: usize) {
+    if msg.len() <= max_len {
+        return;
+    }
+    let mut end = max_len;
+    while !msg.is_char_boundary(end) {
+        end -= 1;
+    }
+    let removed = msg.len() - end;
+    msg.truncate(end);
+    msg.push_str(&format!("…[truncated {} bytes]", removed));
+}
@@ -1,14 +1,85 @@
+/// Log a formatted message at a level.
+///
+/// Without a logger, the message is logged to the logger of the current module (see [with_default](crate::with_default)).
+/// `logger =>` logs to a [Logger](crate::Logger) value and `target: "name",` to the logger with that name.
+/// The level macros ([debug!], [info!], [success!], [warn!], [error!], [critical!] and [fatal!]) accept the same forms.
+/// Levels below [STATIC_MIN_LEVEL](crate::STATIC_MIN_LEVEL) are compiled out, e.g. [debug!] in release builds with the `release_max_level_info` feature.
+/// `tags: [..],` in front of the level attaches audience [tags](crate::Record::tags) to the record, see [TagFilter](crate::TagFilter).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{info, log, Level, Logger};
+/// logging::set_level(Level::ALL);
+/// let logger = Logger::new("foo");
+/// log!(Level::INFO, "Hello {}", "World");
+/// log!(logger => Level::INFO, "Hello {}", "World");
+/// log!(target: "net::http", Level::INFO, "connected to {}", "example.com");
+/// info!(target: "net::http", "connected");
+/// log!(logger => tags: ["user-facing"], Level::INFO, "download finished");
+/// info!(tags: ["internal", "cache"], "evicted {} entries", 12);
+/// ```
 #[macro_export]
 macro_rules! log {
-    ($level:expr, $($arg:tt)*) => {
-        $crate::Logger::new(module_path!()).log(format!($($arg)*), $level)
-    };
-    ($logger:expr => $level:expr, $($arg:tt)*) => {
-        $logger.log(format!($($arg)*), $level)
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $level:expr, $($arg:tt)*) => {{
+        let __level = $level;
+        if __level >= $crate::STATIC_MIN_LEVEL {
+            $crate::Logger::new($target).__log_args_tagged_at(__level, format_args!($($arg)*), $crate::__source!(), &[$($tag),*])
+        }
+    }};
+    (tags: [$($tag:expr),* $(,)?], $level:expr, $($arg:tt)*) => {{
+        let __level = $level;
+        if __level >= $crate::STATIC_MIN_LEVEL {
+            $crate::__macro_logger(module_path!()).__log_args_tagged_at(__level, format_args!($($arg)*), $crate::__source!(), &[$($tag),*])
+        }
+    }};
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $level:expr, $($arg:tt)*) => {{
+        let __level = $level;
+        if __level >= $crate::STATIC_MIN_LEVEL {
+            $logger.__log_args_tagged_at(__level, format_args!($($arg)*), $crate::__source!(), &[$($tag),*])
+        }
+    }};
+    (target: $target:expr, $level:expr, $($arg:tt)*) => {{
+        let __level = $level;
+        if __level >= $crate::STATIC_MIN_LEVEL {
+            $crate::Logger::new($target).__log_args_at(__level, format_args!($($arg)*), $crate::__source!())
+        }
+    }};
+    ($level:expr, $($arg:tt)*) => {{
+        let __level = $level;
+        if __level >= $crate::STATIC_MIN_LEVEL {
+            $crate::__macro_logger(module_path!()).__log_args_at(__level, format_args!($($arg)*), $crate::__source!())
+        }
+    }};
+    ($logger:expr => $level:expr, $($arg:tt)*) => {{
+        let __level = $level;
+        if __level >= $crate::STATIC_MIN_LEVEL {
+            $logger.__log_args_at(__level, format_args!($($arg)*), $crate::__source!())
+        }
+    }};
+}
+/// The [Source](crate::Source) of the place the macro is called at.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __source {
+    () => {
+        $crate::Source { module_path: module_path!(), file: file!(), line: line!() }
     };
 }
 #[macro_export]
 macro_rules! debug {
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(target: $target, tags: [$($tag),*], $crate::Level::DEBUG, $($arg)*)
+    };
+    (target: $target:expr, $($arg:tt)*) => {
+        $crate::log!(target: $target, $crate::Level::DEBUG, $($arg)*)
+    };
+    (tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(tags: [$($tag),*], $crate::Level::DEBUG, $($arg)*)
+    };
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!($logger => tags: [$($tag),*], $crate::Level::DEBUG, $($arg)*)
+    };
     ($logger:expr => $($arg:tt)*) => {
         $crate::log!($logger => $crate::Level::DEBUG, $($arg)*)
     };
@@ -18,6 +89,18 @@ macro_rules! debug {
 }
 #[macro_export]
 macro_rules! info {
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(target: $target, tags: [$($tag),*], $crate::Level::INFO, $($arg)*)
+    };
+    (target: $target:expr, $($arg:tt)*) => {
+        $crate::log!(target: $target, $crate::Level::INFO, $($arg)*)
+    };
+    (tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(tags: [$($tag),*], $crate::Level::INFO, $($arg)*)
+    };
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!($logger => tags: [$($tag),*], $crate::Level::INFO, $($arg)*)
+    };
     ($logger:expr => $($arg:tt)*) => {
         $crate::log!($logger => $crate::Level::INFO, $($arg)*)
     };
@@ -27,6 +110,18 @@ macro_rules! info {
 }
 #[macro_export]
 macro_rules! success {
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(target: $target, tags: [$($tag),*], $crate::Level::SUCCESS, $($arg)*)
+    };
+    (target: $target:expr, $($arg:tt)*) => {
+        $crate::log!(target: $target, $crate::Level::SUCCESS, $($arg)*)
+    };
+    (tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(tags: [$($tag),*], $crate::Level::SUCCESS, $($arg)*)
+    };
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!($logger => tags: [$($tag),*], $crate::Level::SUCCESS, $($arg)*)
+    };
     ($logger:expr => $($arg:tt)*) => {
         $crate::log!($logger => $crate::Level::SUCCESS, $($arg)*)
     };
@@ -37,6 +132,18 @@ macro_rules! success {
 
 #[macro_export]
 macro_rules! warn {
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(target: $target, tags: [$($tag),*], $crate::Level::WARN, $($arg)*)
+    };
+    (target: $target:expr, $($arg:tt)*) => {
+        $crate::log!(target: $target, $crate::Level::WARN, $($arg)*)
+    };
+    (tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(tags: [$($tag),*], $crate::Level::WARN, $($arg)*)
+    };
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!($logger => tags: [$($tag),*], $crate::Level::WARN, $($arg)*)
+    };
     ($logger:expr => $($arg:tt)*) => {
         $crate::log!($logger => $crate::Level::WARN, $($arg)*)
     };
@@ -46,6 +153,18 @@ macro_rules! warn {
 }
 #[macro_export]
 macro_rules! error {
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(target: $target, tags: [$($tag),*], $crate::Level::ERROR, $($arg)*)
+    };
+    (target: $target:expr, $($arg:tt)*) => {
+        $crate::log!(target: $target, $crate::Level::ERROR, $($arg)*)
+    };
+    (tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(tags: [$($tag),*], $crate::Level::ERROR, $($arg)*)
+    };
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!($logger => tags: [$($tag),*], $crate::Level::ERROR, $($arg)*)
+    };
     ($logger:expr => $($arg:tt)*) => {
         $crate::log!($logger => $crate::Level::ERROR, $($arg)*)
     };
@@ -55,6 +174,18 @@ macro_rules! error {
 }
 #[macro_export]
 macro_rules! critical {
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(target: $target, tags: [$($tag),*], $crate::Level::CRITICAL, $($arg)*)
+    };
+    (target: $target:expr, $($arg:tt)*) => {
+        $crate::log!(target: $target, $crate::Level::CRITICAL, $($arg)*)
+    };
+    (tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(tags: [$($tag),*], $crate::Level::CRITICAL, $($arg)*)
+    };
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!($logger => tags: [$($tag),*], $crate::Level::CRITICAL, $($arg)*)
+    };
     ($logger:expr => $($arg:tt)*) => {
         $crate::log!($logger => $crate::Level::CRITICAL, $($arg)*)
     };
@@ -64,10 +195,517 @@ macro_rules! critical {
 }
 #[macro_export]
 macro_rules! fatal {
+    (target: $target:expr, tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(target: $target, tags: [$($tag),*], $crate::Level::FATAL, $($arg)*)
+    };
+    (target: $target:expr, $($arg:tt)*) => {
+        $crate::log!(target: $target, $crate::Level::FATAL, $($arg)*)
+    };
+    (tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!(tags: [$($tag),*], $crate::Level::FATAL, $($arg)*)
+    };
+    ($logger:expr => tags: [$($tag:expr),* $(,)?], $($arg:tt)*) => {
+        $crate::log!($logger => tags: [$($tag),*], $crate::Level::FATAL, $($arg)*)
+    };
     ($logger:expr => $($arg:tt)*) => {
         $crate::log!($logger => $crate::Level::FATAL, $($arg)*)
     };
     ($($arg:tt)*) => {
         $crate::log!($crate::Level::FATAL, $($arg)*)
     };
-}
\ No newline at end of file
+}
+/// Log at [Level::DEBUG](crate::Level::DEBUG) in debug builds only.
+/// In release builds (without `debug_assertions`), the call is removed entirely, so neither the message nor its arguments are evaluated,
+/// regardless of the level set at runtime. Accepts the same forms as [debug!].
+///
+/// # Examples
+///
+/// ```
+/// use logging::{devlog, Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// let state = vec![1, 2, 3];
+/// devlog!(logger => "full state: {:?}", state);
+/// devlog!("state has {} entries", state.len());
+/// ```
+#[macro_export]
+macro_rules! devlog {
+    ($($arg:tt)*) => {{
+        #[cfg(debug_assertions)]
+        {
+            $crate::debug!($($arg)*);
+        }
+    }};
+}
+/// Log a formatted message only if a condition holds.
+/// The condition is evaluated first; if it is false, neither the logger is looked up nor the message formatted.
+/// Accepts the same logger forms as [log!]. [debug_if!], [info_if!], [success_if!], [warn_if!], [error_if!], [critical_if!] and [fatal_if!] log at a fixed level.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{log_if, warn_if, Level, Logger};
+/// let logger = Logger::new("pool");
+/// logger.set_level(Level::ALL);
+/// let (used, size) = (95, 100);
+/// log_if!(used == size, Level::ERROR, "pool exhausted");
+/// warn_if!(logger => used * 10 > size * 9, "pool almost full: {}/{}", used, size);
+/// warn_if!(target: "pool", used > size, "unreachable");
+/// ```
+#[macro_export]
+macro_rules! log_if {
+    (target: $target:expr, $cond:expr, $level:expr, $($arg:tt)*) => {
+        if $cond {
+            $crate::log!(target: $target, $level, $($arg)*)
+        }
+    };
+    ($logger:expr => $cond:expr, $level:expr, $($arg:tt)*) => {
+        if $cond {
+            $crate::log!($logger => $level, $($arg)*)
+        }
+    };
+    ($cond:expr, $level:expr, $($arg:tt)*) => {
+        if $cond {
+            $crate::log!($level, $($arg)*)
+        }
+    };
+}
+/// Log at [Level::DEBUG](crate::Level::DEBUG) only if a condition holds. See [log_if!].
+#[macro_export]
+macro_rules! debug_if {
+    (target: $target:expr, $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!(target: $target, $cond, $crate::Level::DEBUG, $($arg)*)
+    };
+    ($logger:expr => $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($logger => $cond, $crate::Level::DEBUG, $($arg)*)
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($cond, $crate::Level::DEBUG, $($arg)*)
+    };
+}
+/// Log at [Level::INFO](crate::Level::INFO) only if a condition holds. See [log_if!].
+#[macro_export]
+macro_rules! info_if {
+    (target: $target:expr, $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!(target: $target, $cond, $crate::Level::INFO, $($arg)*)
+    };
+    ($logger:expr => $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($logger => $cond, $crate::Level::INFO, $($arg)*)
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($cond, $crate::Level::INFO, $($arg)*)
+    };
+}
+/// Log at [Level::SUCCESS](crate::Level::SUCCESS) only if a condition holds. See [log_if!].
+#[macro_export]
+macro_rules! success_if {
+    (target: $target:expr, $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!(target: $target, $cond, $crate::Level::SUCCESS, $($arg)*)
+    };
+    ($logger:expr => $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($logger => $cond, $crate::Level::SUCCESS, $($arg)*)
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($cond, $crate::Level::SUCCESS, $($arg)*)
+    };
+}
+/// Log at [Level::WARN](crate::Level::WARN) only if a condition holds. See [log_if!].
+#[macro_export]
+macro_rules! warn_if {
+    (target: $target:expr, $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!(target: $target, $cond, $crate::Level::WARN, $($arg)*)
+    };
+    ($logger:expr => $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($logger => $cond, $crate::Level::WARN, $($arg)*)
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($cond, $crate::Level::WARN, $($arg)*)
+    };
+}
+/// Log at [Level::ERROR](crate::Level::ERROR) only if a condition holds. See [log_if!].
+#[macro_export]
+macro_rules! error_if {
+    (target: $target:expr, $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!(target: $target, $cond, $crate::Level::ERROR, $($arg)*)
+    };
+    ($logger:expr => $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($logger => $cond, $crate::Level::ERROR, $($arg)*)
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($cond, $crate::Level::ERROR, $($arg)*)
+    };
+}
+/// Log at [Level::CRITICAL](crate::Level::CRITICAL) only if a condition holds. See [log_if!].
+#[macro_export]
+macro_rules! critical_if {
+    (target: $target:expr, $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!(target: $target, $cond, $crate::Level::CRITICAL, $($arg)*)
+    };
+    ($logger:expr => $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($logger => $cond, $crate::Level::CRITICAL, $($arg)*)
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($cond, $crate::Level::CRITICAL, $($arg)*)
+    };
+}
+/// Log at [Level::FATAL](crate::Level::FATAL) only if a condition holds. See [log_if!].
+#[macro_export]
+macro_rules! fatal_if {
+    (target: $target:expr, $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!(target: $target, $cond, $crate::Level::FATAL, $($arg)*)
+    };
+    ($logger:expr => $cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($logger => $cond, $crate::Level::FATAL, $($arg)*)
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        $crate::log_if!($cond, $crate::Level::FATAL, $($arg)*)
+    };
+}
+/// Run a block and log how long it took, returning the block's value.
+/// Below [STATIC_MIN_LEVEL](crate::STATIC_MIN_LEVEL), only the block is run.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, time};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// let config = time!(logger, Level::DEBUG, "load_config", {
+///     "config contents"
+/// });
+/// assert_eq!(config, "config contents");
+/// // logs with the module's logger
+/// let sum = time!(Level::DEBUG, "sum", { 1 + 2 });
+/// assert_eq!(sum, 3);
+/// ```
+#[macro_export]
+macro_rules! time {
+    ($logger:expr, $level:expr, $label:expr, $body:block) => {{
+        let __level = $level;
+        let _stopwatch = (__level >= $crate::STATIC_MIN_LEVEL).then(|| $crate::Stopwatch::new(&$logger, __level, $label));
+        $body
+    }};
+    ($level:expr, $label:expr, $body:block) => {
+        $crate::time!($crate::__macro_logger(module_path!()), $level, $label, $body)
+    };
+}
+/// Log bytes as a hex dump at [Level::DEBUG](crate::Level::DEBUG). See [Logger::debug_hexdump](crate::Logger::debug_hexdump).
+/// Compiled out if [STATIC_MIN_LEVEL](crate::STATIC_MIN_LEVEL) is above DEBUG.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{hexdump, Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// let packet = [0xde, 0xad, 0xbe, 0xef];
+/// hexdump!(logger => &packet);
+/// hexdump!(&packet);
+/// ```
+#[macro_export]
+macro_rules! hexdump {
+    ($logger:expr => $bytes:expr) => {
+        if $crate::Level::DEBUG >= $crate::STATIC_MIN_LEVEL {
+            $logger.debug_hexdump($bytes)
+        }
+    };
+    ($bytes:expr) => {
+        if $crate::Level::DEBUG >= $crate::STATIC_MIN_LEVEL {
+            $crate::__macro_logger(module_path!()).debug_hexdump($bytes)
+        }
+    };
+}
+/// Log a value pretty-printed with [Debug](std::fmt::Debug) at [Level::DEBUG](crate::Level::DEBUG),
+/// prefixed with the expression that produced it. See [pretty_debug](crate::pretty_debug).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{debug_pretty, Level, Logger};
+/// #[derive(Debug)]
+/// struct Config { host: String, port: u16 }
+///
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// let config = Config { host: "localhost".to_string(), port: 80 };
+/// // logs "config = Config {" followed by the indented fields
+/// debug_pretty!(logger => config);
+/// debug_pretty!(config);
+/// ```
+#[macro_export]
+macro_rules! debug_pretty {
+    ($logger:expr => $value:expr) => {
+        $crate::debug!($logger => "{} = {}", stringify!($value), $crate::pretty_debug(&$value))
+    };
+    ($value:expr) => {
+        $crate::debug!("{} = {}", stringify!($value), $crate::pretty_debug(&$value))
+    };
+}
+/// Log a message at [Level::ERROR](crate::Level::ERROR) and return it as an error from the current function.
+/// The message is converted into the error type with [Into](Into), so this works e.g. for `String` and `Box<dyn Error>`.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{log_and_bail, Level, Logger};
+///
+/// fn connect(logger: &Logger, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+///     if port == 0 {
+///         log_and_bail!(logger => "invalid port {}", port);
+///     }
+///     Ok(())
+/// }
+/// fn open(path: &str) -> Result<(), String> {
+///     log_and_bail!("can't open {}", path)
+/// }
+///
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// assert_eq!(connect(&logger, 0).unwrap_err().to_string(), "invalid port 0");
+/// assert_eq!(open("config.toml"), Err("can't open config.toml".to_string()));
+/// ```
+#[macro_export]
+macro_rules! log_and_bail {
+    ($logger:expr => $($arg:tt)+) => {{
+        let message = format!($($arg)+);
+        $logger.log(message.clone(), $crate::Level::ERROR);
+        return ::std::result::Result::Err(message.into());
+    }};
+    ($($arg:tt)+) => {
+        $crate::log_and_bail!($crate::__macro_logger(module_path!()) => $($arg)+)
+    };
+}
+/// Log an error at [Level::ERROR](crate::Level::ERROR) and return it from the current function.
+/// The error is converted with [Into](Into), just like the `?` operator would.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fail, Level, Logger};
+///
+/// fn read(logger: &Logger) -> Result<String, Box<dyn std::error::Error>> {
+///     match std::fs::read_to_string("/does/not/exist") {
+///         Ok(contents) => Ok(contents),
+///         Err(err) => fail!(logger => err),
+///     }
+/// }
+///
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// assert!(read(&logger).is_err());
+/// ```
+#[macro_export]
+macro_rules! fail {
+    ($logger:expr => $err:expr) => {{
+        let error = $err;
+        $logger.log(error.to_string(), $crate::Level::ERROR);
+        return ::std::result::Result::Err(error.into());
+    }};
+    ($err:expr) => {
+        $crate::fail!($crate::__macro_logger(module_path!()) => $err)
+    };
+}
+/// Log a message at [Level::FATAL](crate::Level::FATAL) and panic with the same message.
+///
+/// # Examples
+///
+/// ```should_panic
+/// use logging::{fatal_panic, Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// fatal_panic!(logger => "database {} is corrupt", "users");
+/// ```
+#[macro_export]
+macro_rules! fatal_panic {
+    ($logger:expr => $($arg:tt)+) => {{
+        let message = format!($($arg)+);
+        $logger.log(message.clone(), $crate::Level::FATAL);
+        panic!("{}", message);
+    }};
+    ($($arg:tt)+) => {
+        $crate::fatal_panic!($crate::__macro_logger(module_path!()) => $($arg)+)
+    };
+}
+/// Like [assert!](assert), but logs the failed assertion at [Level::CRITICAL](crate::Level::CRITICAL) before panicking.
+///
+/// # Examples
+///
+/// ```should_panic
+/// use logging::{log_assert, Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// let connections = 3;
+/// log_assert!(connections < 10);
+/// // logs "assertion failed: connections > 5: only 3 connections" and panics
+/// log_assert!(logger => connections > 5, "only {} connections", connections);
+/// ```
+#[macro_export]
+macro_rules! log_assert {
+    ($logger:expr => $cond:expr $(, $($arg:tt)+)?) => {
+        if !$cond {
+            let message = format!("assertion failed: {}", stringify!($cond));
+            $(let message = format!("{}: {}", message, format!($($arg)+));)?
+            $logger.log(message.clone(), $crate::Level::CRITICAL);
+            panic!("{}", message);
+        }
+    };
+    ($cond:expr $(, $($arg:tt)+)?) => {
+        $crate::log_assert!($crate::__macro_logger(module_path!()) => $cond $(, $($arg)+)?)
+    };
+}
+/// Like [assert_eq!](assert_eq), but logs the failed assertion and both values at [Level::CRITICAL](crate::Level::CRITICAL) before panicking.
+///
+/// # Examples
+///
+/// ```should_panic
+/// use logging::{log_assert_eq, Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// log_assert_eq!(1 + 1, 2);
+/// // logs the failed assertion with both values and panics
+/// log_assert_eq!(logger => 1 + 1, 3, "math is broken");
+/// ```
+#[macro_export]
+macro_rules! log_assert_eq {
+    ($logger:expr => $left:expr, $right:expr $(, $($arg:tt)+)?) => {
+        match (&$left, &$right) {
+            (left, right) => if !(*left == *right) {
+                let message = String::from("assertion `left == right` failed");
+                $(let message = format!("{}: {}", message, format!($($arg)+));)?
+                let message = format!("{}\n  left: {:?}\n right: {:?}", message, left, right);
+                $logger.log(message.clone(), $crate::Level::CRITICAL);
+                panic!("{}", message);
+            }
+        }
+    };
+    ($left:expr, $right:expr $(, $($arg:tt)+)?) => {
+        $crate::log_assert_eq!($crate::__macro_logger(module_path!()) => $left, $right $(, $($arg)+)?)
+    };
+}
+/// Like [log_assert!](log_assert), but only checked in builds with debug assertions enabled, just like [debug_assert!](debug_assert).
+///
+/// # Examples
+///
+/// ```
+/// use logging::debug_log_assert;
+/// let buffer = vec![0u8; 16];
+/// debug_log_assert!(buffer.len() % 16 == 0, "unaligned buffer");
+/// ```
+#[macro_export]
+macro_rules! debug_log_assert {
+    ($($arg:tt)+) => {
+        if cfg!(debug_assertions) {
+            $crate::log_assert!($($arg)+);
+        }
+    };
+}
+/// Log an error and all of its causes at [Level::ERROR](crate::Level::ERROR), optionally prefixed with a message.
+/// See [Logger::error_chain](crate::Logger::error_chain).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{error_chain, Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// if let Err(err) = std::fs::read_to_string("/does/not/exist") {
+///     error_chain!(logger => err);
+///     // logs "reading /does/not/exist: No such file or directory (os error 2)"
+///     error_chain!(err, "reading {}", "/does/not/exist");
+/// }
+/// ```
+#[macro_export]
+macro_rules! error_chain {
+    ($logger:expr => $err:expr) => {
+        $logger.error_chain(&$err)
+    };
+    ($logger:expr => $err:expr, $($arg:tt)+) => {
+        $crate::error!($logger => "{}: {}", format!($($arg)+), $crate::format_error_chain(&$err))
+    };
+    ($err:expr $(, $($arg:tt)+)?) => {
+        $crate::error_chain!($crate::__macro_logger(module_path!()) => $err $(, $($arg)+)?)
+    };
+}
+/// Log an [anyhow::Error](https://docs.rs/anyhow) with its context chain and backtrace at [Level::ERROR](crate::Level::ERROR),
+/// optionally prefixed with a message. See [Logger::error_anyhow](crate::Logger::error_anyhow).
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Context;
+/// use logging::{log_anyhow, Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// if let Err(err) = std::fs::read_to_string("/does/not/exist").context("loading config") {
+///     log_anyhow!(logger => err);
+///     log_anyhow!(err, "startup failed");
+/// }
+/// ```
+#[cfg(feature = "anyhow")]
+#[macro_export]
+macro_rules! log_anyhow {
+    ($logger:expr => $err:expr) => {
+        $logger.error_anyhow(&$err)
+    };
+    ($logger:expr => $err:expr, $($arg:tt)+) => {
+        $crate::error!($logger => "{}: {}", format!($($arg)+), $crate::format_anyhow(&$err))
+    };
+    ($err:expr $(, $($arg:tt)+)?) => {
+        $crate::log_anyhow!($crate::__macro_logger(module_path!()) => $err $(, $($arg)+)?)
+    };
+}
+/// Log an [eyre::Report](https://docs.rs/eyre) with its context chain at [Level::ERROR](crate::Level::ERROR),
+/// optionally prefixed with a message. See [Logger::error_eyre](crate::Logger::error_eyre).
+#[cfg(feature = "eyre")]
+#[macro_export]
+macro_rules! log_eyre {
+    ($logger:expr => $err:expr) => {
+        $logger.error_eyre(&$err)
+    };
+    ($logger:expr => $err:expr, $($arg:tt)+) => {
+        $crate::error!($logger => "{}: {}", format!($($arg)+), $crate::format_eyre(&$err))
+    };
+    ($err:expr $(, $($arg:tt)+)?) => {
+        $crate::log_eyre!($crate::__macro_logger(module_path!()) => $err $(, $($arg)+)?)
+    };
+}
+/// Build the fields for [Logger::log_fields](crate::Logger::log_fields). Values are converted with [ToFieldValue](crate::ToFieldValue) to typed values
+/// if they implement it (like numbers, booleans and text), otherwise with [Display](std::fmt::Display),
+/// otherwise with the serializer set with [set_field_serializer](crate::set_field_serializer) or [Debug].
+/// Values given as closures, like `stats = || expensive_summary()`, are [lazy](crate::FieldValue::lazy):
+/// they are only computed if the record is actually logged. The closures are `move` closures.
+/// Errors marked with `?`, like `err = ?error`, become their message and chain of causes (see [FieldValue::error](crate::FieldValue::error));
+/// boxed errors are marked with `?*error`.
+///
+/// # Examples
+///
+/// ```
+/// use logging::fields;
+/// use logging::FieldValue;
+/// let fields = fields!{ user = "jane", attempts = 3 };
+/// assert_eq!(fields, [("user".to_string(), FieldValue::from("jane")), ("attempts".to_string(), FieldValue::Int(3))]);
+///
+/// let queue = vec![1, 2, 3];
+/// let fields = fields!{ queued = || queue.len(), user = "jane" };
+/// assert_eq!(fields[0].1.clone().evaluate(), FieldValue::Int(3));
+/// ```
+#[macro_export]
+macro_rules! fields {
+    (@fields [$($fields:expr,)*]) => {
+        vec![$($fields),*]
+    };
+    (@fields [$($fields:expr,)*] $key:ident = || $value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields [$($fields,)* (stringify!($key).to_string(), $crate::FieldValue::lazy(move || (&&&&&$crate::__fields::Field(&$value)).__field_value())),] $($($rest)*)?)
+    };
+    (@fields [$($fields:expr,)*] $key:ident = ? $value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields [$($fields,)* (stringify!($key).to_string(), $crate::FieldValue::error(&$value)),] $($($rest)*)?)
+    };
+    (@fields [$($fields:expr,)*] $key:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields [$($fields,)* (stringify!($key).to_string(), (&&&&&$crate::__fields::Field(&$value)).__field_value()),] $($($rest)*)?)
+    };
+    ($($fields:tt)*) => {{
+        #[allow(unused_imports)]
+        use $crate::__fields::{ViaDebug as _, ViaDisplay as _, ViaSerializer as _, ViaSerializerOrDisplay as _, ViaTyped as _};
+        $crate::fields!(@fields [] $($fields)*)
+    }};
+}
@@ -0,0 +1,158 @@
+use crate::{Handler, LogLevel, Record};
+use std::collections::VecDeque;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// A [Handler](Handler) keeping the most recent records in memory, e.g. for a diagnostics endpoint or for tests.
+/// Once the capacity is reached, the oldest record is dropped for every new one.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+/// let memory = Arc::new(MemoryHandler::new(100));
+/// let logger = Logger::new("db");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(Arc::clone(&memory));
+/// logger.info("connected");
+/// logger.error("query failed");
+///
+/// // the last 50 errors
+/// let errors: Vec<_> = memory.query().by_level(Level::ERROR..).iter().rev().take(50).collect();
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].message, "query failed");
+/// // no records were lost in between
+/// let records = memory.records();
+/// assert_eq!(records[1].sequence, records[0].sequence + 1);
+/// ```
+pub struct MemoryHandler {
+    capacity: usize,
+    records: Mutex<VecDeque<Record>>,
+}
+impl MemoryHandler {
+    /// Create a handler keeping up to `capacity` records.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity`: How many records to keep.
+    ///
+    /// returns: MemoryHandler
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, records: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+    /// All records currently kept, oldest first.
+    ///
+    /// returns: Vec<Record>
+    pub fn records(&self) -> Vec<Record> {
+        self.records.lock().expect("Memory handler is poisoned").iter().cloned().collect()
+    }
+    /// Remove all records.
+    ///
+    /// returns: ()
+    pub fn clear(&self) {
+        self.records.lock().expect("Memory handler is poisoned").clear();
+    }
+    /// Start a query over the records kept. Without any conditions, the query matches all records.
+    ///
+    /// returns: MemoryQuery
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Level, MemoryHandler, Handler};
+    /// use std::time::{Duration, SystemTime};
+    /// let memory = MemoryHandler::new(10);
+    /// memory.log(Level::WARN, "disk almost full".to_string(), "storage::disk".to_string());
+    /// memory.log(Level::WARN, "slow request".to_string(), "http".to_string());
+    ///
+    /// let found: Vec<_> = memory.query()
+    ///     .by_logger_prefix("storage")
+    ///     .since(SystemTime::now() - Duration::from_secs(60))
+    ///     .containing("full")
+    ///     .iter()
+    ///     .collect();
+    /// assert_eq!(found.len(), 1);
+    /// ```
+    pub fn query(&self) -> MemoryQuery<'_> {
+        MemoryQuery {
+            handler: self,
+            levels: (Bound::Unbounded, Bound::Unbounded),
+            logger_prefix: None,
+            since: None,
+            text: None,
+        }
+    }
+}
+impl Handler for MemoryHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().expect("Memory handler is poisoned");
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record.clone());
+    }
+}
+
+/// A query over the records of a [MemoryHandler](MemoryHandler), created by [MemoryHandler::query](MemoryHandler::query).
+/// All conditions have to match.
+pub struct MemoryQuery<'a> {
+    handler: &'a MemoryHandler,
+    levels: (Bound<LogLevel>, Bound<LogLevel>),
+    logger_prefix: Option<String>,
+    since: Option<SystemTime>,
+    text: Option<String>,
+}
+impl MemoryQuery<'_> {
+    /// Only match records with a level in the given range, e.g. `Level::WARN..Level::ERROR` or `Level::ERROR..`.
+    pub fn by_level(mut self, levels: impl RangeBounds<LogLevel>) -> Self {
+        self.levels = (levels.start_bound().cloned(), levels.end_bound().cloned());
+        self
+    }
+    /// Only match records of loggers whose name starts with the prefix, e.g. `storage` for `storage::disk`.
+    pub fn by_logger_prefix(mut self, prefix: impl ToString) -> Self {
+        self.logger_prefix = Some(prefix.to_string());
+        self
+    }
+    /// Only match records logged at or after the given time.
+    pub fn since(mut self, time: SystemTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+    /// Only match records whose message contains the text.
+    pub fn containing(mut self, text: impl ToString) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+    /// Check whether a record matches this query.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: The record to check.
+    ///
+    /// returns: bool
+    pub fn matches(&self, record: &Record) -> bool {
+        self.levels.contains(&record.level)
+            && self.logger_prefix.as_ref().is_none_or(|prefix| record.logger.starts_with(prefix.as_str()))
+            && self.since.is_none_or(|since| record.time >= since)
+            && self.text.as_ref().is_none_or(|text| record.message.contains(text.as_str()))
+    }
+    /// The matching records, oldest first. Use `.rev()` to get the newest ones first.
+    ///
+    /// returns: IntoIter<Record>
+    pub fn iter(&self) -> std::vec::IntoIter<Record> {
+        let records = self.handler.records.lock().expect("Memory handler is poisoned");
+        records.iter()
+            .filter(|record| self.matches(record))
+            .cloned()
+            .collect::<Vec<Record>>()
+            .into_iter()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerHealth, LogLevel, Record};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// A [Handler](Handler) writing records as lines of text to a Windows named pipe, e.g. to stream the logs of a service to a local collector agent.
+/// Only available on Windows.
+///
+/// The pipe is opened when the first record is logged. If writing fails, e.g. because the collector was restarted,
+/// the pipe is opened again and the record is retried once. Records that still can't be written are dropped and counted in [stats](crate::stats).
+///
+/// # Examples
+///
+/// ```no_run
+/// use logging::{Level, Logger, NamedPipeHandler};
+/// let logger = Logger::new("service");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(NamedPipeHandler::new("log-collector"));
+/// // written to \\.\pipe\log-collector
+/// logger.info("Hello World");
+/// ```
+pub struct NamedPipeHandler {
+    path: String,
+    pipe: Mutex<Option<File>>,
+    id: HandlerId,
+}
+impl NamedPipeHandler {
+    /// Create a handler writing to the pipe with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the pipe, either without prefix (`log-collector`) or as a full path (`\\.\pipe\log-collector`).
+    ///
+    /// returns: NamedPipeHandler
+    pub fn new(name: impl ToString) -> Self {
+        let name = name.to_string();
+        let path = if name.starts_with(r"\\") { name } else { format!(r"\\.\pipe\{}", name) };
+        Self { path, pipe: Mutex::new(None), id: HandlerId::new() }
+    }
+    /// The full path of the pipe, e.g. `\\.\pipe\log-collector`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    fn write(&self, pipe: &mut Option<File>, line: &[u8]) -> io::Result<()> {
+        let pipe = match pipe {
+            Some(pipe) => pipe,
+            None => pipe.insert(OpenOptions::new().write(true).open(&self.path)?),
+        };
+        pipe.write_all(line)
+    }
+}
+impl Handler for NamedPipeHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut line = record.formatted.clone().unwrap_or_else(|| DefaultFormatter.format(record));
+        line.push('\n');
+        let mut pipe = self.pipe.lock().expect("Named pipe handler is poisoned");
+        let had_pipe = pipe.is_some();
+        let mut written = self.write(&mut pipe, line.as_bytes());
+        if written.is_err() && had_pipe {
+            // the other end may have been restarted, so connect again
+            *pipe = None;
+            written = self.write(&mut pipe, line.as_bytes());
+        }
+        if let Err(err) = written {
+            *pipe = None;
+            self.id.record_failure(&self.describe(), err);
+            self.id.record_dropped(&self.describe(), record);
+        }
+    }
+    fn reopen(&self) {
+        *self.pipe.lock().expect("Named pipe handler is poisoned") = None;
+    }
+    fn close(&self) {
+        if let Some(pipe) = self.pipe.lock().expect("Named pipe handler is poisoned").as_mut() {
+            let _ = pipe.flush();
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
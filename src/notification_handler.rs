@@ -0,0 +1,91 @@
+use crate::stats::HandlerId;
+use crate::{Handler, HandlerHealth, Level, LogLevel, Record};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A [Handler](Handler) raising a desktop notification for severe records, e.g. so a long-running local tool or development daemon
+/// can get your attention when something goes wrong.
+///
+/// By default, only [CRITICAL](Level::CRITICAL) and [FATAL](Level::FATAL) records are shown, and at most one notification every 30 seconds,
+/// so a burst of errors doesn't flood the desktop. Records that aren't shown because of the rate limit are dropped and counted in [stats](crate::stats).
+///
+/// # Examples
+///
+/// ```no_run
+/// use logging::{Level, Logger, NotificationHandler};
+/// use std::time::Duration;
+/// let logger = Logger::new("builder");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(NotificationHandler::new()
+///     .with_app_name("builder")
+///     .with_level(Level::ERROR)
+///     .with_min_interval(Duration::from_secs(10)));
+/// logger.error("The build failed");
+/// ```
+pub struct NotificationHandler {
+    level: LogLevel,
+    min_interval: Duration,
+    app_name: Option<String>,
+    last_shown: Mutex<Option<Instant>>,
+    id: HandlerId,
+}
+impl NotificationHandler {
+    /// Create a handler showing CRITICAL and FATAL records, at most one every 30 seconds.
+    pub fn new() -> Self {
+        Self { level: Level::CRITICAL, min_interval: Duration::from_secs(30), app_name: None, last_shown: Mutex::new(None), id: HandlerId::new() }
+    }
+    /// Only show records at or above this level. Lower records are ignored.
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+    /// Set how long to wait after a notification before showing the next one.
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+    /// Set the name of the application shown with the notification.
+    pub fn with_app_name(mut self, app_name: impl ToString) -> Self {
+        self.app_name = Some(app_name.to_string());
+        self
+    }
+}
+impl Default for NotificationHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Handler for NotificationHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        if record.level < self.level {
+            return;
+        }
+        {
+            let mut last_shown = self.last_shown.lock().expect("Notification handler is poisoned");
+            if last_shown.is_some_and(|last_shown| last_shown.elapsed() < self.min_interval) {
+                self.id.record_dropped(&self.describe(), record);
+                return;
+            }
+            *last_shown = Some(Instant::now());
+        }
+        let summary = if record.logger.is_empty() {
+            Level::name(record.level)
+        } else {
+            format!("{} ({})", Level::name(record.level), record.logger)
+        };
+        let mut notification = notify_rust::Notification::new();
+        notification.summary(&summary).body(&record.message);
+        if let Some(app_name) = &self.app_name {
+            notification.appname(app_name);
+        }
+        if let Err(err) = notification.show() {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
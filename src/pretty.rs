@@ -0,0 +1,21 @@
+use std::fmt::Debug;
+
+/// Pretty-print a value with [Debug](Debug) (`{:#?}`) for logging.
+/// Every line after the first is indented, so no part of the value starts at the beginning of a line and looks like a separate record.
+///
+/// # Arguments
+///
+/// * `value`: The value to print.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug)]
+/// struct Config { port: u16 }
+/// assert_eq!(logging::pretty_debug(&Config { port: 80 }), "Config {\n        port: 80,\n    }");
+/// ```
+pub fn pretty_debug<T: Debug + ?Sized>(value: &T) -> String {
+    format!("{:#?}", value).replace('\n', "\n    ")
+}
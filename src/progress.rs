@@ -0,0 +1,96 @@
+use crate::{Level, LogLevel, Logger};
+use std::time::{Duration, Instant};
+
+/// Reports the progress of long running jobs through a logger.
+///
+/// Progress is logged whenever another milestone (every 10% by default) is reached,
+/// but not more often than once per interval (1 second by default).
+/// Completion is always logged.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, Progress};
+/// let logger = Logger::new("import");
+/// logger.set_level(Level::ALL);
+///
+/// let mut progress = Progress::new(&logger, "importing rows").with_step(25);
+/// for row in 1..=1000 {
+///     // import the row
+///     progress.update(row, 1000);
+/// }
+/// // logs "importing rows: 25% (250/1000)" and "importing rows: 100% (1000/1000)"
+/// ```
+pub struct Progress {
+    logger: Logger,
+    label: String,
+    level: LogLevel,
+    step: u64,
+    interval: Duration,
+    last_milestone: u64,
+    last_logged: Option<Instant>,
+    done: bool,
+}
+impl Progress {
+    /// Create a new progress reporter logging at [Level::INFO](Level::INFO).
+    ///
+    /// # Arguments
+    ///
+    /// * `logger`: The logger to report the progress with.
+    /// * `label`: What is being done. Used in the logged messages.
+    ///
+    /// returns: Progress
+    pub fn new(logger: &Logger, label: impl ToString) -> Self {
+        Self {
+            logger: logger.clone(),
+            label: label.to_string(),
+            level: Level::INFO,
+            step: 10,
+            interval: Duration::from_secs(1),
+            last_milestone: 0,
+            last_logged: None,
+            done: false,
+        }
+    }
+    /// Set the level the progress is logged at.
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+    /// Set the milestones in percent. Values are clamped to 1..=100.
+    pub fn with_step(mut self, percent: u8) -> Self {
+        self.step = percent.clamp(1, 100) as u64;
+        self
+    }
+    /// Set the minimum time between two progress messages.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+    /// Update the progress. Logs a message if a new milestone has been reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `done`: How many units of work are done.
+    /// * `total`: How many units of work there are in total.
+    ///
+    /// returns: ()
+    pub fn update(&mut self, done: u64, total: u64) {
+        if self.done {
+            return;
+        }
+        let percent = match total {
+            0 => 100,
+            total => (done.min(total) as u128 * 100 / total as u128) as u64,
+        };
+        if percent == 100 {
+            self.done = true;
+        } else if percent < self.last_milestone + self.step
+            || self.last_logged.is_some_and(|last| last.elapsed() < self.interval) {
+            return;
+        }
+        self.last_milestone = percent - percent % self.step;
+        self.last_logged = Some(Instant::now());
+        self.logger.log(format!("{}: {}% ({}/{})", self.label, percent, done, total), self.level);
+    }
+}
@@ -0,0 +1,101 @@
+//! Export the logging [stats](crate::stats) as Prometheus metrics.
+//!
+//! The following metrics are exported:
+//! * `logging_records_emitted_total{logger, level}`
+//! * `logging_records_dropped_total{logger, level}`
+//! * `logging_handler_errors_total{handler}`
+
+use crate::{Level, LogLevel};
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{IntCounterVec, Opts, Registry};
+use std::sync::Mutex;
+
+/// A Prometheus [Collector](Collector) reading the logging counters whenever it is scraped.
+pub struct LoggingCollector {
+    emitted: IntCounterVec,
+    dropped: IntCounterVec,
+    handler_errors: IntCounterVec,
+    collecting: Mutex<()>,
+}
+impl LoggingCollector {
+    /// Create a new collector.
+    ///
+    /// returns: LoggingCollector
+    pub fn new() -> Self {
+        let vec = |name: &str, help: &str, labels: &[&str]| IntCounterVec::new(Opts::new(name, help), labels)
+            .expect("Invalid metric");
+        Self {
+            emitted: vec("logging_records_emitted_total", "Records passed on to the handlers.", &["logger", "level"]),
+            dropped: vec("logging_records_dropped_total", "Records dropped before reaching their destination.", &["logger", "level"]),
+            handler_errors: vec("logging_handler_errors_total", "Failures of handlers.", &["handler"]),
+            collecting: Mutex::new(()),
+        }
+    }
+}
+impl Default for LoggingCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Collector for LoggingCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        [&self.emitted, &self.dropped, &self.handler_errors].into_iter()
+            .flat_map(|vec| vec.desc())
+            .collect()
+    }
+    fn collect(&self) -> Vec<MetricFamily> {
+        let _collecting = self.collecting.lock().expect("Collector is poisoned");
+        let stats = crate::stats();
+        self.emitted.reset();
+        self.dropped.reset();
+        self.handler_errors.reset();
+        for (logger, levels) in &stats.by_logger {
+            for (level, counts) in levels {
+                let level = level_label(*level);
+                let labels = [logger.as_str(), level.as_str()];
+                self.emitted.with_label_values(&labels).inc_by(counts.emitted);
+                self.dropped.with_label_values(&labels).inc_by(counts.dropped);
+            }
+        }
+        for (handler, errors) in &stats.handler_errors {
+            self.handler_errors.with_label_values(&[handler.as_str()]).inc_by(*errors);
+        }
+        [&self.emitted, &self.dropped, &self.handler_errors].into_iter()
+            .flat_map(|vec| vec.collect())
+            .collect()
+    }
+}
+fn level_label(level: LogLevel) -> String {
+    Level::get_level(level).unwrap_or(level.to_string())
+}
+
+/// Register a [LoggingCollector](LoggingCollector) with a registry.
+///
+/// # Arguments
+///
+/// * `registry`: The registry the host application exposes to Prometheus.
+///
+/// returns: Result<(), Error>
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// use prometheus::{Encoder, Registry, TextEncoder};
+///
+/// let registry = Registry::new();
+/// logging::prometheus::register(&registry).unwrap();
+///
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.warn("Disk almost full");
+///
+/// let mut output = Vec::new();
+/// TextEncoder::new().encode(&registry.gather(), &mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains(r#"logging_records_emitted_total{level="WARN",logger="foo"} 1"#));
+/// ```
+pub fn register(registry: &Registry) -> prometheus::Result<()> {
+    registry.register(Box::new(LoggingCollector::new()))
+}
@@ -0,0 +1,144 @@
+use crate::{FieldValue, LogLevel};
+use std::backtrace::Backtrace;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// The sequence number of the last record created.
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Where in the source code a record was logged, captured by the logging macros like [info!](crate::info!).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Source {
+    /// The module the record was logged in, as given by [module_path!].
+    pub module_path: &'static str,
+    /// The source file the record was logged in.
+    pub file: &'static str,
+    /// The line the record was logged on.
+    pub line: u32,
+}
+
+/// A single message being logged, together with everything known about it.
+/// Records are created by the loggers and passed to [Handler::handle](crate::Handler::handle).
+///
+/// With the `serde` feature, records can be serialized and deserialized, e.g. to send them over the network.
+/// The backtrace is not part of the serialized record.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use logging::{Level, Record};
+/// let record = Record::new(Level::INFO, "Hello World".to_string(), "foo".to_string());
+/// let json = serde_json::to_string(&record).unwrap();
+/// let deserialized: Record = serde_json::from_str(&json).unwrap();
+/// assert_eq!(deserialized.message, "Hello World");
+/// assert_eq!(deserialized.time, record.time);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Record {
+    /// The level the message is being logged at.
+    pub level: LogLevel,
+    /// The number of the record, counting up by one for every record created by this process, starting at 1.
+    /// Consumers can detect lost records by gaps, and restore the order of records merged from several handlers or threads.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sequence: u64,
+    /// The message itself.
+    pub message: String,
+    /// The full name of the logger that logged the message.
+    pub logger: String,
+    /// Structured key-value pairs attached to the message.
+    pub fields: Vec<(String, FieldValue)>,
+    /// When the message was logged.
+    pub time: SystemTime,
+    /// The correlation ID of the thread the message was logged on (see [correlation](crate::correlation)).
+    pub correlation_id: Option<String>,
+    /// The span ID of the thread the message was logged on (see [set_span](crate::correlation::set_span)).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub span_id: Option<String>,
+    /// The template the message was rendered from, if it was logged with [log_template](crate::Logger::log_template), e.g. `user {user} did {action}`.
+    /// All records of the same kind share it, so structured backends can group by it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub template: Option<String>,
+    /// The audience tags of the record, e.g. `user-facing` or `internal`, set with [with_tags](crate::Logger::with_tags) or the `tags:` form of the logging macros.
+    /// Handlers can be routed on them with a [TagFilter](crate::TagFilter), independent of the level.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
+    /// The record already rendered by a [Formatter](crate::Formatter), e.g. by a [ConfiguredHandler](crate::ConfiguredHandler).
+    /// Handlers writing text should use this instead of their own formatting if it is set.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub formatted: Option<String>,
+    /// Where the message was logged from, if backtraces are captured for its level (see [set_backtrace_level](crate::set_backtrace_level)).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub backtrace: Option<Arc<Backtrace>>,
+    /// Where in the source code the message was logged, if it was logged with one of the logging macros.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub source: Option<Source>,
+}
+impl Record {
+    /// Create a new record logged now.
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The level of the message.
+    /// * `message`: The message.
+    /// * `logger`: The name of the logger logging the message.
+    ///
+    /// returns: Record
+    pub fn new(level: LogLevel, message: String, logger: String) -> Self {
+        let mut record = Self::unsequenced(level, message, logger);
+        record.sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1;
+        record
+    }
+    /// Create a record without taking a sequence number, for records that aren't logged, e.g. ones read back from a file.
+    pub(crate) fn unsequenced(level: LogLevel, message: String, logger: String) -> Self {
+        Self {
+            level,
+            sequence: 0,
+            message,
+            logger,
+            fields: Vec::new(),
+            time: SystemTime::now(),
+            correlation_id: crate::correlation::current(),
+            span_id: crate::correlation::current_span(),
+            template: None,
+            tags: Vec::new(),
+            formatted: None,
+            backtrace: None,
+            source: None,
+        }
+    }
+    /// The value of a field, if the record has it.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The key of the field.
+    ///
+    /// returns: Option<&FieldValue>
+    pub fn field(&self, key: &str) -> Option<&FieldValue> {
+        self.fields.iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, value)| value)
+    }
+    /// Whether the record has a tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag, e.g. `user-facing`.
+    ///
+    /// returns: bool
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|known| known == tag)
+    }
+    /// Capture a backtrace for this record if it doesn't have one yet.
+    ///
+    /// returns: ()
+    pub fn capture_backtrace(&mut self) {
+        if self.backtrace.is_none() {
+            self.backtrace = Some(Arc::new(Backtrace::force_capture()));
+        }
+    }
+}
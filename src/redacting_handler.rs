@@ -0,0 +1,321 @@
+use crate::{FieldValue, Handler, HandlerConfig, HandlerHealth, LogLevel, Record};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Keys whose values are redacted by [RedactingHandler::redact_tokens](RedactingHandler::redact_tokens), e.g. in `password=hunter2` or `"api_key": "abc"`.
+const SECRET_KEYS: [&str; 8] = ["password", "passwd", "secret", "token", "api_key", "apikey", "access_key", "private_key"];
+
+#[derive(Clone)]
+enum Rule {
+    Keyword(String),
+    Emails,
+    CreditCards,
+    Tokens,
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+impl Rule {
+    fn spans(&self, text: &str) -> Vec<Range<usize>> {
+        match self {
+            Rule::Keyword(keyword) => text.match_indices(keyword.as_str())
+                .map(|(start, keyword)| start..start + keyword.len())
+                .collect(),
+            Rule::Emails => email_spans(text),
+            Rule::CreditCards => credit_card_spans(text),
+            Rule::Tokens => token_spans(text),
+            #[cfg(feature = "regex")]
+            Rule::Regex(regex) => regex.find_iter(text).map(|found| found.range()).collect(),
+        }
+    }
+}
+
+/// Removes sensitive information from messages before passing them on to another handler,
+/// so it never reaches log files or the network.
+///
+/// Redacted parts are replaced with `[REDACTED]` (see [with_replacement](RedactingHandler::with_replacement)).
+/// The message, the text values of the [fields](Record::fields) (also nested ones) and an already [formatted](Record::formatted) record are redacted.
+/// With [redact_tokens](RedactingHandler::redact_tokens), fields named like `password` or `api_key` are redacted entirely.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{fields, FieldValue, Handler, Level, Logger, LogLevel, MemoryHandler, RedactingHandler};
+/// use std::sync::{Arc, Mutex};
+///
+/// #[derive(Default)]
+/// struct Collect(Mutex<Vec<String>>);
+/// impl Handler for Collect {
+///     fn log(&self, _level: LogLevel, message: String, _logger: String) {
+///         self.0.lock().unwrap().push(message);
+///     }
+/// }
+///
+/// let collected = Arc::new(Collect::default());
+/// let logger = Logger::new("payments");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(RedactingHandler::new(Arc::clone(&collected)).with_default_rules().redact_keyword("hunter2"));
+///
+/// logger.info("jane@example.com paid with 4111 1111 1111 1111");
+/// logger.info("login with password=hunter2, Authorization: Bearer abc.def");
+/// assert_eq!(*collected.0.lock().unwrap(), [
+///     "[REDACTED] paid with [REDACTED]",
+///     "login with password=[REDACTED], Authorization: Bearer [REDACTED]",
+/// ]);
+///
+/// // fields are redacted as well
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("signup");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(RedactingHandler::new(Arc::clone(&memory)).with_default_rules());
+/// logger.log_fields("signed up", Level::INFO, fields! { user = fields! { email = "jane@example.com" }, api_key = 12345 });
+/// let record = &memory.records()[0];
+/// assert_eq!(record.field("user").unwrap().to_string(), "{email=[REDACTED]}");
+/// assert_eq!(record.field("api_key"), Some(&FieldValue::from("[REDACTED]")));
+/// ```
+#[derive(Clone)]
+pub struct RedactingHandler {
+    inner: Arc<dyn Handler>,
+    rules: Vec<Rule>,
+    replacement: String,
+}
+impl RedactingHandler {
+    /// Wrap a handler. Without any rules, nothing is redacted.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: The handler receiving the redacted records.
+    ///
+    /// returns: RedactingHandler
+    pub fn new<T: Handler + 'static>(inner: T) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            rules: Vec::new(),
+            replacement: "[REDACTED]".to_string(),
+        }
+    }
+    /// Set the text redacted parts are replaced with.
+    pub fn with_replacement(mut self, replacement: impl ToString) -> Self {
+        self.replacement = replacement.to_string();
+        self
+    }
+    /// Redact emails, credit card numbers and tokens.
+    pub fn with_default_rules(self) -> Self {
+        self.redact_emails().redact_credit_cards().redact_tokens()
+    }
+    /// Redact every occurrence of a keyword, e.g. a known secret.
+    pub fn redact_keyword(mut self, keyword: impl ToString) -> Self {
+        let keyword = keyword.to_string();
+        if !keyword.is_empty() {
+            self.rules.push(Rule::Keyword(keyword));
+        }
+        self
+    }
+    /// Redact email addresses.
+    pub fn redact_emails(mut self) -> Self {
+        self.rules.push(Rule::Emails);
+        self
+    }
+    /// Redact credit card numbers (13 to 19 digits, optionally separated by spaces or dashes, with a valid checksum).
+    pub fn redact_credit_cards(mut self) -> Self {
+        self.rules.push(Rule::CreditCards);
+        self
+    }
+    /// Redact bearer tokens and the values of keys like `password`, `secret` or `api_key`.
+    pub fn redact_tokens(mut self) -> Self {
+        self.rules.push(Rule::Tokens);
+        self
+    }
+    /// Redact everything matching a regular expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The regular expression.
+    ///
+    /// returns: Result<RedactingHandler, Error> An error if the pattern is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{ConsoleHandler, RedactingHandler};
+    /// let handler = RedactingHandler::new(ConsoleHandler::new())
+    ///     .redact_regex(r"\b\d{3}-\d{2}-\d{4}\b")
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn redact_regex(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.rules.push(Rule::Regex(regex::Regex::new(pattern)?));
+        Ok(self)
+    }
+    /// Redact a text according to the rules of this handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: The text to redact.
+    ///
+    /// returns: String
+    pub fn redact(&self, text: &str) -> String {
+        let mut spans: Vec<Range<usize>> = self.rules.iter().flat_map(|rule| rule.spans(text)).collect();
+        if spans.is_empty() {
+            return text.to_string();
+        }
+        spans.sort_by_key(|span| span.start);
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(spans.len());
+        for span in spans {
+            match merged.last_mut() {
+                Some(last) if span.start <= last.end => last.end = last.end.max(span.end),
+                _ => merged.push(span),
+            }
+        }
+        let mut redacted = String::with_capacity(text.len());
+        let mut position = 0;
+        for span in merged {
+            redacted.push_str(&text[position..span.start]);
+            redacted.push_str(&self.replacement);
+            position = span.end;
+        }
+        redacted.push_str(&text[position..]);
+        redacted
+    }
+    fn redact_value(&self, key: &str, value: &mut FieldValue) {
+        let lower = key.to_ascii_lowercase();
+        if self.rules.iter().any(|rule| matches!(rule, Rule::Tokens)) && SECRET_KEYS.iter().any(|secret| lower.contains(secret)) {
+            *value = FieldValue::Str(self.replacement.clone());
+            return;
+        }
+        match value {
+            FieldValue::Str(text) => *text = self.redact(text),
+            FieldValue::Map(fields) => fields.iter_mut().for_each(|(key, value)| self.redact_value(key, value)),
+            FieldValue::List(values) => values.iter_mut().for_each(|value| self.redact_value("", value)),
+            FieldValue::Lazy(lazy) => {
+                *value = lazy.evaluate();
+                self.redact_value(key, value);
+            }
+            _ => {}
+        }
+    }
+}
+impl Handler for RedactingHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut record = record.clone();
+        record.message = self.redact(&record.message);
+        for (key, value) in &mut record.fields {
+            self.redact_value(key, value);
+        }
+        record.formatted = record.formatted.map(|formatted| self.redact(&formatted));
+        self.inner.handle(&record)
+    }
+    fn reopen(&self) {
+        self.inner.reopen()
+    }
+    fn close(&self) {
+        self.inner.close()
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        self.inner.accepts(record)
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("RedactingHandler").with_inner(self.inner.describe())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        self.inner.health()
+    }
+}
+
+fn email_spans(text: &str) -> Vec<Range<usize>> {
+    let is_local = |byte: u8| byte.is_ascii_alphanumeric() || b"._%+-".contains(&byte);
+    let is_domain = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'-';
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    for (at, _) in text.match_indices('@') {
+        let start = bytes[..at].iter().rposition(|byte| !is_local(*byte)).map_or(0, |index| index + 1);
+        let mut end = bytes[at + 1..].iter().position(|byte| !is_domain(*byte)).map_or(bytes.len(), |index| at + 1 + index);
+        while end > at + 1 && bytes[end - 1] == b'.' {
+            end -= 1;
+        }
+        let domain = &text[at + 1..end];
+        let valid_tld = domain.rsplit_once('.')
+            .is_some_and(|(name, tld)| !name.is_empty() && tld.len() >= 2 && tld.bytes().all(|byte| byte.is_ascii_alphabetic()));
+        if start < at && valid_tld {
+            spans.push(start..end);
+        }
+    }
+    spans
+}
+fn credit_card_spans(text: &str) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        if !bytes[index].is_ascii_digit() || (index > 0 && bytes[index - 1].is_ascii_alphanumeric()) {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        let mut end = index;
+        let mut digits = Vec::new();
+        while index < bytes.len() {
+            match bytes[index] {
+                byte if byte.is_ascii_digit() => {
+                    digits.push(byte - b'0');
+                    end = index + 1;
+                }
+                b' ' | b'-' if bytes.get(index + 1).is_some_and(u8::is_ascii_digit) => {}
+                _ => break,
+            }
+            index += 1;
+        }
+        let followed_by_letter = bytes.get(end).is_some_and(u8::is_ascii_alphabetic);
+        if (13..=19).contains(&digits.len()) && !followed_by_letter && luhn(&digits) {
+            spans.push(start..end);
+        }
+    }
+    spans
+}
+fn luhn(digits: &[u8]) -> bool {
+    let sum: u32 = digits.iter().rev().enumerate()
+        .map(|(index, &digit)| match (index % 2, digit * 2) {
+            (0, _) => digit as u32,
+            (_, doubled) if doubled > 9 => doubled as u32 - 9,
+            (_, doubled) => doubled as u32,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+fn token_spans(text: &str) -> Vec<Range<usize>> {
+    let is_token = |byte: u8| byte.is_ascii_alphanumeric() || b"-._~+/=".contains(&byte);
+    let is_value = |byte: u8| !byte.is_ascii_whitespace() && !b"\"'&,;".contains(&byte);
+    let lower = text.to_ascii_lowercase();
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    for (start, _) in lower.match_indices("bearer ") {
+        let value_start = start + "bearer ".len();
+        let value_end = bytes[value_start..].iter().position(|byte| !is_token(*byte)).map_or(bytes.len(), |index| value_start + index);
+        if value_end > value_start {
+            spans.push(value_start..value_end);
+        }
+    }
+    for key in SECRET_KEYS {
+        for (start, _) in lower.match_indices(key) {
+            let mut index = start + key.len();
+            // skip a closing quote and whitespace up to the separator
+            while index < bytes.len() && (bytes[index] == b'"' || bytes[index] == b'\'' || bytes[index] == b' ') {
+                index += 1;
+            }
+            if index >= bytes.len() || (bytes[index] != b'=' && bytes[index] != b':') {
+                continue;
+            }
+            index += 1;
+            while index < bytes.len() && (bytes[index] == b'"' || bytes[index] == b'\'' || bytes[index] == b' ') {
+                index += 1;
+            }
+            let value_end = bytes[index..].iter().position(|byte| !is_value(*byte)).map_or(bytes.len(), |end| index + end);
+            if value_end > index {
+                spans.push(index..value_end);
+            }
+        }
+    }
+    spans
+}
@@ -0,0 +1,365 @@
+use crate::{FieldValue, Handler, Level, LogLevel, Record, RecordReader};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+fn invalid(message: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Reads records written by the [JsonFormatter](crate::JsonFormatter), e.g. the files of a [JsonlFileHandler](crate::JsonlFileHandler),
+/// one JSON object per line. Empty lines are skipped.
+///
+/// Iterates over the records until the end of the input. A line that isn't a record ends the iteration with an error.
+/// Times are only kept to the millisecond, like they are written, and lazy fields were written evaluated.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{FieldValue, JsonlReader, Level};
+/// let lines = r#"{"schema_version":3,"time":"2024-05-01T12:30:00.000Z","sequence":7,"level":"WARN","level_number":30,"logger":"disk","message":"almost full","fields":{"percent":93}}"#;
+/// let records: Vec<_> = JsonlReader::new(lines.as_bytes()).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(records[0].level, Level::WARN);
+/// assert_eq!(records[0].sequence, 7);
+/// assert_eq!(records[0].field("percent"), Some(&FieldValue::Int(93)));
+///
+/// // malformed lines are errors, not panics
+/// let line = r#"{"time":"99999999999999999-01-01T00:00:00Z","level":"INFO","message":"from the future"}"#;
+/// let error = JsonlReader::new(line.as_bytes()).next().unwrap().unwrap_err();
+/// assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+/// ```
+pub struct JsonlReader<R> {
+    source: R,
+    line: usize,
+    failed: bool,
+}
+impl<R: BufRead> JsonlReader<R> {
+    /// Read records from any source.
+    pub fn new(source: R) -> Self {
+        Self { source, line: 0, failed: false }
+    }
+}
+impl JsonlReader<BufReader<File>> {
+    /// Read the records of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file written with the [JsonFormatter](crate::JsonFormatter).
+    ///
+    /// returns: Result<JsonlReader<BufReader<File>>, Error>
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufReader::new(File::open(path)?)))
+    }
+}
+impl<R: BufRead> Iterator for JsonlReader<R> {
+    type Item = io::Result<Record>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let mut line = String::new();
+        loop {
+            line.clear();
+            self.line += 1;
+            match self.source.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) if line.trim().is_empty() => continue,
+                Ok(_) => break,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        let record = parse_record(line.trim()).map_err(|error| invalid(format!("line {}: {}", self.line, error)));
+        self.failed = record.is_err();
+        Some(record)
+    }
+}
+
+/// Read the records of a log file written with the [JsonFormatter](crate::JsonFormatter) or by a [BinaryHandler](crate::BinaryHandler).
+/// The format is detected from the start of the file.
+///
+/// # Arguments
+///
+/// * `path`: The log file.
+///
+/// returns: Result<Box<dyn Iterator<Item=Result<Record, Error>>+Send>, Error>
+pub fn read_log_file(path: impl AsRef<Path>) -> io::Result<Box<dyn Iterator<Item = io::Result<Record>> + Send>> {
+    let mut source = BufReader::new(File::open(path)?);
+    let jsonl = match source.fill_buf()?.iter().find(|byte| !byte.is_ascii_whitespace()) {
+        // a binary record always starts with an array marker
+        Some(byte) => *byte == b'{',
+        None => true,
+    };
+    Ok(match jsonl {
+        true => Box::new(JsonlReader::new(source)),
+        false => Box::new(RecordReader::new(source)),
+    })
+}
+
+/// Pass records read back from a log file through a handler, for reprocessing old logs offline,
+/// e.g. to format them differently or to search them with a [MemoryHandler](crate::MemoryHandler).
+/// The records keep their time, sequence number and ids.
+///
+/// # Arguments
+///
+/// * `records`: The records, e.g. from [read_log_file](read_log_file).
+/// * `handler`: The handler to pass them to, e.g. a [ConfiguredHandler](crate::ConfiguredHandler) for filtering and formatting.
+///
+/// returns: Result<usize, Error> The number of records replayed, or the first error reading them. The records before the error are replayed.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{JsonlFileHandler, Level, Logger, MemoryHandler};
+/// let path = std::env::temp_dir().join(format!("logging-replay-{}.jsonl", std::process::id()));
+/// let logger = Logger::new("replay");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(JsonlFileHandler::new(&path).unwrap());
+/// logger.info("started");
+/// logger.error("disk full");
+///
+/// let memory = MemoryHandler::new(100);
+/// let replayed = logging::replay(logging::read_log_file(&path).unwrap(), &memory).unwrap();
+/// assert_eq!(replayed, 2);
+/// let errors: Vec<_> = memory.query().by_level(Level::ERROR..).iter().collect();
+/// assert_eq!(errors[0].message, "disk full");
+/// assert_eq!(errors[0].logger, "replay");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn replay(records: impl IntoIterator<Item = io::Result<Record>>, handler: &dyn Handler) -> io::Result<usize> {
+    let mut replayed = 0;
+    for record in records {
+        handler.handle(&record?);
+        replayed += 1;
+    }
+    Ok(replayed)
+}
+
+fn parse_record(line: &str) -> Result<Record, String> {
+    let mut parser = Parser { text: line, position: 0, depth: 0 };
+    let Json::Object(entries) = parser.value()? else { return Err("not an object".to_string()) };
+    parser.skip_whitespace();
+    if parser.position < line.len() {
+        return Err(format!("unexpected text at column {}", parser.position + 1));
+    }
+    let get = |key: &str| entries.iter().find(|(name, _)| name == key).map(|(_, value)| value);
+    let text = |key: &str| match get(key) {
+        Some(Json::Str(text)) => Ok(Some(text.clone())),
+        None | Some(Json::Null) => Ok(None),
+        Some(_) => Err(format!("{} must be a string", key)),
+    };
+    let level: LogLevel = match (get("level_number"), get("level")) {
+        (Some(Json::Int(level)), _) => LogLevel::try_from(*level).map_err(|_| format!("invalid level {}", level))?,
+        (_, Some(Json::Str(name))) => Level::by_name(name).ok_or_else(|| format!("unknown level '{}'", name))?,
+        _ => return Err("missing level".to_string()),
+    };
+    let message = text("message")?.ok_or("missing message")?;
+    let mut record = Record::unsequenced(level, message, text("logger")?.unwrap_or_default());
+    if let Some(time) = text("time")? {
+        record.time = crate::formatter::parse_time(&time).ok_or_else(|| format!("invalid time '{}'", time))?;
+    }
+    record.sequence = match get("sequence") {
+        Some(Json::Int(sequence)) => u64::try_from(*sequence).map_err(|_| format!("invalid sequence {}", sequence))?,
+        _ => 0,
+    };
+    record.fields = match get("fields") {
+        Some(Json::Object(fields)) => fields.iter().map(|(key, value)| (key.clone(), value.to_field())).collect(),
+        _ => Vec::new(),
+    };
+    record.correlation_id = text("correlation_id")?;
+    record.span_id = text("span_id")?;
+    record.template = text("template")?;
+    record.tags = match get("tags") {
+        Some(Json::List(tags)) => tags.iter()
+            .map(|tag| match tag {
+                Json::Str(tag) => Ok(tag.clone()),
+                _ => Err("tags must be strings".to_string()),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => Vec::new(),
+    };
+    Ok(record)
+}
+
+/// A parsed JSON value, keeping the order of object entries like the fields of a record.
+enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    List(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+impl Json {
+    fn to_field(&self) -> FieldValue {
+        match self {
+            Json::Null => FieldValue::Str("null".to_string()),
+            Json::Bool(value) => FieldValue::Bool(*value),
+            Json::Int(number) => FieldValue::Int(*number),
+            Json::Float(number) => FieldValue::Float(*number),
+            Json::Str(text) => FieldValue::Str(text.clone()),
+            Json::List(values) => FieldValue::List(values.iter().map(Json::to_field).collect()),
+            Json::Object(entries) => FieldValue::Map(entries.iter().map(|(key, value)| (key.clone(), value.to_field())).collect()),
+        }
+    }
+}
+
+/// How deeply values may be nested, so a crafted line can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    /// How many values are being parsed around the current one.
+    depth: usize,
+}
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.rest().starts_with(token) {
+            true => {
+                self.position += token.len();
+                Ok(())
+            }
+            false => Err(format!("expected '{}' at column {}", token, self.position + 1)),
+        }
+    }
+    fn value(&mut self) -> Result<Json, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("nested too deeply at column {}", self.position + 1));
+        }
+        self.depth += 1;
+        let value = self.nested_value();
+        self.depth -= 1;
+        value
+    }
+    fn nested_value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        match rest.as_bytes().first() {
+            Some(b'{') => {
+                self.position += 1;
+                let mut entries = Vec::new();
+                self.skip_whitespace();
+                if self.rest().starts_with('}') {
+                    self.position += 1;
+                    return Ok(Json::Object(entries));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(":")?;
+                    entries.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.rest().as_bytes().first() {
+                        Some(b',') => self.position += 1,
+                        _ => break,
+                    }
+                }
+                self.expect("}")?;
+                Ok(Json::Object(entries))
+            }
+            Some(b'[') => {
+                self.position += 1;
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.rest().starts_with(']') {
+                    self.position += 1;
+                    return Ok(Json::List(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.rest().as_bytes().first() {
+                        Some(b',') => self.position += 1,
+                        _ => break,
+                    }
+                }
+                self.expect("]")?;
+                Ok(Json::List(values))
+            }
+            Some(b'"') => self.string().map(Json::Str),
+            _ if rest.starts_with("true") => {
+                self.position += 4;
+                Ok(Json::Bool(true))
+            }
+            _ if rest.starts_with("false") => {
+                self.position += 5;
+                Ok(Json::Bool(false))
+            }
+            _ if rest.starts_with("null") => {
+                self.position += 4;
+                Ok(Json::Null)
+            }
+            _ => {
+                let length = rest.find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E')).unwrap_or(rest.len());
+                let number = &rest[..length];
+                let value = match number.contains(['.', 'e', 'E']) {
+                    false => number.parse().map(Json::Int).ok(),
+                    true => None,
+                };
+                let value = match value {
+                    Some(value) => value,
+                    None => number.parse().map(Json::Float).map_err(|_| format!("unexpected value at column {}", self.position + 1))?,
+                };
+                self.position += length;
+                Ok(value)
+            }
+        }
+    }
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut text = String::new();
+        let mut characters = self.rest().char_indices();
+        while let Some((index, character)) = characters.next() {
+            match character {
+                '"' => {
+                    self.position += index + 1;
+                    return Ok(text);
+                }
+                '\\' => match characters.next().map(|(_, escaped)| escaped) {
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some('b') => text.push('\u{8}'),
+                    Some('f') => text.push('\u{c}'),
+                    Some('u') => {
+                        let code = hex_digits(&mut characters).ok_or("invalid unicode escape")?;
+                        let code = match code {
+                            // a surrogate pair
+                            0xd800..=0xdbff => {
+                                let (Some((_, '\\')), Some((_, 'u'))) = (characters.next(), characters.next()) else {
+                                    return Err("invalid unicode escape".to_string());
+                                };
+                                let low = hex_digits(&mut characters).ok_or("invalid unicode escape")?;
+                                0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff)
+                            }
+                            code => code,
+                        };
+                        text.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    Some(escaped) => text.push(escaped),
+                    None => break,
+                },
+                character => text.push(character),
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+}
+
+/// The code unit of a `\u` escape.
+fn hex_digits(characters: &mut std::str::CharIndices) -> Option<u32> {
+    let digits: String = characters.take(4).map(|(_, digit)| digit).collect();
+    u32::from_str_radix(&digits, 16).ok().filter(|_| digits.len() == 4)
+}
@@ -0,0 +1,148 @@
+use crate::formatter::civil_from_days;
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerHealth, LogLevel, Record};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+struct Current {
+    file: File,
+    path: PathBuf,
+    date: String,
+    index: u32,
+    size: u64,
+}
+
+/// A [Handler](Handler) writing to files named after a template, starting a new file every day and whenever the current one gets too large.
+///
+/// The template may contain these placeholders:
+/// * `{date}`: The current date (UTC), e.g. `2024-05-01`. A new file is started when it changes.
+/// * `{index}`: Counts up from 0 whenever the file has reached the [maximum size](RotatingFileHandler::with_max_size). Starts at 0 again every day.
+/// * `{hostname}`: The name of this machine.
+/// * `{pid}`: The ID of this process.
+///
+/// Without `{index}` in the template, size based rotation has no file to switch to, so the maximum size is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, RotatingFileHandler};
+/// let dir = std::env::temp_dir().join(format!("logging-rotating-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let handler = RotatingFileHandler::new(dir.join("app-{date}-{index}.log").to_str().unwrap())
+///     .unwrap()
+///     .with_max_size(20);
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(handler);
+/// logger.info("Hello World");
+/// logger.info("Hello again");
+/// assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub struct RotatingFileHandler {
+    template: String,
+    max_size: Option<u64>,
+    current: Mutex<Current>,
+    id: HandlerId,
+}
+impl RotatingFileHandler {
+    /// Create a handler writing to files named after the template. Existing files are appended to.
+    ///
+    /// # Arguments
+    ///
+    /// * `template`: The path of the files with placeholders, e.g. `logs/{hostname}-{date}-{index}.log`.
+    ///
+    /// returns: Result<RotatingFileHandler, Error>
+    pub fn new(template: impl ToString) -> io::Result<Self> {
+        let template = template.to_string();
+        let date = today();
+        let path = PathBuf::from(render(&template, &date, 0));
+        let file = open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { template, max_size: None, current: Mutex::new(Current { file, path, date, index: 0, size }), id: HandlerId::new() })
+    }
+    /// Start a new file when writing a record would make the current one larger than this.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes`: The maximum size of a file.
+    ///
+    /// returns: RotatingFileHandler
+    pub fn with_max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+    /// The path of the file currently written to.
+    pub fn path(&self) -> PathBuf {
+        self.current.lock().expect("Rotating file handler is poisoned").path.clone()
+    }
+    fn rotate(&self, current: &mut Current, date: String, index: u32) -> io::Result<()> {
+        let path = PathBuf::from(render(&self.template, &date, index));
+        let file = open(&path)?;
+        *current = Current { size: file.metadata()?.len(), file, path, date, index };
+        Ok(())
+    }
+}
+impl Handler for RotatingFileHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut line = record.formatted.clone().unwrap_or_else(|| DefaultFormatter.format(record));
+        line.push('\n');
+        let mut current = self.current.lock().expect("Rotating file handler is poisoned");
+        let date = today();
+        let mut rotated = Ok(());
+        if date != current.date && self.template.contains("{date}") {
+            rotated = self.rotate(&mut current, date, 0);
+        }
+        if let Some(max_size) = self.max_size.filter(|_| self.template.contains("{index}")) {
+            // skip files that are already full, e.g. from an earlier run
+            while rotated.is_ok() && current.size > 0 && current.size + line.len() as u64 > max_size {
+                let (date, index) = (current.date.clone(), current.index + 1);
+                rotated = self.rotate(&mut current, date, index);
+            }
+        }
+        let written = rotated.and_then(|_| current.file.write_all(line.as_bytes()));
+        match written {
+            Ok(()) => current.size += line.len() as u64,
+            Err(err) => self.id.record_failure(&self.describe(), err),
+        }
+    }
+    fn reopen(&self) {
+        let mut current = self.current.lock().expect("Rotating file handler is poisoned");
+        let (date, index) = (current.date.clone(), current.index);
+        if let Err(err) = self.rotate(&mut current, date, index) {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn close(&self) {
+        let mut current = self.current.lock().expect("Rotating file handler is poisoned");
+        if let Err(err) = current.file.flush() {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+
+fn open(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+/// Fill in the placeholders of a filename template.
+fn render(template: &str, date: &str, index: u32) -> String {
+    template
+        .replace("{date}", date)
+        .replace("{index}", &index.to_string())
+        .replace("{hostname}", crate::system::hostname())
+        .replace("{pid}", &std::process::id().to_string())
+}
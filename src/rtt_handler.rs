@@ -0,0 +1,165 @@
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerConfig, HandlerHealth, LogLevel, Record};
+use std::ptr::{self, addr_of, addr_of_mut};
+use std::sync::atomic::{fence, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// The size of the ring buffer of the up channel.
+const BUFFER_SIZE: usize = 4096;
+/// Room kept for the marker of a truncated record (e.g. `…[truncated 5000 bytes]`) and the line break.
+const MARKER_LEN: usize = 48;
+/// Skip records that don't fit into the buffer, instead of waiting for the probe to read it.
+const MODE_NO_BLOCK_SKIP: u32 = 0;
+
+/// A channel as laid out by SEGGER RTT.
+#[repr(C)]
+struct Buffer {
+    name: *const u8,
+    buffer: *mut u8,
+    size: u32,
+    /// Written by this process only.
+    write: u32,
+    /// Written by the probe only.
+    read: u32,
+    flags: u32,
+}
+
+/// A SEGGER RTT control block with one up channel and no down channels. Probes find it in memory by its ID.
+#[repr(C)]
+struct ControlBlock {
+    id: [u8; 16],
+    max_up_buffers: i32,
+    max_down_buffers: i32,
+    up: Buffer,
+}
+
+/// The control block of this process, which never moves or goes away.
+struct Channel {
+    block: *mut ControlBlock,
+}
+// SAFETY: the block is only written through the mutex around the channel (and by the probe, from outside the process).
+unsafe impl Send for Channel {}
+impl Channel {
+    fn get() -> &'static Mutex<Channel> {
+        static CHANNEL: OnceLock<Mutex<Channel>> = OnceLock::new();
+        CHANNEL.get_or_init(|| {
+            let buffer = Box::leak(vec![0; BUFFER_SIZE].into_boxed_slice());
+            let block = Box::leak(Box::new(ControlBlock {
+                id: [0; 16],
+                max_up_buffers: 1,
+                max_down_buffers: 0,
+                up: Buffer {
+                    name: c"Terminal".as_ptr().cast(),
+                    buffer: buffer.as_mut_ptr(),
+                    size: BUFFER_SIZE as u32,
+                    write: 0,
+                    read: 0,
+                    flags: MODE_NO_BLOCK_SKIP,
+                },
+            }));
+            // the ID is written last, so probes don't find a half initialized block,
+            // and in two parts, so they don't find a copy of it in the binary instead
+            fence(Ordering::SeqCst);
+            for (index, byte) in b"SEGGER".iter().chain(b" RTT").enumerate() {
+                // SAFETY: the index is within the ID.
+                unsafe { ptr::write_volatile(block.id.as_mut_ptr().add(index), *byte) };
+            }
+            Mutex::new(Channel { block })
+        })
+    }
+    /// Append the bytes to the ring buffer, if they fit.
+    ///
+    /// returns: bool, whether the bytes were written
+    fn write(&mut self, bytes: &[u8]) -> bool {
+        // SAFETY: the block and its buffer live forever, and the read offset is only ever read.
+        unsafe {
+            let up = addr_of_mut!((*self.block).up);
+            let write = ptr::read_volatile(addr_of!((*up).write)) as usize;
+            let read = ptr::read_volatile(addr_of!((*up).read)) as usize;
+            // one byte stays free, as an equal read and write offset means the buffer is empty
+            let free = match read > write {
+                true => read - write - 1,
+                false => BUFFER_SIZE - write + read - 1,
+            };
+            if bytes.len() > free {
+                return false;
+            }
+            let buffer = (*up).buffer;
+            let first = bytes.len().min(BUFFER_SIZE - write);
+            ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.add(write), first);
+            ptr::copy_nonoverlapping(bytes.as_ptr().add(first), buffer, bytes.len() - first);
+            // the probe may only see the new offset once the bytes are there
+            fence(Ordering::SeqCst);
+            ptr::write_volatile(addr_of_mut!((*up).write), ((write + bytes.len()) % BUFFER_SIZE) as u32);
+        }
+        true
+    }
+}
+
+/// A [Handler](Handler) writing records to the first up channel (`Terminal`) of [SEGGER RTT](https://www.segger.com/products/debug-probes/j-link/technology/about-real-time-transfer/),
+/// for targets running with `std` that are debugged with a probe instead of having a console, e.g. ESP-IDF chips with [probe-rs](https://probe.rs).
+///
+/// The RTT control block is created in memory with the first handler and shared by all handlers of the process.
+/// Probes usually find it by scanning the RAM, otherwise pass them its [address](RttHandler::control_block_address).
+/// Records are written as text lines. Records longer than the 4 KiB buffer are truncated, so they fit once the probe has read the buffer.
+/// Records that don't fit into the buffer because the probe doesn't read it fast enough (or isn't attached)
+/// are dropped and counted in [stats](crate::stats), so logging never waits for the probe.
+///
+/// This handler needs `std`, for the allocator and the lock around the buffer. Targets without `std` aren't covered.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, RttHandler};
+/// let logger = Logger::new("firmware");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(RttHandler::new());
+/// // truncated to fit into the buffer, instead of never fitting
+/// logger.info(format!("config: {}", "x".repeat(10_000)));
+/// assert_eq!(logging::stats().by_logger["firmware"][&Level::INFO].dropped, 0);
+/// logger.info("Booted");
+/// println!("RTT control block at {:#x}", RttHandler::control_block_address());
+/// ```
+pub struct RttHandler {
+    channel: &'static Mutex<Channel>,
+    id: HandlerId,
+}
+impl RttHandler {
+    /// Create a handler, setting up the RTT control block if there is none yet.
+    ///
+    /// returns: RttHandler
+    pub fn new() -> Self {
+        Self { channel: Channel::get(), id: HandlerId::new() }
+    }
+    /// The address of the RTT control block of this process, for probes that don't scan the memory for it.
+    ///
+    /// returns: usize
+    pub fn control_block_address() -> usize {
+        Channel::get().lock().expect("RTT handler is poisoned").block as usize
+    }
+}
+impl Default for RttHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Handler for RttHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut line = record.formatted.clone().unwrap_or_else(|| DefaultFormatter.format(record));
+        // one byte of the buffer always stays free
+        crate::logger::truncate(&mut line, BUFFER_SIZE - 1 - MARKER_LEN);
+        line.push('\n');
+        if !self.channel.lock().expect("RTT handler is poisoned").write(line.as_bytes()) {
+            self.id.record_dropped(&self.describe(), record);
+        }
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("RttHandler").with_setting("channel", "Terminal").with_setting("buffer", BUFFER_SIZE)
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
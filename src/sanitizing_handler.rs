@@ -0,0 +1,176 @@
+use crate::{FieldValue, Handler, HandlerConfig, HandlerHealth, LogLevel, Record};
+use std::sync::Arc;
+
+/// Removes ANSI escape sequences (e.g. colours) and other control characters from records before passing them on to another handler,
+/// so coloured strings from other code don't end up as garbage in log files or mess up the terminal of someone running `tail -f`.
+///
+/// Newlines and tabs are kept. With [escaping](SanitizingHandler::escaping), control characters are written as escapes like `\x1b` instead of being removed.
+/// With [single_line](SanitizingHandler::single_line), line breaks are escaped as well, for handlers writing one record per line.
+/// The message, the field values and an already [formatted](Record::formatted) record are sanitized.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler, SanitizingHandler};
+/// use std::sync::Arc;
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("build");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(SanitizingHandler::new(Arc::clone(&memory)));
+///
+/// logger.info("\x1b[1;32mCompiled\x1b[0m in 3s\x07");
+/// assert_eq!(memory.records()[0].message, "Compiled in 3s");
+/// ```
+#[derive(Clone)]
+pub struct SanitizingHandler {
+    inner: Arc<dyn Handler>,
+    escape: bool,
+    single_line: bool,
+}
+impl SanitizingHandler {
+    /// Wrap a handler, removing escape sequences and control characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: The handler receiving the sanitized records.
+    ///
+    /// returns: SanitizingHandler
+    pub fn new<T: Handler + 'static>(inner: T) -> Self {
+        Self { inner: Arc::new(inner), escape: false, single_line: false }
+    }
+    /// Escape control characters (including the start of escape sequences) instead of removing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{ConsoleHandler, SanitizingHandler};
+    /// let handler = SanitizingHandler::new(ConsoleHandler::new()).escaping();
+    /// assert_eq!(handler.sanitize("\x1b[31mred\x1b[0m"), "\\x1b[31mred\\x1b[0m");
+    /// ```
+    pub fn escaping(mut self) -> Self {
+        self.escape = true;
+        self
+    }
+    /// Escape line breaks as `\n` and `\r` (and the unicode line separators as `\u2028` and `\u2029`), so every record stays on one line.
+    /// This keeps user controlled input like `"admin\nINFO (auth): login succeeded"` from forging log lines
+    /// and from breaking parsers of line based files. Tabs are still kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{FileHandler, SanitizingHandler};
+    /// let path = std::env::temp_dir().join(format!("logging-single-line-{}.log", std::process::id()));
+    /// let handler = SanitizingHandler::new(FileHandler::new(&path).unwrap()).single_line();
+    /// assert_eq!(handler.sanitize("user admin\r\nINFO (auth): login succeeded"), "user admin\\r\\nINFO (auth): login succeeded");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn single_line(mut self) -> Self {
+        self.single_line = true;
+        self
+    }
+    /// Sanitize a text according to the settings of this handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: The text to sanitize.
+    ///
+    /// returns: String
+    pub fn sanitize(&self, text: &str) -> String {
+        if !text.chars().any(|character| self.is_unsafe(character)) {
+            return text.to_string();
+        }
+        let mut sanitized = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(character) = chars.next() {
+            if !self.is_unsafe(character) {
+                sanitized.push(character);
+            } else if let (true, Some(escaped)) = (self.single_line, line_break_escape(character)) {
+                sanitized.push_str(escaped);
+            } else if self.escape {
+                sanitized.push_str(&format!("\\x{:02x}", character as u32));
+            } else if character == '\x1b' {
+                skip_escape_sequence(&mut chars);
+            }
+        }
+        sanitized
+    }
+    fn is_unsafe(&self, character: char) -> bool {
+        is_unsafe(character) || (self.single_line && line_break_escape(character).is_some())
+    }
+    fn sanitize_value(&self, value: &mut FieldValue) {
+        match value {
+            FieldValue::Str(text) => *text = self.sanitize(text),
+            FieldValue::Map(fields) => fields.iter_mut().for_each(|(_, value)| self.sanitize_value(value)),
+            FieldValue::List(values) => values.iter_mut().for_each(|value| self.sanitize_value(value)),
+            _ => {}
+        }
+    }
+}
+impl Handler for SanitizingHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut record = record.clone();
+        record.message = self.sanitize(&record.message);
+        for (_, value) in &mut record.fields {
+            self.sanitize_value(value);
+        }
+        record.formatted = record.formatted.map(|formatted| self.sanitize(&formatted));
+        self.inner.handle(&record)
+    }
+    fn reopen(&self) {
+        self.inner.reopen()
+    }
+    fn close(&self) {
+        self.inner.close()
+    }
+    fn accepts(&self, record: &Record) -> bool {
+        self.inner.accepts(record)
+    }
+    fn describe(&self) -> HandlerConfig {
+        HandlerConfig::new("SanitizingHandler")
+            .with_setting("escape", self.escape)
+            .with_setting("single_line", self.single_line)
+            .with_inner(self.inner.describe())
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        self.inner.health()
+    }
+}
+
+fn is_unsafe(character: char) -> bool {
+    character.is_control() && character != '\n' && character != '\t'
+}
+/// The escape of a character breaking a line.
+fn line_break_escape(character: char) -> Option<&'static str> {
+    match character {
+        '\n' => Some("\\n"),
+        '\r' => Some("\\r"),
+        '\u{2028}' => Some("\\u2028"),
+        '\u{2029}' => Some("\\u2029"),
+        _ => None,
+    }
+}
+/// Skip the rest of an escape sequence after the escape character:
+/// CSI sequences like `[1;32m`, OSC sequences like `]0;title` up to BEL or `ESC \` and two character sequences.
+fn skip_escape_sequence(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    match chars.next() {
+        Some('[') => {
+            // parameters and intermediates up to the final byte
+            for character in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&character) {
+                    break;
+                }
+            }
+        }
+        Some(']') => {
+            while let Some(character) = chars.next() {
+                if character == '\x07' || (character == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                    break;
+                }
+            }
+        }
+        _ => {}
+    }
+}
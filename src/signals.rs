@@ -0,0 +1,73 @@
+//! Control logging through Unix signals.
+//!
+//! * `SIGHUP` reopens all handlers (see [reopen_handlers](crate::reopen_handlers)), which is what logrotate expects.
+//! * `SIGUSR1` bumps the root level up to the next registered level, making logging less verbose.
+//! * `SIGUSR2` bumps the root level down to the previous registered level, making logging more verbose.
+
+use crate::{Level, LogLevel, Logger};
+use signal_hook::consts::{SIGHUP, SIGUSR1, SIGUSR2};
+use signal_hook::iterator::{Handle, Signals};
+use std::io;
+use std::thread::{self, JoinHandle};
+
+/// Listens for the logging signals in a background thread.
+/// The signals are no longer handled once this is dropped.
+pub struct SignalListener {
+    handle: Handle,
+    thread: Option<JoinHandle<()>>,
+}
+impl Drop for SignalListener {
+    fn drop(&mut self) {
+        self.handle.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Start handling `SIGHUP`, `SIGUSR1` and `SIGUSR2`.
+///
+/// returns: Result<SignalListener, Error>
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// logging::set_level(Level::INFO);
+/// let listener = logging::signals::listen().unwrap();
+/// // kill -USR1 <pid>
+/// # signal_hook::low_level::raise(signal_hook::consts::SIGUSR1).unwrap();
+/// # std::thread::sleep(std::time::Duration::from_millis(100));
+/// assert_eq!(Logger::new("").level(), Level::SUCCESS);
+/// # drop(listener);
+/// ```
+pub fn listen() -> io::Result<SignalListener> {
+    let mut signals = Signals::new([SIGHUP, SIGUSR1, SIGUSR2])?;
+    let handle = signals.handle();
+    let thread = thread::Builder::new()
+        .name("logging-signals".to_string())
+        .spawn(move || {
+            for signal in signals.forever() {
+                match signal {
+                    SIGHUP => crate::reopen_handlers(),
+                    SIGUSR1 => bump_level(true),
+                    SIGUSR2 => bump_level(false),
+                    _ => {}
+                }
+            }
+        })?;
+    Ok(SignalListener { handle, thread: Some(thread) })
+}
+fn bump_level(up: bool) {
+    let root = Logger::new("");
+    let current = root.level();
+    let levels: Vec<LogLevel> = Level::get_levels().into_iter().map(|(level, _)| level).collect();
+    let next = if up {
+        levels.into_iter().find(|level| *level > current)
+    } else {
+        levels.into_iter().rev().find(|level| *level < current)
+    };
+    if let Some(level) = next {
+        crate::set_level(level);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{FieldValue, Level, Logger};
+
+/// The environment variables logged by default.
+const DEFAULT_ENV: [&str; 6] = ["RUST_LOG", "RUST_BACKTRACE", "LANG", "TZ", "HOSTNAME", "USER"];
+/// The words marking environment variables and arguments whose values are not logged by default.
+const DEFAULT_REDACTED: [&str; 7] = ["secret", "token", "password", "passwd", "key", "credential", "auth"];
+/// The short flags whose values are not logged by default.
+const DEFAULT_REDACTED_FLAGS: [&str; 1] = ["-p"];
+
+/// What [log_startup_info](log_startup_info) logs, for programs that need more environment variables or their own version in the preamble.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler, StartupInfo};
+/// use std::sync::Arc;
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("app");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(Arc::clone(&memory));
+///
+/// std::env::set_var("APP_MODE", "batch");
+/// std::env::set_var("APP_DB_PASSWORD", "hunter2");
+/// StartupInfo::new()
+///     .with_version(env!("CARGO_PKG_VERSION"))
+///     .with_env("APP_*")
+///     .log(&logger);
+///
+/// let env = memory.records()[0].field("env").unwrap().to_string();
+/// assert!(env.contains("APP_MODE=batch"));
+/// assert!(env.contains("APP_DB_PASSWORD=***"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct StartupInfo {
+    version: Option<String>,
+    env: Vec<String>,
+    redacted: Vec<String>,
+    redacted_flags: Vec<String>,
+}
+impl Default for StartupInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl StartupInfo {
+    /// Log the default environment variables (`RUST_LOG`, `RUST_BACKTRACE`, `LANG`, `TZ`, `HOSTNAME` and `USER`),
+    /// redacting values of variables and arguments whose name contains `secret`, `token`, `password`, `passwd`, `key`, `credential` or `auth`,
+    /// and of the `-p` flag.
+    pub fn new() -> Self {
+        Self {
+            version: None,
+            env: DEFAULT_ENV.iter().map(|name| name.to_string()).collect(),
+            redacted: DEFAULT_REDACTED.iter().map(|word| word.to_string()).collect(),
+            redacted_flags: DEFAULT_REDACTED_FLAGS.iter().map(|flag| flag.to_string()).collect(),
+        }
+    }
+    /// Log the version of the program as well, usually `env!("CARGO_PKG_VERSION")`.
+    pub fn with_version(mut self, version: impl ToString) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+    /// Log another environment variable, if it is set. A name ending with `*` logs all variables starting with the rest, e.g. `APP_*`.
+    pub fn with_env(mut self, name: impl ToString) -> Self {
+        self.env.push(name.to_string());
+        self
+    }
+    /// Redact the values of environment variables and flags (`--name=value` and `--name value`) whose name contains this word (case-insensitive).
+    pub fn with_redacted(mut self, word: impl ToString) -> Self {
+        self.redacted.push(word.to_string().to_lowercase());
+        self
+    }
+    /// Redact the value of a flag, given in full (e.g. `-k`), for short flags that don't contain any of the redacted words.
+    pub fn with_redacted_flag(mut self, flag: impl ToString) -> Self {
+        self.redacted_flags.push(flag.to_string());
+        self
+    }
+    fn is_redacted(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.redacted.iter().any(|word| name.contains(word.as_str()))
+    }
+    fn is_redacted_flag(&self, flag: &str) -> bool {
+        flag.starts_with('-') && (self.is_redacted(flag) || self.redacted_flags.iter().any(|redacted| redacted == flag))
+    }
+    /// Redact arguments the way [log](StartupInfo::log) does: the value of a redacted flag is replaced with `***`,
+    /// both in the form `--password=value` and as the argument following it (`--password value`), unless that argument starts with `-`.
+    ///
+    /// # Arguments
+    ///
+    /// * `args`: The arguments, including the program.
+    ///
+    /// returns: Vec<String>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::StartupInfo;
+    /// let args = ["server", "--token", "abc", "-p", "hunter2", "--db-password=hunter2", "--verbose", "input.txt"];
+    /// assert_eq!(
+    ///     StartupInfo::new().redact_args(args),
+    ///     ["server", "--token", "***", "-p", "***", "--db-password=***", "--verbose", "input.txt"],
+    /// );
+    /// ```
+    pub fn redact_args<T: Into<String>>(&self, args: impl IntoIterator<Item = T>) -> Vec<String> {
+        let mut redact_next = false;
+        args.into_iter()
+            .map(|arg| {
+                let arg: String = arg.into();
+                if std::mem::take(&mut redact_next) && !arg.starts_with('-') {
+                    return "***".to_string();
+                }
+                match arg.split_once('=') {
+                    Some((name, _)) if self.is_redacted_flag(name) => format!("{}=***", name),
+                    Some(_) => arg,
+                    None => {
+                        redact_next = self.is_redacted_flag(&arg);
+                        arg
+                    }
+                }
+            })
+            .collect()
+    }
+    /// Log the startup information at [Level::INFO](Level::INFO).
+    ///
+    /// # Arguments
+    ///
+    /// * `logger`: The logger to log with.
+    ///
+    /// returns: ()
+    pub fn log(&self, logger: &Logger) {
+        let args: Vec<FieldValue> = self.redact_args(std::env::args_os().map(|arg| arg.to_string_lossy().into_owned()))
+            .into_iter()
+            .map(FieldValue::from)
+            .collect();
+        let mut env: Vec<(String, FieldValue)> = std::env::vars_os()
+            .map(|(name, value)| (name.to_string_lossy().into_owned(), value.to_string_lossy().into_owned()))
+            .filter(|(name, _)| self.env.iter().any(|selected| match selected.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == selected,
+            }))
+            .map(|(name, value)| {
+                let value = if self.is_redacted(&name) { "***".to_string() } else { value };
+                (name, value.into())
+            })
+            .collect();
+        env.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut fields = vec![
+            ("args".to_string(), args.into()),
+            ("env".to_string(), env.into()),
+        ];
+        if let Ok(directory) = std::env::current_dir() {
+            fields.push(("cwd".to_string(), directory.display().to_string().into()));
+        }
+        if let Some(version) = &self.version {
+            fields.push(("version".to_string(), version.as_str().into()));
+        }
+        fields.push(("logging_version".to_string(), env!("CARGO_PKG_VERSION").into()));
+        fields.push(("rustc_version".to_string(), env!("LOGGING_RUSTC_VERSION").into()));
+        logger.log_fields("process started", Level::INFO, fields);
+    }
+}
+
+/// Log what this process was run with at [Level::INFO](Level::INFO): the arguments, selected environment variables, the working directory
+/// and the versions of this crate and the compiler, as the fields `args`, `env`, `cwd`, `logging_version` and `rustc_version`.
+/// Secrets in the environment and arguments are redacted. Use [StartupInfo](StartupInfo) to log more.
+///
+/// # Arguments
+///
+/// * `logger`: The logger to log with.
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler};
+/// use std::sync::Arc;
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let logger = Logger::new("app");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(Arc::clone(&memory));
+///
+/// logging::log_startup_info(&logger);
+/// let record = &memory.records()[0];
+/// assert_eq!(record.message, "process started");
+/// assert!(record.field("cwd").is_some());
+/// assert!(record.field("rustc_version").unwrap().to_string().starts_with("rustc"));
+/// ```
+pub fn log_startup_info(logger: &Logger) {
+    StartupInfo::new().log(logger)
+}
@@ -0,0 +1,446 @@
+use crate::{HandlerConfig, LogLevel, Record};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Default)]
+struct Counter {
+    emitted: AtomicU64,
+    dropped: AtomicU64,
+}
+type Counters = RwLock<HashMap<String, HashMap<LogLevel, Arc<Counter>>>>;
+static COUNTERS: OnceLock<Counters> = OnceLock::new();
+static HANDLERS: OnceLock<Mutex<HashMap<HandlerKey, HandlerEntry>>> = OnceLock::new();
+/// How long a handler counts as degraded after its last error.
+const DEGRADED_FOR: Duration = Duration::from_secs(60);
+thread_local! {
+    /// Handler errors recorded on this thread, so wrappers can tell whether the handler they called failed.
+    static THREAD_HANDLER_ERRORS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Identifies a built-in handler in [handler_health](handler_health), so several handlers of the same type are told apart.
+/// Handlers get one when they are created and share it with their clones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct HandlerId(u64);
+impl HandlerId {
+    pub(crate) fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+    /// Like [record_handler_failure](record_handler_failure), for this handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler`: The description of the handler, see [Handler::describe](crate::Handler::describe).
+    /// * `error`: What went wrong.
+    ///
+    /// returns: ()
+    pub(crate) fn record_failure(self, handler: &HandlerConfig, error: impl Display) {
+        THREAD_HANDLER_ERRORS.set(THREAD_HANDLER_ERRORS.get() + 1);
+        let error = error.to_string();
+        update_handler(HandlerKey::Instance(self), handler, |entry| {
+            entry.errors += 1;
+            entry.last_error = Some((error, SystemTime::now()));
+        });
+    }
+    /// Like [record_dropped](record_dropped), also counting the record for this handler.
+    pub(crate) fn record_dropped(self, handler: &HandlerConfig, record: &Record) {
+        record_dropped(record);
+        update_handler(HandlerKey::Instance(self), handler, |entry| entry.dropped += 1);
+    }
+    /// Mark this handler as disabled (or enabled again), e.g. by a [CircuitBreakerHandler](crate::CircuitBreakerHandler).
+    pub(crate) fn set_disabled(self, handler: &HandlerConfig, disabled: bool) {
+        update_handler(HandlerKey::Instance(self), handler, |entry| entry.disabled = disabled);
+    }
+    /// The health of this handler, see [Handler::health](crate::Handler::health).
+    ///
+    /// # Arguments
+    ///
+    /// * `describe`: Describes the handler, only called if it has never had problems.
+    ///
+    /// returns: HandlerHealth
+    pub(crate) fn health(self, describe: impl FnOnce() -> HandlerConfig) -> HandlerHealth {
+        let handlers = HANDLERS.get_or_init(Default::default).lock().expect("Stats are poisoned");
+        if let Some(entry) = handlers.get(&HandlerKey::Instance(self)) {
+            return entry.health(Some(self));
+        }
+        drop(handlers);
+        let handler = describe();
+        HandlerEntry { kind: handler.kind, settings: handler.settings, ..Default::default() }.health(Some(self))
+    }
+}
+
+/// Mark the handler a [health](crate::Handler::health) report belongs to as disabled (or enabled again).
+/// Reports without an [id](HandlerHealth::id) are shared by every handler with that name and are left alone.
+pub(crate) fn set_handler_disabled(health: &HandlerHealth, disabled: bool) {
+    let Some(id) = health.id else { return };
+    let mut handlers = HANDLERS.get_or_init(Default::default).lock().expect("Stats are poisoned");
+    let entry = handlers.entry(HandlerKey::Instance(HandlerId(id))).or_insert_with(|| HandlerEntry {
+        kind: health.handler.clone(),
+        settings: health.settings.clone(),
+        ..Default::default()
+    });
+    entry.disabled = disabled;
+}
+
+/// Whom a [HandlerEntry] belongs to.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum HandlerKey {
+    /// Every handler reporting with this name, see [record_handler_error](record_handler_error).
+    Name(String),
+    Instance(HandlerId),
+}
+
+#[derive(Default)]
+struct HandlerEntry {
+    kind: String,
+    settings: BTreeMap<String, String>,
+    errors: u64,
+    last_error: Option<(String, SystemTime)>,
+    dropped: u64,
+    disabled: bool,
+}
+impl HandlerEntry {
+    fn health(&self, id: Option<HandlerId>) -> HandlerHealth {
+        let last_error_time = self.last_error.as_ref().map(|(_, time)| *time);
+        let recent = last_error_time.is_some_and(|time| time.elapsed().is_ok_and(|elapsed| elapsed < DEGRADED_FOR));
+        let state = if self.disabled {
+            HealthState::Disabled
+        } else if recent {
+            HealthState::Degraded
+        } else {
+            HealthState::Ok
+        };
+        HandlerHealth {
+            handler: self.kind.clone(),
+            id: id.map(|id| id.0),
+            settings: self.settings.clone(),
+            state,
+            errors: self.errors,
+            last_error: self.last_error.as_ref().map(|(error, _)| error.clone()),
+            last_error_time,
+            dropped: self.dropped,
+        }
+    }
+}
+fn update_handler(key: HandlerKey, handler: &HandlerConfig, update: impl FnOnce(&mut HandlerEntry)) {
+    let mut handlers = HANDLERS.get_or_init(Default::default).lock().expect("Stats are poisoned");
+    let entry = handlers.entry(key).or_default();
+    entry.kind.clone_from(&handler.kind);
+    entry.settings.clone_from(&handler.settings);
+    update(entry)
+}
+fn update_named_handler(handler: &str, update: impl FnOnce(&mut HandlerEntry)) {
+    let mut handlers = HANDLERS.get_or_init(Default::default).lock().expect("Stats are poisoned");
+    let entry = handlers.entry(HandlerKey::Name(handler.to_string())).or_default();
+    if entry.kind.is_empty() {
+        entry.kind = handler.to_string();
+    }
+    update(entry)
+}
+
+fn counter(logger: &str, level: LogLevel) -> Arc<Counter> {
+    let counters = COUNTERS.get_or_init(Default::default);
+    if let Some(counter) = counters.read().expect("Stats are poisoned").get(logger).and_then(|levels| levels.get(&level)) {
+        return Arc::clone(counter);
+    }
+    let mut lock = counters.write().expect("Stats are poisoned");
+    Arc::clone(lock.entry(logger.to_string()).or_default().entry(level).or_default())
+}
+pub(crate) fn record_emitted(logger: &str, level: LogLevel) {
+    counter(logger, level).emitted.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a record as dropped, e.g. because a queue was full or a rate limit was hit.
+/// Handlers that drop records should call this, so the drops show up in [stats](stats).
+///
+/// # Arguments
+///
+/// * `record`: The record that was dropped.
+///
+/// returns: ()
+pub fn record_dropped(record: &Record) {
+    counter(&record.logger, record.level).dropped.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a failure of a handler, e.g. a failed write to a file or socket.
+///
+/// # Arguments
+///
+/// * `handler`: The name of the handler, e.g. `FileHandler`.
+///
+/// returns: ()
+pub fn record_handler_error(handler: &str) {
+    THREAD_HANDLER_ERRORS.set(THREAD_HANDLER_ERRORS.get() + 1);
+    update_named_handler(handler, |entry| entry.errors += 1);
+}
+
+/// Like [record_handler_error](record_handler_error), but also keeps the error, so it shows up in [handler_health](handler_health).
+///
+/// # Arguments
+///
+/// * `handler`: The name of the handler, e.g. `FileHandler`.
+/// * `error`: What went wrong.
+///
+/// returns: ()
+pub fn record_handler_failure(handler: &str, error: impl Display) {
+    record_handler_error(handler);
+    let error = error.to_string();
+    update_named_handler(handler, |entry| entry.last_error = Some((error, SystemTime::now())));
+}
+
+/// Run `f` and return whether a handler failed while it ran on this thread.
+pub(crate) fn handler_failed(f: impl FnOnce()) -> bool {
+    let before = THREAD_HANDLER_ERRORS.get();
+    f();
+    THREAD_HANDLER_ERRORS.get() != before
+}
+
+/// Whether a handler is working, see [HandlerHealth](HandlerHealth).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthState {
+    /// The handler hasn't failed in the last minute.
+    Ok,
+    /// The handler has failed in the last minute, so some records may be missing.
+    Degraded,
+    /// Records aren't passed on to the handler at the moment, e.g. because a [CircuitBreakerHandler](crate::CircuitBreakerHandler) stopped it after too many failures.
+    Disabled,
+}
+
+/// The health of a handler, returned by [handler_health](handler_health).
+#[derive(Clone, Debug)]
+pub struct HandlerHealth {
+    /// The kind of handler, e.g. `FileHandler`, or the name it reports errors with.
+    pub handler: String,
+    /// Tells built-in handlers of the same kind apart. `None` for handlers reporting by name with [record_handler_failure](record_handler_failure).
+    pub id: Option<u64>,
+    /// The settings of the handler when it last had a problem, e.g. its path, see [Handler::describe](crate::Handler::describe).
+    pub settings: BTreeMap<String, String>,
+    /// Whether the handler is working.
+    pub state: HealthState,
+    /// The number of failures.
+    pub errors: u64,
+    /// The last error reported with [record_handler_failure](record_handler_failure).
+    pub last_error: Option<String>,
+    /// When the last error happened.
+    pub last_error_time: Option<SystemTime>,
+    /// The number of records the handler dropped.
+    pub dropped: u64,
+}
+
+/// Report the health of all handlers that have had problems, e.g. to include "logging to the collector is broken" in the health check of an application.
+/// Every built-in handler has an entry of its own, other handlers share an entry per name they report errors with.
+/// Handlers that never failed or dropped anything aren't listed.
+///
+/// returns: Vec<HandlerHealth> Sorted by name.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{handler_health, record_handler_failure, HealthState};
+///
+/// record_handler_failure("MailHandler", "connection refused");
+/// let health = handler_health();
+/// let mail = health.iter().find(|health| health.handler == "MailHandler").unwrap();
+/// assert_eq!(mail.state, HealthState::Degraded);
+/// assert_eq!(mail.last_error.as_deref(), Some("connection refused"));
+///
+/// // built-in handlers are listed one by one, with their settings
+/// # #[cfg(target_os = "linux")] {
+/// use logging::{FileHandler, Handler, Level};
+/// let full = FileHandler::new("/dev/full").unwrap();
+/// full.log(Level::INFO, "Hello World".to_string(), "foo".to_string());
+/// let health = handler_health();
+/// let file = health.iter().find(|health| health.settings.get("path").is_some_and(|path| path == "/dev/full")).unwrap();
+/// assert_eq!(file.handler, "FileHandler");
+/// assert_eq!(file.state, HealthState::Degraded);
+/// assert!(file.id.is_some());
+/// # }
+/// ```
+pub fn handler_health() -> Vec<HandlerHealth> {
+    let Some(handlers) = HANDLERS.get() else { return Vec::new() };
+    let handlers = handlers.lock().expect("Stats are poisoned");
+    let mut health: Vec<HandlerHealth> = handlers.iter()
+        .map(|(key, entry)| match key {
+            HandlerKey::Name(_) => entry.health(None),
+            HandlerKey::Instance(id) => entry.health(Some(*id)),
+        })
+        .collect();
+    health.sort_by(|a, b| (&a.handler, a.id).cmp(&(&b.handler, b.id)));
+    health
+}
+
+/// How many records were emitted and dropped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// Records that passed the level of their logger and were passed on to the handlers.
+    pub emitted: u64,
+    /// Records that were dropped before reaching their destination (see [record_dropped](record_dropped)).
+    pub dropped: u64,
+}
+impl std::ops::AddAssign for Counts {
+    fn add_assign(&mut self, other: Self) {
+        self.emitted += other.emitted;
+        self.dropped += other.dropped;
+    }
+}
+
+/// A snapshot of the logging counters, returned by [stats](stats).
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    /// The counts per level over all loggers.
+    pub by_level: BTreeMap<LogLevel, Counts>,
+    /// The counts per logger and level.
+    pub by_logger: BTreeMap<String, BTreeMap<LogLevel, Counts>>,
+    /// The number of failures per handler (see [record_handler_error](record_handler_error)).
+    pub handler_errors: BTreeMap<String, u64>,
+}
+impl Stats {
+    /// The counts of all levels at or above the given one, e.g. to get the number of errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `level`: The minimum level to count.
+    ///
+    /// returns: Counts
+    pub fn at_least(&self, level: LogLevel) -> Counts {
+        let mut counts = Counts::default();
+        for level_counts in self.by_level.range(level..).map(|(_, counts)| *counts) {
+            counts += level_counts;
+        }
+        counts
+    }
+}
+
+/// Take a snapshot of how many records were emitted and dropped, per level and per logger.
+///
+/// returns: Stats
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::INFO);
+/// logger.error("Something failed");
+/// logger.info("Hello World");
+/// // not emitted, as it is below the level of the logger
+/// logger.debug("Hello World");
+///
+/// let stats = logging::stats();
+/// assert_eq!(stats.by_level[&Level::INFO].emitted, 1);
+/// assert_eq!(stats.at_least(Level::ERROR).emitted, 1);
+/// assert_eq!(stats.by_logger["foo"][&Level::ERROR].emitted, 1);
+/// assert!(!stats.by_level.contains_key(&Level::DEBUG));
+/// ```
+pub fn stats() -> Stats {
+    let mut stats = Stats::default();
+    if let Some(handlers) = HANDLERS.get() {
+        for entry in handlers.lock().expect("Stats are poisoned").values().filter(|entry| entry.errors > 0) {
+            *stats.handler_errors.entry(entry.kind.clone()).or_default() += entry.errors;
+        }
+    }
+    let Some(counters) = COUNTERS.get() else { return stats };
+    for (logger, levels) in counters.read().expect("Stats are poisoned").iter() {
+        for (level, counter) in levels {
+            let counts = Counts {
+                emitted: counter.emitted.load(Ordering::Relaxed),
+                dropped: counter.dropped.load(Ordering::Relaxed),
+            };
+            *stats.by_level.entry(*level).or_default() += counts;
+            stats.by_logger.entry(logger.clone()).or_default().insert(*level, counts);
+        }
+    }
+    stats
+}
+
+struct DropReport {
+    interval: Duration,
+    next: Instant,
+    /// The drop counts at the last report, per logger and level.
+    reported: HashMap<(String, LogLevel), u64>,
+}
+static DROP_REPORTING: AtomicBool = AtomicBool::new(false);
+static DROP_REPORT: Mutex<Option<DropReport>> = Mutex::new(None);
+
+/// Periodically log a summary of the records dropped since the last summary, so losing records doesn't go unnoticed.
+/// The summary is logged at [WARN](crate::Level::WARN) by the logger `logging` when a record is logged after the interval has passed,
+/// and only if records were dropped. It lists the number of dropped records per logger and level.
+///
+/// # Arguments
+///
+/// * `interval`: How often to report at most. `None` turns reporting off (the default).
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, MemoryHandler, Record};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let memory = Arc::new(MemoryHandler::new(10));
+/// let reports = Logger::new("logging");
+/// reports.set_level(Level::ALL);
+/// reports.add_handler(Arc::clone(&memory));
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logging::set_drop_report_interval(Some(Duration::ZERO));
+///
+/// logging::record_dropped(&Record::new(Level::INFO, "lost".to_string(), "foo".to_string()));
+/// logger.warn("Something is off");
+/// let report = memory.query().by_logger_prefix("logging").iter().next().unwrap();
+/// assert_eq!(report.message, "Dropped 1 record since the last report: foo INFO 1");
+/// assert_eq!(report.field("dropped"), Some(&logging::FieldValue::Int(1)));
+/// # logging::set_drop_report_interval(None);
+/// ```
+pub fn set_drop_report_interval(interval: Option<Duration>) {
+    let mut report = DROP_REPORT.lock().expect("Drop report is poisoned");
+    *report = interval.map(|interval| DropReport {
+        interval,
+        next: Instant::now() + interval,
+        reported: dropped_counts(),
+    });
+    DROP_REPORTING.store(report.is_some(), Ordering::Relaxed);
+}
+fn dropped_counts() -> HashMap<(String, LogLevel), u64> {
+    let Some(counters) = COUNTERS.get() else { return HashMap::new() };
+    let mut counts = HashMap::new();
+    for (logger, levels) in counters.read().expect("Stats are poisoned").iter() {
+        for (level, counter) in levels {
+            counts.insert((logger.clone(), *level), counter.dropped.load(Ordering::Relaxed));
+        }
+    }
+    counts
+}
+/// Log a summary of dropped records if reporting is on and the interval has passed.
+pub(crate) fn report_drops() {
+    if !DROP_REPORTING.load(Ordering::Relaxed) {
+        return;
+    }
+    // whoever holds the lock is already reporting
+    let Ok(mut lock) = DROP_REPORT.try_lock() else { return };
+    let Some(report) = lock.as_mut().filter(|report| Instant::now() >= report.next) else { return };
+    report.next = Instant::now() + report.interval;
+    let counts = dropped_counts();
+    let mut dropped: Vec<(&(String, LogLevel), u64)> = counts.iter()
+        .map(|(key, count)| (key, count - report.reported.get(key).copied().unwrap_or(0)))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    dropped.sort();
+    let total: u64 = dropped.iter().map(|(_, count)| count).sum();
+    let summary = dropped.iter()
+        .map(|((logger, level), count)| format!("{} {} {}", logger, crate::Level::name(*level), count))
+        .collect::<Vec<String>>()
+        .join(", ");
+    report.reported = counts.clone();
+    drop(lock);
+    if total > 0 {
+        let message = format!("Dropped {} record{} since the last report: {}", total, if total == 1 { "" } else { "s" }, summary);
+        crate::Logger::new("logging").log_fields(message, crate::Level::WARN, vec![("dropped".to_string(), total.into())]);
+    }
+}
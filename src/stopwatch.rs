@@ -0,0 +1,57 @@
+use crate::{LogLevel, Logger};
+use std::time::{Duration, Instant};
+
+/// Measures how long something takes and logs the duration when dropped.
+/// The record has the label as `span` field and the duration in microseconds as `duration_us` field,
+/// e.g. for the [ChromeTraceHandler](crate::ChromeTraceHandler).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, Stopwatch};
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// {
+///     let _stopwatch = Stopwatch::new(&logger, Level::DEBUG, "load_config");
+///     // do the work
+/// } // logs "load_config took ..."
+/// ```
+pub struct Stopwatch {
+    logger: Logger,
+    level: LogLevel,
+    label: String,
+    start: Instant,
+}
+impl Stopwatch {
+    /// Start measuring.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger`: The logger to log the duration with.
+    /// * `level`: The level to log the duration at.
+    /// * `label`: What is being measured. Used in the logged message.
+    ///
+    /// returns: Stopwatch
+    pub fn new(logger: &Logger, level: LogLevel, label: impl ToString) -> Self {
+        Self {
+            logger: logger.clone(),
+            level,
+            label: label.to_string(),
+            start: Instant::now(),
+        }
+    }
+    /// The time passed since the stopwatch was started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+impl Drop for Stopwatch {
+    fn drop(&mut self) {
+        let elapsed = self.elapsed();
+        self.logger.log_fields(
+            format!("{} took {:?}", self.label, elapsed),
+            self.level,
+            vec![("span".to_string(), self.label.as_str().into()), ("duration_us".to_string(), elapsed.as_micros().into())],
+        )
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{Level, LogLevel, Record};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// Every stream currently subscribed.
+static SUBSCRIBERS: Mutex<Vec<Arc<Channel>>> = Mutex::new(Vec::new());
+/// The number of streams in [SUBSCRIBERS], so logging doesn't need the lock while nobody is subscribed.
+static SUBSCRIBED: AtomicUsize = AtomicUsize::new(0);
+
+struct State {
+    records: VecDeque<Record>,
+    capacity: usize,
+    level: LogLevel,
+    dropped: u64,
+    waker: Option<Waker>,
+}
+
+struct Channel {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+/// A live feed of the records logged by all loggers, as returned by [subscribe](subscribe). Unsubscribes when dropped.
+///
+/// Records are buffered until they are taken. When the buffer is full, the oldest record is dropped for every new one,
+/// so a slow consumer (e.g. a TUI redrawing once a second) always sees the most recent records and never slows down logging.
+///
+/// As an [Iterator](Iterator), the stream blocks until the next record arrives and never ends. In async code, use [recv](RecordStream::recv).
+pub struct RecordStream {
+    channel: Arc<Channel>,
+}
+impl RecordStream {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.channel.state.lock().expect("Record stream is poisoned")
+    }
+    /// Set how many records are buffered before the oldest ones are dropped. Defaults to 1000.
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        let mut state = self.state();
+        state.capacity = capacity.max(1);
+        while state.records.len() > state.capacity {
+            state.records.pop_front();
+            state.dropped += 1;
+        }
+        drop(state);
+        self
+    }
+    /// Only receive records at or above this level.
+    pub fn with_level(self, level: LogLevel) -> Self {
+        self.state().level = level;
+        self
+    }
+    /// The next record if one is buffered, without waiting.
+    ///
+    /// returns: Option<Record>
+    pub fn try_next(&self) -> Option<Record> {
+        self.state().records.pop_front()
+    }
+    /// Wait up to `timeout` for the next record.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout`: How long to wait at most.
+    ///
+    /// returns: Option<Record> `None` if no record arrived in time.
+    pub fn next_timeout(&self, timeout: Duration) -> Option<Record> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state();
+        loop {
+            if let Some(record) = state.records.pop_front() {
+                return Some(record);
+            }
+            let remaining = deadline.checked_duration_since(Instant::now()).filter(|remaining| !remaining.is_zero())?;
+            state = self.channel.ready.wait_timeout(state, remaining).expect("Record stream is poisoned").0;
+        }
+    }
+    /// Wait for the next record without blocking the thread, for async code. Works with any async runtime.
+    ///
+    /// returns: Record
+    pub async fn recv(&self) -> Record {
+        std::future::poll_fn(|context| {
+            let mut state = self.state();
+            match state.records.pop_front() {
+                Some(record) => Poll::Ready(record),
+                None => {
+                    state.waker = Some(context.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }).await
+    }
+    /// How many records have been dropped so far because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.state().dropped
+    }
+}
+impl Iterator for RecordStream {
+    type Item = Record;
+    fn next(&mut self) -> Option<Record> {
+        let mut state = self.state();
+        loop {
+            if let Some(record) = state.records.pop_front() {
+                return Some(record);
+            }
+            state = self.channel.ready.wait(state).expect("Record stream is poisoned");
+        }
+    }
+}
+impl Drop for RecordStream {
+    fn drop(&mut self) {
+        let mut subscribers = SUBSCRIBERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        subscribers.retain(|channel| !Arc::ptr_eq(channel, &self.channel));
+        SUBSCRIBED.store(subscribers.len(), Ordering::Relaxed);
+    }
+}
+
+/// Receive a copy of every record logged from now on by any logger (at or above the logger's level),
+/// e.g. to show live logs in a TUI, a debug overlay or a web dashboard without writing a [Handler](crate::Handler).
+///
+/// returns: RecordStream
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger};
+/// use std::time::Duration;
+/// let stream = logging::subscribe().with_level(Level::WARN);
+/// let logger = Logger::new("ui");
+/// logger.set_level(Level::ALL);
+/// logger.info("redrawing");
+/// logger.warn("frame took 40ms");
+///
+/// let record = stream.next_timeout(Duration::from_secs(1)).unwrap();
+/// assert_eq!(record.message, "frame took 40ms");
+/// assert!(stream.try_next().is_none());
+/// ```
+pub fn subscribe() -> RecordStream {
+    let channel = Arc::new(Channel {
+        state: Mutex::new(State { records: VecDeque::new(), capacity: 1000, level: Level::MIN, dropped: 0, waker: None }),
+        ready: Condvar::new(),
+    });
+    let mut subscribers = SUBSCRIBERS.lock().expect("Subscribers are poisoned");
+    subscribers.push(Arc::clone(&channel));
+    SUBSCRIBED.store(subscribers.len(), Ordering::Relaxed);
+    RecordStream { channel }
+}
+
+/// Pass a logged record on to all subscribed streams.
+pub(crate) fn publish(record: &Record) {
+    if SUBSCRIBED.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    let subscribers = SUBSCRIBERS.lock().expect("Subscribers are poisoned").clone();
+    for channel in subscribers {
+        let mut state = channel.state.lock().expect("Record stream is poisoned");
+        if record.level < state.level {
+            continue;
+        }
+        if state.records.len() >= state.capacity {
+            state.records.pop_front();
+            state.dropped += 1;
+        }
+        state.records.push_back(record.clone());
+        let waker = state.waker.take();
+        drop(state);
+        channel.ready.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::stats::HandlerId;
+use crate::{FileHandler, Handler, HandlerHealth, LogLevel, Record};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+/// Writes the records of every top-level logger and its sub-loggers into a file of its own, e.g. `net.log` for `net` and `net::http`.
+/// Files are created as new top-level loggers appear, so a large application gets organized output without configuring every subtree.
+/// Records of the root logger go to `root.log`.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, SubtreeFilesHandler};
+/// let dir = std::env::temp_dir().join(format!("logging-subtrees-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// logging::set_level(Level::ALL);
+/// logging::add_handler(SubtreeFilesHandler::new(&dir));
+/// Logger::new("net::http").info("GET /");
+/// Logger::new("db").info("connected");
+///
+/// assert!(std::fs::read_to_string(dir.join("net.log")).unwrap().contains("GET /"));
+/// assert!(std::fs::read_to_string(dir.join("db.log")).unwrap().contains("connected"));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct SubtreeFilesHandler {
+    dir: PathBuf,
+    files: Arc<RwLock<HashMap<String, Arc<FileHandler>>>>,
+    id: HandlerId,
+}
+impl SubtreeFilesHandler {
+    /// Create a handler writing into a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`: The directory to create the files in. It has to exist.
+    ///
+    /// returns: SubtreeFilesHandler
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), files: Default::default(), id: HandlerId::new() }
+    }
+    fn file(&self, subtree: &str) -> Option<Arc<FileHandler>> {
+        if let Some(file) = self.files.read().expect("Subtree files handler is poisoned").get(subtree) {
+            return Some(Arc::clone(file));
+        }
+        let mut files = self.files.write().expect("Subtree files handler is poisoned");
+        if let Some(file) = files.get(subtree) {
+            return Some(Arc::clone(file));
+        }
+        // logger names may contain anything, but the file name shouldn't
+        let name: String = subtree.chars()
+            .map(|character| if character.is_alphanumeric() || "-_.".contains(character) { character } else { '_' })
+            .collect();
+        match FileHandler::new(self.dir.join(format!("{}.log", name))) {
+            Ok(file) => Some(Arc::clone(files.entry(subtree.to_string()).or_insert(Arc::new(file)))),
+            Err(err) => {
+                self.id.record_failure(&self.describe(), err);
+                None
+            }
+        }
+    }
+    fn files(&self) -> Vec<Arc<FileHandler>> {
+        self.files.read().expect("Subtree files handler is poisoned").values().cloned().collect()
+    }
+}
+impl Handler for SubtreeFilesHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let subtree = record.logger.split("::").next().filter(|name| !name.is_empty()).unwrap_or("root");
+        match self.file(subtree) {
+            Some(file) => file.handle(record),
+            None => self.id.record_dropped(&self.describe(), record),
+        }
+    }
+    fn reopen(&self) {
+        for file in self.files() {
+            file.reopen();
+        }
+    }
+    fn close(&self) {
+        for file in self.files() {
+            file.close();
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
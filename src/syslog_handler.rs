@@ -0,0 +1,242 @@
+use crate::formatter::{civil_from_days, format_time};
+use crate::stats::HandlerId;
+use crate::{Handler, HandlerHealth, Level, LogLevel, Record, TcpHandler};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The syslog facility `user`, used by default.
+const USER: u8 = 1;
+/// The enterprise number of the structured data element carrying the fields (the one reserved for documentation).
+const FIELDS_ID: &str = "fields@32473";
+
+/// The message format of a [SyslogHandler](SyslogHandler).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyslogFormat {
+    /// [RFC 5424](https://www.rfc-editor.org/rfc/rfc5424), e.g. `<14>1 2024-05-01T12:30:00.000Z host my_app 4242 foo - Hello World`.
+    /// Fields are sent as structured data. This is the default.
+    #[default]
+    Rfc5424,
+    /// The legacy BSD format of [RFC 3164](https://www.rfc-editor.org/rfc/rfc3164), e.g. `<14>May  1 12:30:00 host my_app[4242]: Hello World`,
+    /// for older syslog daemons and network appliances that reject RFC 5424. Fields aren't sent.
+    Rfc3164,
+}
+
+enum Transport {
+    Udp(UdpSocket, SocketAddr),
+    Tcp(TcpHandler),
+    #[cfg(unix)]
+    Unix(UnixDatagram, PathBuf),
+}
+
+/// A [Handler](Handler) sending records to a syslog daemon, either over the network or to the local socket.
+///
+/// The level of a record is mapped to the syslog severity: `DEBUG` to debug, `INFO` to informational, `SUCCESS` to notice,
+/// `WARN` to warning, `ERROR` to error, `CRITICAL` to critical and `FATAL` to alert.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, SyslogFormat, SyslogHandler};
+/// use std::net::UdpSocket;
+///
+/// let daemon = UdpSocket::bind("127.0.0.1:0").unwrap();
+/// let handler = SyslogHandler::udp(daemon.local_addr().unwrap()).unwrap()
+///     .with_app_name("my_app")
+///     .with_format(SyslogFormat::Rfc3164);
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(handler);
+/// logger.warn("Disk almost full");
+///
+/// let mut buffer = [0; 1024];
+/// let length = daemon.recv(&mut buffer).unwrap();
+/// let message = String::from_utf8_lossy(&buffer[..length]);
+/// assert!(message.starts_with("<12>"));
+/// assert!(message.ends_with(&format!(" my_app[{}]: Disk almost full", std::process::id())));
+/// ```
+pub struct SyslogHandler {
+    transport: Transport,
+    format: SyslogFormat,
+    facility: u8,
+    app_name: String,
+    id: HandlerId,
+}
+impl SyslogHandler {
+    fn new(transport: Transport) -> Self {
+        let app_name = std::env::current_exe().ok()
+            .and_then(|exe| exe.file_stem().map(|name| name.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "rust".to_string());
+        Self { transport, format: SyslogFormat::default(), facility: USER, app_name, id: HandlerId::new() }
+    }
+    /// Send records as UDP datagrams, the classic transport of syslog.
+    ///
+    /// # Arguments
+    ///
+    /// * `address`: The address of the syslog daemon, usually on port 514.
+    ///
+    /// returns: Result<SyslogHandler, Error> An error if the address can't be resolved.
+    pub fn udp(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let address = address.to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing"))?;
+        let local: SocketAddr = if address.is_ipv4() { ([0, 0, 0, 0], 0).into() } else { ([0; 16], 0).into() };
+        Ok(Self::new(Transport::Udp(UdpSocket::bind(local)?, address)))
+    }
+    /// Send records over TCP, one per line. The connection is handled by a [TcpHandler](TcpHandler),
+    /// which can be configured with timeouts, backoff and a spool before it is passed in.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection`: The handler connected to the syslog daemon.
+    ///
+    /// returns: SyslogHandler
+    pub fn tcp(connection: TcpHandler) -> Self {
+        Self::new(Transport::Tcp(connection))
+    }
+    /// Send records to the local syslog daemon through its socket, usually `/dev/log`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path of the socket.
+    ///
+    /// returns: Result<SyslogHandler, Error>
+    #[cfg(unix)]
+    pub fn unix(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(Transport::Unix(UnixDatagram::unbound()?, path.as_ref().to_path_buf())))
+    }
+    /// Set the message format. Defaults to [RFC 5424](SyslogFormat::Rfc5424).
+    pub fn with_format(mut self, format: SyslogFormat) -> Self {
+        self.format = format;
+        self
+    }
+    /// Set the facility, e.g. 16 for `local0`. Defaults to 1 (`user`).
+    pub fn with_facility(mut self, facility: u8) -> Self {
+        self.facility = facility.min(23);
+        self
+    }
+    /// Set the name of the application (the tag in RFC 3164). Defaults to the name of the executable.
+    pub fn with_app_name(mut self, app_name: impl ToString) -> Self {
+        self.app_name = app_name.to_string();
+        self
+    }
+    /// Render a record as a syslog message in the configured format.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: The record to render.
+    ///
+    /// returns: String
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{fields, Level, Record, SyslogHandler};
+    /// let handler = SyslogHandler::udp("127.0.0.1:514").unwrap().with_app_name("my_app");
+    /// let mut record = Record::new(Level::INFO, "Hello World".to_string(), "foo".to_string());
+    /// record.fields = fields! { user = "jane" };
+    /// let message = handler.render(&record);
+    /// assert!(message.starts_with("<14>1 "));
+    /// assert!(message.ends_with(&format!(" my_app {} foo [fields@32473 user=\"jane\"] Hello World", std::process::id())));
+    /// ```
+    pub fn render(&self, record: &Record) -> String {
+        let priority = self.facility as u32 * 8 + severity(record.level) as u32;
+        let hostname = crate::system::hostname();
+        match self.format {
+            SyslogFormat::Rfc5424 => {
+                let logger = if record.logger.is_empty() { "-".to_string() } else { printable(&record.logger, 32) };
+                let data = if record.fields.is_empty() {
+                    "-".to_string()
+                } else {
+                    let params: Vec<String> = record.fields.iter()
+                        .map(|(key, value)| format!("{}=\"{}\"", printable(key, 32), escape_param(&value.to_string())))
+                        .collect();
+                    format!("[{} {}]", FIELDS_ID, params.join(" "))
+                };
+                format!(
+                    "<{}>1 {} {} {} {} {} {} {}",
+                    priority, format_time(record.time), printable(hostname, 255), printable(&self.app_name, 48),
+                    std::process::id(), logger, data, record.message,
+                )
+            }
+            SyslogFormat::Rfc3164 => format!(
+                "<{}>{} {} {}[{}]: {}",
+                priority, bsd_time(record.time), hostname, self.app_name, std::process::id(), record.message,
+            ),
+        }
+    }
+}
+impl Handler for SyslogHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let message = self.render(record);
+        let sent = match &self.transport {
+            Transport::Udp(socket, address) => socket.send_to(message.as_bytes(), address).map(|_| ()),
+            Transport::Tcp(connection) => {
+                let mut record = record.clone();
+                record.formatted = Some(message);
+                return connection.handle(&record);
+            }
+            #[cfg(unix)]
+            Transport::Unix(socket, path) => socket.send_to(message.as_bytes(), path).map(|_| ()),
+        };
+        if let Err(err) = sent {
+            self.id.record_failure(&self.describe(), err);
+        }
+    }
+    fn reopen(&self) {
+        if let Transport::Tcp(connection) = &self.transport {
+            connection.reopen();
+        }
+    }
+    fn close(&self) {
+        if let Transport::Tcp(connection) = &self.transport {
+            connection.close();
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
+
+/// The syslog severity of a level.
+fn severity(level: LogLevel) -> u8 {
+    match level {
+        level if level >= Level::FATAL => 1,
+        level if level >= Level::CRITICAL => 2,
+        level if level >= Level::ERROR => 3,
+        level if level >= Level::WARN => 4,
+        level if level >= Level::SUCCESS => 5,
+        level if level >= Level::INFO => 6,
+        _ => 7,
+    }
+}
+/// A time in the format of RFC 3164 (UTC), e.g. `May  1 12:30:00`.
+fn bsd_time(time: SystemTime) -> String {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (_, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let seconds_of_day = seconds % 86_400;
+    format!(
+        "{} {:>2} {:02}:{:02}:{:02}",
+        MONTHS[month as usize - 1], day, seconds_of_day / 3600, seconds_of_day % 3600 / 60, seconds_of_day % 60,
+    )
+}
+/// A header field or parameter name of RFC 5424: printable ASCII without spaces and the separators of structured data,
+/// limited in length. Empty values become `-`.
+fn printable(text: &str, max_len: usize) -> String {
+    let text: String = text.chars()
+        .filter(|character| character.is_ascii_graphic() && !"=]\"".contains(*character))
+        .take(max_len)
+        .collect();
+    if text.is_empty() { "-".to_string() } else { text }
+}
+/// Escape a structured data parameter value of RFC 5424.
+fn escape_param(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace(']', "\\]")
+}
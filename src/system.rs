@@ -0,0 +1,14 @@
+use std::sync::OnceLock;
+
+/// The name of this machine, read once from the environment or `/etc/hostname`. Falls back to `localhost`.
+pub(crate) fn hostname() -> &'static str {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+    HOSTNAME.get_or_init(|| {
+        std::env::var("HOSTNAME").ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .map(|hostname| hostname.trim().to_string())
+            .filter(|hostname| !hostname.is_empty())
+            .unwrap_or_else(|| "localhost".to_string())
+    })
+}
@@ -0,0 +1,397 @@
+use crate::stats::HandlerId;
+use crate::{DefaultFormatter, Formatter, Handler, HandlerHealth, Level, LogLevel, Record};
+use std::collections::hash_map::RandomState;
+use std::fs::{self, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+enum Stream {
+    Plain(TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Plain(stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => stream.write(buf),
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Plain(stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+struct Connection {
+    stream: Option<Stream>,
+    failures: u32,
+    retry_at: Option<Instant>,
+    /// Whether the spool may contain records.
+    spooled: bool,
+    /// How many bytes at the start of the spool have been sent already.
+    spool_sent: u64,
+}
+
+/// How many bytes of spooled records are sent at most along with one record, so draining a large spool doesn't stall the thread logging.
+const SPOOL_BATCH_BYTES: usize = 64 * 1024;
+
+/// Where records that couldn't be delivered are kept until the collector is reachable again.
+struct Spool {
+    path: PathBuf,
+    max_bytes: u64,
+    level: LogLevel,
+}
+impl Spool {
+    /// Read whole lines from `offset` on, until at least [SPOOL_BATCH_BYTES] are read or the end of the spool is reached.
+    ///
+    /// returns: Result<(Vec<u8>, bool), Error> The lines, and whether they reach the end of the spool.
+    fn read_batch(&self, offset: u64) -> io::Result<(Vec<u8>, bool)> {
+        let mut file = match fs::File::open(&self.path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), true)),
+            file => file?,
+        };
+        file.seek(SeekFrom::Start(offset))?;
+        let mut reader = BufReader::new(file);
+        let mut batch = Vec::new();
+        while batch.len() < SPOOL_BATCH_BYTES {
+            if reader.read_until(b'\n', &mut batch)? == 0 {
+                return Ok((batch, true));
+            }
+        }
+        let end = reader.fill_buf()?.is_empty();
+        Ok((batch, end))
+    }
+    /// Append a line, unless the records not sent yet would grow beyond the maximum size.
+    ///
+    /// # Arguments
+    ///
+    /// * `line`: The line to append.
+    /// * `sent`: How many bytes at the start of the spool have been sent already.
+    ///
+    /// returns: Result<(), Error>
+    fn append(&self, line: &[u8], sent: u64) -> io::Result<()> {
+        let size = fs::metadata(&self.path).map(|metadata| metadata.len()).unwrap_or(0);
+        if size.saturating_sub(sent) + line.len() as u64 > self.max_bytes {
+            return Err(io::Error::new(io::ErrorKind::StorageFull, "spool is full"));
+        }
+        OpenOptions::new().create(true).append(true).open(&self.path)?.write_all(line)
+    }
+}
+
+/// A [Handler](Handler) sending records as lines of text to a TCP collector, e.g. a log shipper listening on a socket.
+///
+/// Connecting and writing are bounded by timeouts, so a slow or unreachable collector can't stall logging indefinitely.
+/// If a write fails, the handler reconnects and retries once. If that fails as well, it backs off exponentially (with jitter)
+/// before connecting again. Records logged while backing off are dropped and counted in [stats](crate::stats),
+/// unless a [spool](TcpHandler::with_spool) keeps them on disk until the collector is reachable again.
+/// With the `tls` feature, the connection can be encrypted with `with_tls`.
+///
+/// # Examples
+///
+/// ```
+/// use logging::{Level, Logger, TcpHandler};
+/// use std::io::{BufRead, BufReader};
+/// use std::net::TcpListener;
+/// use std::time::Duration;
+///
+/// let collector = TcpListener::bind("127.0.0.1:0").unwrap();
+/// let handler = TcpHandler::new(collector.local_addr().unwrap()).unwrap()
+///     .with_connect_timeout(Duration::from_secs(1))
+///     .with_write_timeout(Duration::from_secs(1))
+///     .with_backoff(Duration::from_millis(100), Duration::from_secs(10));
+/// let logger = Logger::new("foo");
+/// logger.set_level(Level::ALL);
+/// logger.add_handler(handler);
+/// logger.info("Hello World");
+///
+/// let (connection, _) = collector.accept().unwrap();
+/// let mut line = String::new();
+/// BufReader::new(connection).read_line(&mut line).unwrap();
+/// assert_eq!(line, "INFO (foo): Hello World\n");
+/// ```
+pub struct TcpHandler {
+    addresses: Vec<SocketAddr>,
+    connect_timeout: Duration,
+    write_timeout: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    spool: Option<Spool>,
+    #[cfg(feature = "tls")]
+    tls: Option<(std::sync::Arc<rustls::ClientConfig>, rustls::pki_types::ServerName<'static>)>,
+    connection: Mutex<Connection>,
+    id: HandlerId,
+}
+impl TcpHandler {
+    /// Create a handler sending to the given address. The connection is established when the first record is logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `address`: The address of the collector, e.g. `"localhost:5170"`.
+    ///
+    /// returns: Result<TcpHandler, Error> An error if the address can't be resolved.
+    pub fn new(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        if addresses.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing"));
+        }
+        Ok(Self {
+            addresses,
+            connect_timeout: Duration::from_secs(5),
+            write_timeout: Duration::from_secs(5),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(60),
+            spool: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+            connection: Mutex::new(Connection { stream: None, failures: 0, retry_at: None, spooled: false, spool_sent: 0 }),
+            id: HandlerId::new(),
+        })
+    }
+    /// Set how long connecting may take per address. Defaults to 5 seconds.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+    /// Set how long writing a record may block. Defaults to 5 seconds.
+    pub fn with_write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = timeout;
+        self
+    }
+    /// Set how long to wait before reconnecting after the first failure, and the limit the wait doubles up to after further failures.
+    /// Defaults to 500 milliseconds and 60 seconds. Each wait is randomly shortened by up to half, so many clients don't reconnect at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial`: The wait after the first failure.
+    /// * `max`: The longest wait.
+    ///
+    /// returns: TcpHandler
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+    /// Keep WARN and more severe records that can't be delivered in a file, and send them before the next record once the collector is reachable again.
+    /// Spooled records are removed only after they've been sent, so they may be delivered twice but aren't lost, even across restarts.
+    /// When the spool is full, further records are dropped.
+    ///
+    /// A large spool is sent in batches of about 64 KiB along with the next records, so the thread logging isn't stalled by sending all of it at once.
+    /// Until it is drained, new records of any level are queued behind it in the spool, so the collector receives all records in order.
+    /// If the spool can't be read, it is kept and sending is tried again with the next record.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to keep the records in.
+    /// * `max_bytes`: How large the file may grow.
+    ///
+    /// returns: TcpHandler
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Level, Logger, TcpHandler};
+    /// use std::net::TcpListener;
+    /// let spool = std::env::temp_dir().join(format!("logging-spool-{}.log", std::process::id()));
+    /// // nothing is listening on this address anymore
+    /// let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    /// let handler = TcpHandler::new(address).unwrap().with_spool(&spool, 1024 * 1024);
+    /// let logger = Logger::new("foo");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(handler);
+    /// logger.info("Hello World");
+    /// logger.error("Something failed");
+    ///
+    /// assert_eq!(std::fs::read_to_string(&spool).unwrap(), "ERROR (foo): Something failed\n");
+    ///
+    /// // once the collector is back, a large spool is drained before any new record is sent
+    /// std::fs::write(&spool, "ERROR (foo): Something failed\n".repeat(5000)).unwrap();
+    /// let collector = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let address = collector.local_addr().unwrap();
+    /// let received = std::thread::spawn(move || {
+    ///     let lines = std::io::BufRead::lines(std::io::BufReader::new(collector.accept().unwrap().0));
+    ///     lines.take(5010).map(Result::unwrap).collect::<Vec<String>>()
+    /// });
+    /// let logger = Logger::new("bar");
+    /// logger.set_level(Level::ALL);
+    /// logger.add_handler(TcpHandler::new(address).unwrap().with_spool(&spool, 1024 * 1024));
+    /// for number in 0..10 {
+    ///     logger.info(format!("live {}", number));
+    /// }
+    /// let received = received.join().unwrap();
+    /// assert!(received[..5000].iter().all(|line| line == "ERROR (foo): Something failed"));
+    /// assert_eq!(received[5000], "INFO (bar): live 0");
+    /// assert!(!spool.exists());
+    /// ```
+    pub fn with_spool(mut self, path: impl AsRef<Path>, max_bytes: u64) -> Self {
+        let path = path.as_ref().to_path_buf();
+        // records spooled by an earlier run are sent as well
+        self.connection.get_mut().expect("TCP handler is poisoned").spooled = path.exists();
+        self.spool = Some(Spool { path, max_bytes, level: Level::WARN });
+        self
+    }
+    /// Set the minimum level of records kept in the [spool](TcpHandler::with_spool). Defaults to [WARN](Level::WARN).
+    /// Has no effect without a spool.
+    pub fn with_spool_level(mut self, level: LogLevel) -> Self {
+        if let Some(spool) = &mut self.spool {
+            spool.level = level;
+        }
+        self
+    }
+    /// Encrypt the connection with TLS. The write timeout also bounds the handshake.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: How to verify the server.
+    /// * `server_name`: The name the certificate of the server has to be valid for, e.g. `logs.example.com`.
+    ///
+    /// returns: Result<TcpHandler, Error> An error if the configuration or the name is invalid.
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, config: crate::TlsConfig, server_name: &str) -> io::Result<Self> {
+        self.tls = Some((config.client_config()?, crate::tls::server_name(server_name)?));
+        Ok(self)
+    }
+    fn connect(&self) -> io::Result<Stream> {
+        let mut last_error = None;
+        for address in &self.addresses {
+            match TcpStream::connect_timeout(address, self.connect_timeout) {
+                Ok(stream) => {
+                    stream.set_write_timeout(Some(self.write_timeout))?;
+                    stream.set_nodelay(true)?;
+                    #[cfg(feature = "tls")]
+                    if let Some((config, server_name)) = &self.tls {
+                        // the handshake reads from the socket as well
+                        stream.set_read_timeout(Some(self.write_timeout))?;
+                        let connection = rustls::ClientConnection::new(std::sync::Arc::clone(config), server_name.clone())
+                            .map_err(io::Error::other)?;
+                        return Ok(Stream::Tls(Box::new(rustls::StreamOwned::new(connection, stream))));
+                    }
+                    return Ok(Stream::Plain(stream));
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| io::ErrorKind::NotConnected.into()))
+    }
+    fn send(&self, connection: &mut Connection, line: &[u8]) -> io::Result<()> {
+        let stream = match &mut connection.stream {
+            Some(stream) => stream,
+            None => connection.stream.insert(self.connect()?),
+        };
+        stream.write_all(line)
+    }
+    /// Send bytes, reconnecting once if the connection broke, and back off if that fails as well.
+    ///
+    /// returns: bool, whether the bytes were sent
+    fn deliver(&self, connection: &mut Connection, payload: &[u8]) -> bool {
+        let had_connection = connection.stream.is_some();
+        let mut sent = self.send(connection, payload);
+        if sent.is_err() && had_connection {
+            // the collector may have closed an idle connection, so try once more with a fresh one
+            connection.stream = None;
+            sent = self.send(connection, payload);
+        }
+        match sent {
+            Ok(()) => {
+                connection.failures = 0;
+                connection.retry_at = None;
+                true
+            }
+            Err(err) => {
+                connection.stream = None;
+                connection.failures += 1;
+                connection.retry_at = Some(Instant::now() + self.backoff(connection.failures));
+                self.id.record_failure(&self.describe(), err);
+                false
+            }
+        }
+    }
+    /// Queue a record behind the spooled ones and send the next batch of the spool, so the collector receives all records in order.
+    fn deliver_spooled(&self, connection: &mut Connection, spool: &Spool, record: &Record, line: &str) {
+        if let Err(err) = spool.append(line.as_bytes(), connection.spool_sent) {
+            self.id.record_failure(&self.describe(), err);
+            self.id.record_dropped(&self.describe(), record);
+        }
+        let (batch, drained) = match spool.read_batch(connection.spool_sent) {
+            Ok(batch) => batch,
+            Err(err) => return self.id.record_failure(&self.describe(), err),
+        };
+        if !self.deliver(connection, &batch) {
+            return;
+        }
+        connection.spool_sent += batch.len() as u64;
+        // only remove the spool once all of it has been written
+        if drained {
+            connection.spooled = false;
+            connection.spool_sent = 0;
+            if let Err(err) = fs::remove_file(&spool.path) {
+                self.id.record_failure(&self.describe(), err);
+            }
+        }
+    }
+    /// Spool a record that can't be sent right now, or drop it.
+    fn undeliverable(&self, connection: &mut Connection, record: &Record, line: &str) {
+        if let Some(spool) = self.spool.as_ref().filter(|spool| record.level >= spool.level) {
+            match spool.append(line.as_bytes(), connection.spool_sent) {
+                Ok(()) => {
+                    connection.spooled = true;
+                    return;
+                }
+                Err(err) => self.id.record_failure(&self.describe(), err),
+            }
+        }
+        self.id.record_dropped(&self.describe(), record);
+    }
+    fn backoff(&self, failures: u32) -> Duration {
+        let backoff = self.initial_backoff.saturating_mul(2u32.saturating_pow(failures.saturating_sub(1))).min(self.max_backoff);
+        let jitter = RandomState::new().build_hasher().finish() % 1000;
+        backoff.mul_f64(1.0 - jitter as f64 / 2000.0)
+    }
+}
+impl Handler for TcpHandler {
+    fn log(&self, level: LogLevel, message: String, logger: String) {
+        self.handle(&Record::new(level, message, logger))
+    }
+    fn handle(&self, record: &Record) {
+        let mut line = record.formatted.clone().unwrap_or_else(|| DefaultFormatter.format(record));
+        line.push('\n');
+        let mut connection = self.connection.lock().expect("TCP handler is poisoned");
+        if connection.retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+            self.undeliverable(&mut connection, record, &line);
+            return;
+        }
+        // spooled records go first, so the collector receives them in order
+        if let Some(spool) = self.spool.as_ref().filter(|_| connection.spooled) {
+            return self.deliver_spooled(&mut connection, spool, record, &line);
+        }
+        if !self.deliver(&mut connection, line.as_bytes()) {
+            self.undeliverable(&mut connection, record, &line);
+        }
+    }
+    fn reopen(&self) {
+        let mut connection = self.connection.lock().expect("TCP handler is poisoned");
+        connection.stream = None;
+        connection.retry_at = None;
+    }
+    fn close(&self) {
+        let mut connection = self.connection.lock().expect("TCP handler is poisoned");
+        if let Some(mut stream) = connection.stream.take() {
+            #[cfg(feature = "tls")]
+            if let Stream::Tls(stream) = &mut stream {
+                stream.conn.send_close_notify();
+            }
+            let _ = stream.flush();
+        }
+    }
+    fn health(&self) -> Option<HandlerHealth> {
+        Some(self.id.health(|| self.describe()))
+    }
+}
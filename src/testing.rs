@@ -0,0 +1,80 @@
+//! Isolated logging for tests, as set up by the `#[logging::test]` attribute (with the `macros` feature).
+//!
+//! Loggers are global, so tests running in parallel normally see each other's records and level changes.
+//! While a [capture](capture) is active on a thread, records logged on that thread are collected for that thread only,
+//! and all loggers use the level of the capture on that thread instead of their own. Handlers still receive the records as usual.
+//! Records logged on other threads (including ones spawned by the test) are not captured.
+
+use crate::{DefaultFormatter, Formatter, LogLevel, Record};
+use std::cell::RefCell;
+
+struct Capture {
+    level: LogLevel,
+    records: Vec<Record>,
+}
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Capture>> = const { RefCell::new(None) };
+}
+
+/// Ends a capture when dropped, restoring the capture that was active before (if any).
+/// If the thread is panicking, e.g. because an assertion of the test failed, the captured records are printed first.
+#[must_use = "the capture ends when it is dropped"]
+pub struct CaptureGuard {
+    previous: Option<Capture>,
+}
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        let capture = CAPTURE.with(|capture| capture.replace(previous));
+        if let Some(capture) = capture.filter(|_| std::thread::panicking()) {
+            eprintln!("captured log records:");
+            for record in &capture.records {
+                eprintln!("{}", DefaultFormatter.format(record));
+            }
+        }
+    }
+}
+
+/// Start capturing the records logged on the current thread, with all loggers using the given level on this thread,
+/// until the returned guard is dropped.
+///
+/// # Arguments
+///
+/// * `level`: The level all loggers use on this thread during the capture.
+///
+/// returns: CaptureGuard
+///
+/// # Examples
+///
+/// ```
+/// use logging::{testing, Level, Logger};
+/// let _capture = testing::capture(Level::ALL);
+/// Logger::new("app").debug("connecting");
+/// assert_eq!(testing::captured()[0].message, "connecting");
+/// ```
+pub fn capture(level: LogLevel) -> CaptureGuard {
+    let previous = CAPTURE.with(|capture| capture.replace(Some(Capture { level, records: Vec::new() })));
+    CaptureGuard { previous }
+}
+
+/// The records captured on the current thread so far, oldest first. Empty if no capture is active.
+///
+/// returns: Vec<Record>
+pub fn captured() -> Vec<Record> {
+    CAPTURE.with_borrow(|capture| capture.as_ref().map(|capture| capture.records.clone()).unwrap_or_default())
+}
+
+/// The level of the capture active on the current thread, if any.
+pub(crate) fn level() -> Option<LogLevel> {
+    CAPTURE.with_borrow(|capture| capture.as_ref().map(|capture| capture.level))
+}
+
+/// Add a record to the capture active on the current thread, if any.
+pub(crate) fn record(record: &Record) {
+    CAPTURE.with_borrow_mut(|capture| {
+        if let Some(capture) = capture {
+            capture.records.push(record.clone());
+        }
+    })
+}
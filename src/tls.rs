@@ -0,0 +1,131 @@
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// How network handlers like the [TcpHandler](crate::TcpHandler) secure their connection with TLS, so logs containing sensitive data aren't sent in cleartext.
+///
+/// By default, the certificate of the server is verified against the Mozilla root certificates.
+/// Servers with certificates of a private CA can be trusted with [with_ca_file](TlsConfig::with_ca_file).
+///
+/// # Examples
+///
+/// ```
+/// use logging::{TcpHandler, TlsConfig};
+/// // connects when the first record is logged
+/// let handler = TcpHandler::new("127.0.0.1:6514").unwrap()
+///     .with_tls(TlsConfig::new(), "logs.example.com")
+///     .unwrap();
+/// assert!(TlsConfig::new().with_ca_file("/does/not/exist.pem").is_err());
+/// ```
+#[derive(Clone)]
+pub struct TlsConfig {
+    roots: RootCertStore,
+    verify: bool,
+    client_auth: Option<(Vec<CertificateDer<'static>>, Arc<PrivateKeyDer<'static>>)>,
+}
+impl TlsConfig {
+    /// Trust the Mozilla root certificates, like browsers do.
+    pub fn new() -> Self {
+        Self {
+            roots: RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() },
+            verify: true,
+            client_auth: None,
+        }
+    }
+    /// Additionally trust the certificates in a PEM file, e.g. the CA of a company or the self-signed certificate of a collector.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The PEM file with one or more certificates.
+    ///
+    /// returns: Result<TlsConfig, Error> An error if the file can't be read or doesn't contain valid certificates.
+    pub fn with_ca_file(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        let certificates = CertificateDer::pem_file_iter(path).map_err(invalid)?;
+        for certificate in certificates {
+            self.roots.add(certificate.map_err(invalid)?).map_err(invalid)?;
+        }
+        Ok(self)
+    }
+    /// Authenticate to the server with a client certificate (mutual TLS).
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate`: The PEM file with the certificate chain of the client.
+    /// * `key`: The PEM file with the private key of the client.
+    ///
+    /// returns: Result<TlsConfig, Error> An error if the files can't be read or are invalid.
+    pub fn with_client_cert(mut self, certificate: impl AsRef<Path>, key: impl AsRef<Path>) -> io::Result<Self> {
+        let chain = CertificateDer::pem_file_iter(certificate).map_err(invalid)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid)?;
+        let key = PrivateKeyDer::from_pem_file(key).map_err(invalid)?;
+        self.client_auth = Some((chain, Arc::new(key)));
+        Ok(self)
+    }
+    /// Don't verify the certificate of the server. The connection is still encrypted, but anyone able to intercept it
+    /// can pretend to be the server and read the logs, so this should only be used for testing.
+    pub fn danger_accept_invalid_certs(mut self) -> Self {
+        self.verify = false;
+        self
+    }
+    pub(crate) fn client_config(&self) -> io::Result<Arc<ClientConfig>> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .map_err(invalid)?;
+        let builder = if self.verify {
+            builder.with_root_certificates(self.roots.clone())
+        } else {
+            builder.dangerous().with_custom_certificate_verifier(Arc::new(NoVerification(provider.signature_verification_algorithms)))
+        };
+        let config = match &self.client_auth {
+            Some((chain, key)) => builder.with_client_auth_cert(chain.clone(), key.clone_key()).map_err(invalid)?,
+            None => builder.with_no_client_auth(),
+        };
+        Ok(Arc::new(config))
+    }
+}
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check the name of a server for use with TLS.
+pub(crate) fn server_name(name: &str) -> io::Result<ServerName<'static>> {
+    ServerName::try_from(name.to_string()).map_err(invalid)
+}
+
+fn invalid(error: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, error)
+}
+
+/// Accepts any certificate, but still checks that the server owns it.
+#[derive(Debug)]
+struct NoVerification(WebPkiSupportedAlgorithms);
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+    fn verify_tls12_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0)
+    }
+    fn verify_tls13_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0)
+    }
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_schemes()
+    }
+}